@@ -0,0 +1,161 @@
+use std::{
+	hint::black_box,
+	time::{Duration, Instant},
+};
+
+use calcagebra_lib::{
+	interpreter::Interpreter,
+	lexer::Lexer,
+	matrix::{Complex, Matrix},
+	parser::Parser,
+};
+use rust_decimal::Decimal;
+
+const SIZE: usize = 200;
+const RUNS: u32 = 5;
+
+/// Deterministic matrix with small integer entries
+fn matrix(seed: usize, imaginary: bool) -> Matrix {
+	Matrix::from_fn(SIZE, SIZE, |i, j| {
+		let value = Decimal::from(((i * 31 + j * 17 + seed) % 19) as i64 - 9);
+
+		if imaginary {
+			Complex::new(value, Decimal::ONE)
+		} else {
+			Complex::real(value)
+		}
+	})
+}
+
+/// Element of matrices as they were stored before being flattened into complex
+/// arrays, a nested `Vec<Vec<Data>>` whose every operation matched on the kind
+/// of value and could fail
+#[derive(Clone)]
+enum Data {
+	Number(Decimal, Decimal),
+	Matrix(Vec<Vec<Data>>),
+}
+
+fn add(lhd: &Data, rhd: &Data) -> Result<Data, String> {
+	match (lhd, rhd) {
+		(Data::Number(a, b), Data::Number(c, d)) => Ok(Data::Number(a + c, b + d)),
+		(Data::Matrix(a), Data::Matrix(b)) => Ok(Data::Matrix(
+			a.iter()
+				.zip(b)
+				.map(|(a, b)| a.iter().zip(b).map(|(a, b)| add(a, b)).collect())
+				.collect::<Result<_, _>>()?,
+		)),
+		_ => Err("expected numbers or matrices".to_string()),
+	}
+}
+
+fn mul(lhd: &Data, rhd: &Data) -> Result<Data, String> {
+	match (lhd, rhd) {
+		(Data::Number(a, b), Data::Number(c, d)) => Ok(Data::Number(a * c - b * d, a * d + b * c)),
+		(Data::Matrix(a), Data::Matrix(b)) => {
+			let mut r = vec![];
+
+			for row in a {
+				let mut col = vec![];
+
+				for c in 0..b[0].len() {
+					let mut sum = Data::Number(Decimal::ZERO, Decimal::ZERO);
+
+					for (number, b_row) in row.iter().zip(b) {
+						sum = add(&sum, &mul(number, &b_row[c])?)?;
+					}
+
+					col.push(sum);
+				}
+
+				r.push(col);
+			}
+
+			Ok(Data::Matrix(r))
+		}
+		_ => Err("expected numbers or matrices".to_string()),
+	}
+}
+
+fn nested(m: &Matrix) -> Data {
+	Data::Matrix(
+		m.iter_rows()
+			.map(|row| row.iter().map(|z| Data::Number(z.re, z.im)).collect())
+			.collect(),
+	)
+}
+
+fn time(name: &str, mut f: impl FnMut()) {
+	let mut total = Duration::ZERO;
+
+	for _ in 0..RUNS {
+		let start = Instant::now();
+
+		f();
+
+		total += start.elapsed();
+	}
+
+	println!("{name:<40} {:?}", total / RUNS);
+}
+
+fn main() {
+	let (a, b) = (matrix(1, false), matrix(2, false));
+	let (c, d) = (matrix(1, true), matrix(2, true));
+
+	let (old_a, old_b) = (nested(&a), nested(&b));
+	let (old_c, old_d) = (nested(&c), nested(&d));
+
+	time("baseline real 200x200 multiply", || {
+		black_box(mul(&old_a, &old_b).unwrap());
+	});
+
+	time("baseline complex 200x200 multiply", || {
+		black_box(mul(&old_c, &old_d).unwrap());
+	});
+
+	time("baseline real 200x200 add", || {
+		black_box(add(&old_a, &old_b).unwrap());
+	});
+
+	time("real 200x200 multiply", || {
+		black_box(a.matmul(&b));
+	});
+
+	time("complex 200x200 multiply", || {
+		black_box(c.matmul(&d));
+	});
+
+	time("real 200x200 add", || {
+		black_box(a.zip_map(&b, |x, y| x + y));
+	});
+
+	let literal = |m: &Matrix| {
+		format!(
+			"[{}]",
+			m.iter_rows()
+				.map(|row| {
+					row
+						.iter()
+						.map(|z| z.re.to_string())
+						.collect::<Vec<String>>()
+						.join(", ")
+				})
+				.collect::<Vec<String>>()
+				.join("; ")
+		)
+	};
+
+	let source = format!(
+		"let A = {}\nlet B = {}\nlet C = A * B\nlet D = A + B",
+		literal(&a),
+		literal(&b)
+	);
+
+	time("script 200x200 multiply and add", || {
+		let tokens = Lexer::new(&source).tokens();
+		let ast = Parser::new(&tokens).ast().unwrap();
+
+		Interpreter::new().interpret(ast).unwrap();
+	});
+}
@@ -0,0 +1,516 @@
+use rust_decimal::Decimal;
+
+use crate::{
+	errors::Error,
+	expr::{Expression, Operands},
+	simplify::{
+		LARGEST_POWER, Ratio, base_exponent, build_product, exponent_order, factors, gcd, map, negate,
+		number, power, powers, product, ratio, sum, terms,
+	},
+	token::Token,
+};
+
+/// Largest number whose divisors are tried as roots and values of factors
+pub(crate) const LARGEST_DIVIDED: Decimal = Decimal::from_parts(3_567_587_328, 232, 0, false, 0);
+
+/// Highest degree a polynomial can have to be written out by its coefficients
+const LARGEST_DEGREE: i64 = 1000;
+
+/// Most candidate factors Kronecker's method tries before giving up on a
+/// polynomial as irreducible
+const KRONECKER_CANDIDATES: usize = 100_000;
+
+#[inline(always)]
+fn is_sum(expr: &Expression) -> bool {
+	matches!(expr, Expression::Binary(_, Token::Add | Token::Sub, _, _))
+}
+
+fn expand(expr: &Expression) -> Expression {
+	let expr = map(expr, expand);
+
+	if !matches!(
+		expr,
+		Expression::Binary(_, Token::Mul | Token::Div | Token::Pow, _, _)
+	) {
+		return expr;
+	}
+
+	let mut expanded = vec![number(Decimal::ONE)];
+
+	for factor in factors(expr) {
+		let (base, exponent) = base_exponent(factor.clone());
+
+		let times = match ratio(&exponent).and_then(Ratio::integer) {
+			Some(n) if is_sum(&base) && (1..=LARGEST_POWER).contains(&n) => n,
+			_ => {
+				expanded = expanded
+					.into_iter()
+					.map(|term| product(vec![term, factor.clone()]))
+					.collect();
+				continue;
+			}
+		};
+
+		for _ in 0..times {
+			expanded = terms(sum(
+				expanded
+					.iter()
+					.flat_map(|term| {
+						terms(base.clone())
+							.into_iter()
+							.map(|other| product(vec![term.clone(), other]))
+					})
+					.collect(),
+			));
+		}
+	}
+
+	sum(expanded)
+}
+
+/// Splits the power of `x` out of a term, leaving its coefficient
+#[inline(always)]
+fn split(term: Expression, x: &str) -> (Expression, Expression) {
+	let (coefficient, mut powers) = powers(vec![term]);
+
+	let exponent = match powers
+		.iter()
+		.position(|(base, _)| *base == Expression::Identifier(x.to_string()))
+	{
+		Some(i) => powers.remove(i).1,
+		None => number(Decimal::ZERO),
+	};
+
+	(exponent, build_product(coefficient, powers))
+}
+
+/// Exact fraction of integers equal to a ratio, whose parts may be decimals
+#[inline(always)]
+fn fraction(n: Ratio) -> Option<Ratio> {
+	let integer = |d: Decimal| {
+		(
+			Decimal::from_i128_with_scale(d.mantissa(), 0),
+			Decimal::from_i128_with_scale(10i128.pow(d.scale()), 0),
+		)
+	};
+
+	let (numerator, a) = integer(n.numerator);
+	let (denominator, b) = integer(n.denominator);
+
+	Ratio::new(numerator.checked_mul(b)?, denominator.checked_mul(a)?)
+}
+
+#[inline(always)]
+fn is_integer(n: &Ratio) -> bool {
+	n.denominator == Decimal::ONE && n.numerator.fract().is_zero()
+}
+
+/// Value of a polynomial with coefficients from the highest power down
+#[inline(always)]
+fn evaluate(f: &[Ratio], x: Ratio) -> Ratio {
+	f.iter().fold(Ratio::ZERO, |value, &c| value.mul(x).add(c))
+}
+
+/// Quotient and remainder of the division of one polynomial by another
+pub(crate) fn divide(f: &[Ratio], g: &[Ratio]) -> Option<(Vec<Ratio>, Vec<Ratio>)> {
+	let lead = g.first()?.recip()?;
+
+	if f.len() < g.len() {
+		return Some((vec![Ratio::ZERO], f.to_vec()));
+	}
+
+	let mut remainder = f.to_vec();
+	let mut quotient = vec![];
+
+	for i in 0..=f.len() - g.len() {
+		let q = remainder[i].mul(lead);
+
+		for (j, &c) in g.iter().enumerate() {
+			remainder[i + j] = remainder[i + j].add(q.mul(c).neg());
+		}
+
+		quotient.push(q);
+	}
+
+	Some((quotient, remainder.split_off(f.len() - g.len() + 1)))
+}
+
+#[inline(always)]
+pub(crate) fn multiply(f: &[Ratio], g: &[Ratio]) -> Vec<Ratio> {
+	let mut product = vec![Ratio::ZERO; f.len() + g.len() - 1];
+
+	for (i, &a) in f.iter().enumerate() {
+		for (j, &b) in g.iter().enumerate() {
+			product[i + j] = product[i + j].add(a.mul(b));
+		}
+	}
+
+	product
+}
+
+/// Positive divisors of an integer, when it is small enough to search
+fn divisors(n: Decimal) -> Option<Vec<Decimal>> {
+	let n = n.abs();
+
+	if n.is_zero() || n > LARGEST_DIVIDED {
+		return None;
+	}
+
+	let (mut small, mut large) = (vec![], vec![]);
+	let mut d = Decimal::ONE;
+
+	while d * d <= n {
+		if (n % d).is_zero() {
+			small.push(d);
+
+			if d * d != n {
+				large.push(n / d);
+			}
+		}
+
+		d += Decimal::ONE;
+	}
+
+	small.extend(large.into_iter().rev());
+
+	Some(small)
+}
+
+/// Polynomial with integer coefficients which have no common factor and a
+/// positive leading one, along with the number it was divided by
+pub(crate) fn primitive(f: &[Ratio]) -> Option<(Ratio, Vec<Ratio>)> {
+	let f = f
+		.iter()
+		.map(|&c| fraction(c))
+		.collect::<Option<Vec<Ratio>>>()?;
+
+	let multiple = f.iter().try_fold(Decimal::ONE, |multiple, c| {
+		(multiple / gcd(multiple, c.denominator)).checked_mul(c.denominator)
+	})?;
+	let divisor = f
+		.iter()
+		.fold(Decimal::ZERO, |divisor, c| gcd(divisor, c.numerator.abs()));
+
+	let divisor = match f.iter().find(|c| !c.is_zero()) {
+		Some(lead) if lead.is_negative() => -divisor,
+		_ => divisor,
+	};
+
+	let constant = Ratio::new(divisor, multiple)?;
+	let scale = constant.recip()?;
+
+	Some((constant, f.iter().map(|c| c.mul(scale)).collect()))
+}
+
+/// A rational root of a polynomial with integer coefficients, which is a
+/// divisor of the constant over a divisor of the leading coefficient
+fn rational_root(f: &[Ratio]) -> Option<Ratio> {
+	let constants = divisors(f.last()?.numerator)?;
+	let leads = divisors(f.first()?.numerator)?;
+
+	for &q in &leads {
+		for &p in &constants {
+			for p in [p, -p] {
+				let root = Ratio::new(p, q)?;
+
+				if evaluate(f, root).is_zero() {
+					return Some(root);
+				}
+			}
+		}
+	}
+
+	None
+}
+
+/// Polynomial of lowest degree through the points
+fn interpolate(points: &[(Ratio, Ratio)]) -> Option<Vec<Ratio>> {
+	let mut f = vec![Ratio::ZERO; points.len()];
+
+	for (i, &(xi, yi)) in points.iter().enumerate() {
+		let mut basis = vec![Ratio::ONE];
+		let mut scale = yi;
+
+		for (j, &(xj, _)) in points.iter().enumerate() {
+			if i != j {
+				basis = multiply(&basis, &[Ratio::ONE, xj.neg()]);
+				scale = scale.mul(xi.add(xj.neg()).recip()?);
+			}
+		}
+
+		for (c, b) in f.iter_mut().zip(basis) {
+			*c = c.add(b.mul(scale));
+		}
+	}
+
+	Some(f)
+}
+
+/// A factor of degree `d` of a polynomial with integer coefficients by
+/// Kronecker's method: its values at `d + 1` integers must divide the values of
+/// the polynomial there, so every choice of divisors is tried as the values of
+/// the factor
+fn kronecker(f: &[Ratio], d: usize) -> Option<Vec<Ratio>> {
+	let points = (0..=d as i64)
+		.map(|i| Decimal::from(if i % 2 == 0 { -i / 2 } else { i / 2 + 1 }))
+		.map(|x| Ratio::new(x, Decimal::ONE))
+		.collect::<Option<Vec<Ratio>>>()?;
+
+	// The factor and its negation are the same, so the first value is positive
+	let choices = points
+		.iter()
+		.enumerate()
+		.map(|(i, &x)| {
+			let divisors = divisors(evaluate(f, x).numerator)?;
+
+			Some(if i == 0 {
+				divisors
+			} else {
+				divisors.iter().flat_map(|&d| [d, -d]).collect()
+			})
+		})
+		.collect::<Option<Vec<Vec<Decimal>>>>()?;
+
+	if choices
+		.iter()
+		.try_fold(1usize, |count, choice| count.checked_mul(choice.len()))
+		.is_none_or(|count| count > KRONECKER_CANDIDATES)
+	{
+		return None;
+	}
+
+	let mut chosen = vec![0; choices.len()];
+
+	loop {
+		let values = points
+			.iter()
+			.zip(&chosen)
+			.zip(&choices)
+			.map(|((&x, &i), choice)| Some((x, Ratio::new(choice[i], Decimal::ONE)?)))
+			.collect::<Option<Vec<(Ratio, Ratio)>>>()?;
+
+		if let Some(g) = interpolate(&values)
+			&& !g[0].is_zero()
+			&& g.iter().all(is_integer)
+			&& let Some((_, remainder)) = divide(f, &g)
+			&& remainder.iter().all(|c| c.is_zero())
+		{
+			return Some(primitive(&g)?.1);
+		}
+
+		// Moves on to the next choice of divisors like the digits of a counter
+		let mut i = 0;
+
+		while i < chosen.len() && chosen[i] + 1 == choices[i].len() {
+			chosen[i] = 0;
+			i += 1;
+		}
+
+		if i == chosen.len() {
+			return None;
+		}
+
+		chosen[i] += 1;
+	}
+}
+
+/// Irreducible factors over the rationals of a primitive polynomial with
+/// integer coefficients and a constant term which is not zero
+pub(crate) fn irreducible(f: Vec<Ratio>) -> Vec<Vec<Ratio>> {
+	let mut factors = vec![];
+	let mut pending = vec![f];
+
+	while let Some(f) = pending.pop() {
+		let degree = f.len() - 1;
+
+		if degree == 0 {
+			continue;
+		}
+
+		let factor = if degree == 1 {
+			None
+		} else if let Some(root) = rational_root(&f) {
+			Some(vec![
+				Ratio::new(root.denominator, Decimal::ONE).unwrap_or(Ratio::ONE),
+				Ratio::new(-root.numerator, Decimal::ONE).unwrap_or(Ratio::ZERO),
+			])
+		} else {
+			// Quadratics and cubics without rational roots do not factor
+			(2..=degree / 2).find_map(|d| kronecker(&f, d))
+		};
+
+		match factor.and_then(|g| Some((divide(&f, &g)?.0, g))) {
+			Some((quotient, g)) => {
+				pending.push(quotient);
+				pending.push(g);
+			}
+			None => factors.push(f),
+		}
+	}
+
+	factors
+}
+
+/// Expression of a polynomial in `x` with coefficients from the highest power
+/// down
+#[inline(always)]
+fn polynomial(f: &[Ratio], x: &str) -> Expression {
+	sum(
+		f.iter()
+			.rev()
+			.enumerate()
+			.map(|(k, c)| {
+				product(vec![
+					c.expression(),
+					power(
+						Expression::Identifier(x.to_string()),
+						number(Decimal::from(k)),
+					),
+				])
+			})
+			.collect(),
+	)
+}
+
+impl Expression {
+	/// Multiplies out every product of sums and positive integer power of a
+	/// sum, so the expression becomes a sum of products.
+	pub fn expand(&self) -> Expression {
+		expand(&self.simplify()).simplify()
+	}
+
+	/// Expands the expression and gathers the terms with the same power of `x`,
+	/// from the highest power down.
+	pub fn collect(&self, x: &str) -> Expression {
+		let mut groups: Vec<(Expression, Vec<Expression>)> = vec![];
+
+		for term in terms(self.expand()) {
+			let (exponent, coefficient) = split(term, x);
+
+			match groups.iter_mut().find(|(other, _)| *other == exponent) {
+				Some((_, coefficients)) => coefficients.push(coefficient),
+				None => groups.push((exponent, vec![coefficient])),
+			}
+		}
+
+		groups.sort_by(|(a, _), (b, _)| exponent_order(b, a));
+
+		groups
+			.into_iter()
+			.map(|(exponent, coefficients)| (exponent, sum(coefficients)))
+			.filter(|(_, coefficient)| !ratio(coefficient).is_some_and(Ratio::is_zero))
+			.map(|(exponent, coefficient)| {
+				let power = power(Expression::Identifier(x.to_string()), exponent);
+
+				if is_sum(&coefficient) {
+					Expression::Binary(
+						Box::new(coefficient),
+						Token::Mul,
+						Box::new(power),
+						Operands::default(),
+					)
+				} else {
+					product(vec![coefficient, power])
+				}
+			})
+			.reduce(|sum, term| {
+				if term.is_negation() {
+					Expression::Binary(
+						Box::new(sum),
+						Token::Sub,
+						Box::new(negate(term)),
+						Operands::default(),
+					)
+				} else {
+					Expression::Binary(
+						Box::new(sum),
+						Token::Add,
+						Box::new(term),
+						Operands::default(),
+					)
+				}
+			})
+			.unwrap_or(number(Decimal::ZERO))
+	}
+
+	/// Coefficients of the expression as a polynomial in `x`, from the highest
+	/// power down to the constant.
+	pub fn coefficients(&self, x: &str) -> Result<Vec<Expression>, Error> {
+		let not_polynomial =
+			|| Error::LogicError(format!("expected a polynomial in `{x}`, found `{self}`"));
+
+		let mut coefficients: Vec<Vec<Expression>> = vec![];
+
+		for term in terms(self.expand()) {
+			let (exponent, coefficient) = split(term, x);
+
+			let degree = match ratio(&exponent).and_then(Ratio::integer) {
+				Some(degree) if (0..=LARGEST_DEGREE).contains(&degree) => degree as usize,
+				_ => return Err(not_polynomial()),
+			};
+
+			if coefficient.involves(x) {
+				return Err(not_polynomial());
+			}
+
+			if coefficients.len() <= degree {
+				coefficients.resize(degree + 1, vec![]);
+			}
+
+			coefficients[degree].push(coefficient);
+		}
+
+		Ok(coefficients.into_iter().rev().map(sum).collect())
+	}
+
+	/// Product of the irreducible factors over the rationals of a polynomial of
+	/// one variable with rational coefficients.
+	pub fn factor(&self) -> Result<Expression, Error> {
+		let expanded = self.expand();
+		let variables = expanded.identifiers();
+
+		let x = match &variables[..] {
+			[] => return Ok(expanded),
+			[x] => x,
+			_ => {
+				return Err(Error::LogicError(format!(
+					"expected a polynomial of one variable to factor, found {}",
+					variables.join(", ")
+				)));
+			}
+		};
+
+		let f = expanded
+			.coefficients(x)?
+			.iter()
+			.map(ratio)
+			.collect::<Option<Vec<Ratio>>>()
+			.ok_or_else(|| {
+				Error::LogicError(format!(
+					"expected a polynomial with rational coefficients, found `{expanded}`"
+				))
+			})?;
+
+		let Some((constant, mut f)) = primitive(&f) else {
+			return Err(Error::LogicError(format!(
+				"coefficients of `{expanded}` are too large to factor"
+			)));
+		};
+
+		let zeros = f.iter().rev().take_while(|c| c.is_zero()).count();
+
+		f.truncate(f.len() - zeros);
+
+		let mut factors = vec![
+			constant.expression(),
+			power(
+				Expression::Identifier(x.clone()),
+				number(Decimal::from(zeros)),
+			),
+		];
+
+		factors.extend(irreducible(f).iter().map(|g| polynomial(g, x)));
+
+		Ok(product(factors))
+	}
+}
@@ -1,4 +1,4 @@
-use clap::{Parser as ClapParser, Subcommand, command};
+use clap::{Parser as ClapParser, Subcommand};
 
 use rustyline::{
 	Completer, Config, Editor, Helper, Hinter, Validator,
@@ -130,7 +130,11 @@ pub fn repl() {
 	rl.set_helper(Some(h));
 
 	let mut interpreter = Interpreter::new();
-	let ctx = &mut (&mut interpreter.globals, &mut interpreter.functions);
+	let ctx = &mut (
+		&mut interpreter.globals,
+		&mut interpreter.functions,
+		&mut interpreter.random,
+	);
 
 	loop {
 		"\x1b[1m\x1b[32m[In]:\x1b[0m "
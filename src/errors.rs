@@ -7,6 +7,8 @@ use codespan_reporting::{
 	term::termcolor::{ColorChoice, StandardStream},
 };
 
+use rust_decimal::Decimal;
+
 use crate::token::Token;
 use crate::types::DataType;
 
@@ -16,6 +18,9 @@ pub enum Error {
 	TypeError(TypeError),
 	LogicError(String),
 	EOLError(EOLError),
+	IndexError(IndexError),
+	ShapeError(ShapeError),
+	ValueError(ValueError),
 }
 
 impl From<&str> for Error {
@@ -32,6 +37,9 @@ impl Error {
 			Error::TypeError(type_error) => type_error.error_message(),
 			Error::LogicError(error_message) => error_message.to_string(),
 			Error::EOLError(eol_error) => eol_error.error_message(),
+			Error::IndexError(index_error) => index_error.error_message(),
+			Error::ShapeError(shape_error) => shape_error.error_message(),
+			Error::ValueError(value_error) => value_error.error_message(),
 		}
 	}
 
@@ -42,6 +50,9 @@ impl Error {
 			Error::TypeError(type_error) => type_error.help_message(),
 			Error::LogicError(help_message) => help_message.to_string(),
 			Error::EOLError(eol_error) => eol_error.help_message(),
+			Error::IndexError(index_error) => index_error.help_message(),
+			Error::ShapeError(shape_error) => shape_error.help_message(),
+			Error::ValueError(value_error) => value_error.help_message(),
 		}
 	}
 
@@ -52,7 +63,29 @@ impl Error {
 			Error::TypeError(type_error) => type_error.range.clone(),
 			Error::LogicError(..) => 0..0,
 			Error::EOLError(eol_error) => eol_error.range.clone(),
+			Error::IndexError(index_error) => index_error.range.clone(),
+			Error::ShapeError(shape_error) => shape_error.range.clone(),
+			Error::ValueError(value_error) => value_error.range.clone(),
+		}
+	}
+
+	/// Points errors raised without knowledge of the source, eg by operators, at
+	/// the expression which caused them.
+	#[inline(always)]
+	pub fn with_range(mut self, range: Range<usize>) -> Self {
+		match &mut self {
+			Error::TypeError(TypeError { range: r, .. })
+			| Error::IndexError(IndexError { range: r, .. })
+			| Error::ShapeError(ShapeError { range: r, .. })
+			| Error::ValueError(ValueError { range: r, .. })
+				if *r == (0..0) =>
+			{
+				*r = range
+			}
+			_ => {}
 		}
+
+		self
 	}
 }
 
@@ -96,6 +129,7 @@ impl SyntaxError {
 pub struct TypeError {
 	expected: DataType,
 	got: DataType,
+	message: Option<String>,
 	pub range: Range<usize>,
 }
 
@@ -105,16 +139,28 @@ impl TypeError {
 		Self {
 			expected,
 			got,
+			message: None,
 			range,
 		}
 	}
 
+	/// Names the operation the value can not be used in, when the expected type
+	/// alone would not say what went wrong
+	#[inline(always)]
+	pub fn with_message(mut self, message: String) -> Self {
+		self.message = Some(message);
+		self
+	}
+
 	#[inline(always)]
 	pub fn error_message(&self) -> String {
-		format!(
-			"\x1b[1mexpected `{}`, found `{}`\x1b[0m",
-			self.expected, self.got
-		)
+		match &self.message {
+			Some(message) => format!("\x1b[1m{message}\x1b[0m"),
+			None => format!(
+				"\x1b[1mexpected `{}`, found `{}`\x1b[0m",
+				self.expected, self.got
+			),
+		}
 	}
 
 	#[inline(always)]
@@ -158,6 +204,132 @@ impl EOLError {
 	}
 }
 
+#[derive(Debug)]
+pub struct IndexError {
+	index: Decimal,
+	len: usize,
+	pub range: Range<usize>,
+}
+
+impl IndexError {
+	#[inline(always)]
+	pub fn new(index: Decimal, len: usize, range: Range<usize>) -> Self {
+		Self { index, len, range }
+	}
+
+	#[inline(always)]
+	pub fn error_message(&self) -> String {
+		format!(
+			"\x1b[1mindex `{}` is out of bounds for length `{}`\x1b[0m",
+			self.index, self.len
+		)
+	}
+
+	#[inline(always)]
+	pub fn help_message(&self) -> String {
+		format!(
+			"\x1b[1mhelp:\x1b[0m indices are whole numbers from `1` upto `{}`",
+			self.len
+		)
+	}
+
+	#[inline(always)]
+	pub fn to_error(self) -> Error {
+		Error::IndexError(self)
+	}
+}
+
+#[derive(Debug)]
+pub struct ShapeError {
+	expected: (usize, usize),
+	got: (usize, usize),
+	reason: &'static str,
+	message: Option<String>,
+	pub range: Range<usize>,
+}
+
+impl ShapeError {
+	#[inline(always)]
+	pub fn new(
+		expected: (usize, usize),
+		got: (usize, usize),
+		reason: &'static str,
+		range: Range<usize>,
+	) -> Self {
+		Self {
+			expected,
+			got,
+			reason,
+			message: None,
+			range,
+		}
+	}
+
+	/// Describes the mismatch in words instead of by the two shapes, when they
+	/// alone would not say which part is wrong
+	#[inline(always)]
+	pub fn with_message(mut self, message: String) -> Self {
+		self.message = Some(message);
+		self
+	}
+
+	#[inline(always)]
+	pub fn error_message(&self) -> String {
+		match &self.message {
+			Some(message) => format!("\x1b[1m{message}\x1b[0m"),
+			None => format!(
+				"\x1b[1mexpected `{}x{}`, found `{}x{}`\x1b[0m",
+				self.expected.0, self.expected.1, self.got.0, self.got.1
+			),
+		}
+	}
+
+	#[inline(always)]
+	pub fn help_message(&self) -> String {
+		format!("\x1b[1mhelp:\x1b[0m {}", self.reason)
+	}
+
+	#[inline(always)]
+	pub fn to_error(self) -> Error {
+		Error::ShapeError(self)
+	}
+}
+
+/// A value of the right type which the operation is not defined for, such as
+/// an argument outside the domain of a function
+#[derive(Debug)]
+pub struct ValueError {
+	message: String,
+	reason: &'static str,
+	pub range: Range<usize>,
+}
+
+impl ValueError {
+	#[inline(always)]
+	pub fn new(message: String, reason: &'static str, range: Range<usize>) -> Self {
+		Self {
+			message,
+			reason,
+			range,
+		}
+	}
+
+	#[inline(always)]
+	pub fn error_message(&self) -> String {
+		format!("\x1b[1m{}\x1b[0m", self.message)
+	}
+
+	#[inline(always)]
+	pub fn help_message(&self) -> String {
+		format!("\x1b[1mhelp:\x1b[0m {}", self.reason)
+	}
+
+	#[inline(always)]
+	pub fn to_error(self) -> Error {
+		Error::ValueError(self)
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct ErrorReporter<'a> {
 	file: SimpleFile<&'a str, &'a str>,
@@ -176,7 +348,11 @@ impl<'a> ErrorReporter<'a> {
 		let diagnostic = Diagnostic::error()
 			.with_message(&error_message)
 			.with_labels(vec![
-				Label::primary((), range.start.saturating_sub(1)..range.end).with_message(error_message),
+				Label::primary(
+					(),
+					range.start.saturating_sub(1)..range.end.saturating_sub(1),
+				)
+				.with_message(error_message),
 			])
 			.with_notes(vec![help_message]);
 
@@ -198,7 +374,11 @@ impl<'a> ErrorReporter<'a> {
 		let diagnostic = Diagnostic::error()
 			.with_message(&error_message)
 			.with_labels(vec![
-				Label::primary((), range.start.saturating_sub(1)..range.end).with_message(error_message),
+				Label::primary(
+					(),
+					range.start.saturating_sub(1)..range.end.saturating_sub(1),
+				)
+				.with_message(error_message),
 			])
 			.with_notes(vec![help_message]);
 
@@ -1,25 +1,33 @@
 use crate::standardlibrary::{
-	internal_type_map, is_std, math,
-	operators::{add, div, gt, gteq, is_eq, lt, lteq, mul, neq, pow, rem, sub},
+	internal_type_map, is_elementwise, is_std, math,
+	operators::{
+		add, div, dot_div, dot_mul, dot_pow, gt, gteq, is_eq, lt, lteq, mul, neq, plus_minus, pow, rem,
+		sub,
+	},
 };
 use crate::{
-	errors::{Error, TypeError},
+	errors::{Error, IndexError, ShapeError, TypeError, ValueError},
 	interpreter::{Function, InterpreterContext, UserDefinedFunction, Variable},
+	matrix::{Complex, Matrix},
 	token::Token,
 	types::{Data, DataType},
 };
-use rust_decimal::{Decimal, MathematicalOps};
+use rust_decimal::Decimal;
 use std::{fmt::Display, ops::Range};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expression {
 	Assignment((String, Option<DataType>), Box<Expression>),
+	Symbols(Vec<String>),
 	Abs(Box<Expression>),
-	Binary(Box<Expression>, Token, Box<Expression>),
+	Binary(Box<Expression>, Token, Box<Expression>, Operands),
 	Branched(Box<Expression>, Box<Expression>, Box<Expression>),
 	Identifier(String),
 	Float(Decimal),
 	Matrix(Vec<Vec<Expression>>),
+	List(Vec<Expression>),
+	Index(Box<Expression>, Slices),
+	IndexedAssignment(String, Slices, Box<Expression>),
 	FunctionCall(String, Vec<(Expression, Range<usize>)>),
 	FunctionDeclaration(
 		String,
@@ -30,6 +38,455 @@ pub enum Expression {
 	),
 }
 
+/// Indices along with the range they were parsed from
+pub type Slices = Vec<(Slice, Range<usize>)>;
+
+/// Ranges the two operands of a binary operation were parsed from, to point
+/// errors at them. Expressions which are built rather than parsed have none, and
+/// where an operand came from never makes two expressions differ.
+#[derive(Debug, Clone, Default)]
+pub struct Operands(pub Range<usize>, pub Range<usize>);
+
+impl Operands {
+	/// Range of each operand, or `range` for those which were not parsed
+	#[inline(always)]
+	fn or(&self, range: &Range<usize>) -> (Range<usize>, Range<usize>) {
+		let or = |operand: &Range<usize>| {
+			if *operand == (0..0) {
+				range.clone()
+			} else {
+				operand.clone()
+			}
+		};
+
+		(or(&self.0), or(&self.1))
+	}
+}
+
+impl PartialEq for Operands {
+	fn eq(&self, _: &Self) -> bool {
+		true
+	}
+}
+
+/// A single index into a list or one dimension of a matrix, indices start at 1
+/// and ranges are inclusive of both ends.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Slice {
+	All,
+	Single(Expression),
+	Range(Option<Expression>, Option<Expression>),
+}
+
+impl Slice {
+	/// Resolves the slice into zero based positions along a dimension of length
+	/// `len`.
+	pub fn resolve<'a, 'b>(
+		&self,
+		len: usize,
+		ctx: &'a mut InterpreterContext<'b>,
+		range: Range<usize>,
+	) -> Result<Vec<usize>, Error>
+	where
+		'b: 'a,
+	{
+		let mut position = |expr: &Expression| -> Result<usize, Error> {
+			let data = expr.clone().evaluate(ctx, range.clone())?;
+
+			let Data::Number(n, b) = data else {
+				return Err(TypeError::new(DataType::Number, data.ty(), range.clone()).to_error());
+			};
+
+			if !b.is_zero() || !n.fract().is_zero() {
+				return Err(
+					ValueError::new(
+						format!("index `{data}` is not a whole number"),
+						"indices are whole numbers counting from `1`",
+						range.clone(),
+					)
+					.to_error(),
+				);
+			}
+
+			if n < Decimal::ONE || n > Decimal::from(len) {
+				return Err(IndexError::new(n, len, range.clone()).to_error());
+			}
+
+			Ok(n.to_string().parse::<usize>().unwrap() - 1)
+		};
+
+		Ok(match self {
+			Slice::All => (0..len).collect(),
+			Slice::Single(expr) => vec![position(expr)?],
+			Slice::Range(lower, upper) => {
+				let lower = match lower {
+					Some(expr) => position(expr)?,
+					None => 0,
+				};
+
+				let upper = match upper {
+					Some(expr) => position(expr)? + 1,
+					None => len,
+				};
+
+				(lower..upper).collect()
+			}
+		})
+	}
+}
+
+impl Display for Slice {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Slice::All => write!(f, ":"),
+			Slice::Single(expr) => write!(f, "{expr}"),
+			Slice::Range(lower, upper) => write!(
+				f,
+				"{}..{}",
+				lower.as_ref().map(|e| e.to_string()).unwrap_or_default(),
+				upper.as_ref().map(|e| e.to_string()).unwrap_or_default()
+			),
+		}
+	}
+}
+
+/// Resolves `slices` against the dimensions of `data`, a vector shaped matrix
+/// can be indexed with a single slice.
+fn resolve_slices<'a, 'b>(
+	data: &Data,
+	slices: &[(Slice, Range<usize>)],
+	ctx: &'a mut InterpreterContext<'b>,
+) -> Result<(Vec<usize>, Vec<usize>), Error>
+where
+	'b: 'a,
+{
+	let count = |expected: &str, shape: (usize, usize), reason: &'static str| {
+		let range = match (slices.first(), slices.last()) {
+			(Some((_, first)), Some((_, last))) => first.start..last.end,
+			_ => 0..0,
+		};
+
+		ShapeError::new(shape, shape, reason, range)
+			.with_message(format!("expected {expected}, found {}", slices.len()))
+			.to_error()
+	};
+
+	match data {
+		Data::List(list) => match slices {
+			[(slice, range)] => Ok((vec![0], slice.resolve(list.len(), ctx, range.clone())?)),
+			_ => Err(count(
+				"1 index for a list",
+				(1, list.len()),
+				"lists are indexed by a single position, as in `l[i]`",
+			)),
+		},
+		Data::Matrix(matrix) => {
+			let (rows, cols) = matrix.shape();
+
+			match slices {
+				[(row, row_range), (col, col_range)] => Ok((
+					row.resolve(rows, ctx, row_range.clone())?,
+					col.resolve(cols, ctx, col_range.clone())?,
+				)),
+				[(slice, range)] if rows == 1 => Ok((vec![0], slice.resolve(cols, ctx, range.clone())?)),
+				[(slice, range)] if cols == 1 => Ok((slice.resolve(rows, ctx, range.clone())?, vec![0])),
+				_ => Err(count(
+					&format!("2 indices for a {rows}x{cols} matrix"),
+					(rows, cols),
+					"matrices are indexed by row and column, as in `A[i, j]`, only vectors take a single index",
+				)),
+			}
+		}
+		_ => Err(
+			TypeError::new(
+				DataType::Matrix,
+				data.ty(),
+				slices.first().map(|(_, r)| r.clone()).unwrap_or(0..0),
+			)
+			.to_error(),
+		),
+	}
+}
+
+/// Whether the value stands for unknown numbers, because it is a name without a
+/// value, an expression or a list holding one
+#[inline(always)]
+fn is_unknown(data: &Data) -> bool {
+	match data {
+		Data::Ident(..) | Data::Expression(..) => true,
+		Data::List(list) => list.iter().any(is_unknown),
+		_ => false,
+	}
+}
+
+/// Applies a binary operator to the values of its operands, which were parsed
+/// from `ranges`.
+fn operate(
+	lhd: Data,
+	token: Token,
+	rhd: Data,
+	(lhs_range, rhs_range): (Range<usize>, Range<usize>),
+) -> Result<Data, Error> {
+	let range = lhs_range.start..rhs_range.end;
+
+	// Names without values and expressions are combined into a larger
+	// expression, such as an equation to solve
+	if token != Token::PlusMinus && (is_unknown(&lhd) || is_unknown(&rhd)) {
+		return symbolic(lhd, token, rhd, (lhs_range, rhs_range));
+	}
+
+	match token {
+		Token::Add => add(&lhd, &rhd),
+		Token::Sub => sub(&lhd, &rhd),
+		Token::Mul => mul(&lhd, &rhd),
+		Token::Div => div(&lhd, &rhd),
+		Token::Pow => pow(&lhd, &rhd),
+		Token::Rem => rem(&lhd, &rhd),
+		Token::DotMul => dot_mul(&lhd, &rhd),
+		Token::DotDiv => dot_div(&lhd, &rhd),
+		Token::DotPow => dot_pow(&lhd, &rhd),
+		Token::PlusMinus => plus_minus(&lhd, &rhd),
+		Token::IsEq => is_eq(&lhd, &rhd),
+		Token::NEq => neq(&lhd, &rhd),
+		Token::Gt => gt(&lhd, &rhd),
+		Token::GtEq => gteq(&lhd, &rhd),
+		Token::Lt => lt(&lhd, &rhd),
+		Token::LtEq => lteq(&lhd, &rhd),
+		_ => unreachable!(),
+	}
+	.map_err(|err| err.with_range(range))
+}
+
+/// Builds the expression for an operator applied to unknown numbers. Lists are
+/// combined element by element like lists of numbers, and so are matrices of
+/// numbers, which become matrices of expressions.
+fn symbolic(
+	lhd: Data,
+	token: Token,
+	rhd: Data,
+	(lhs_range, rhs_range): (Range<usize>, Range<usize>),
+) -> Result<Data, Error> {
+	let range = lhs_range.start..rhs_range.end;
+	let ranges = || (lhs_range.clone(), rhs_range.clone());
+
+	match (lhd, rhd) {
+		(Data::List(a), Data::List(b)) => {
+			if a.len() != b.len() {
+				return Err(
+					ShapeError::new(
+						(1, a.len()),
+						(1, b.len()),
+						"lists must have the same length for element-wise operations",
+						range,
+					)
+					.to_error(),
+				);
+			}
+
+			Ok(Data::List(
+				a.into_iter()
+					.zip(b)
+					.map(|(a, b)| operate(a, token.clone(), b, ranges()))
+					.collect::<Result<_, _>>()?,
+			))
+		}
+		(Data::List(a), b) => Ok(Data::List(
+			a.into_iter()
+				.map(|a| operate(a, token.clone(), b.clone(), ranges()))
+				.collect::<Result<_, _>>()?,
+		)),
+		(a, Data::List(b)) => Ok(Data::List(
+			b.into_iter()
+				.map(|b| operate(a.clone(), token.clone(), b, ranges()))
+				.collect::<Result<_, _>>()?,
+		)),
+		(lhd, rhd)
+			if matches!(lhd, Data::Matrix(..) | Data::Sparse(..))
+				|| matches!(rhd, Data::Matrix(..) | Data::Sparse(..)) =>
+		{
+			symbolic_matrix(&lhd, token, &rhd, (lhs_range, rhs_range))
+		}
+		(lhd, rhd) => {
+			let operand = |data: &Data, range: &Range<usize>| {
+				Expression::from_data(data)
+					.ok_or_else(|| TypeError::new(DataType::Number, data.ty(), range.clone()).to_error())
+			};
+
+			Ok(Data::Expression(Expression::Binary(
+				Box::new(operand(&lhd, &lhs_range)?),
+				token,
+				Box::new(operand(&rhd, &rhs_range)?),
+				Operands::default(),
+			)))
+		}
+	}
+}
+
+/// Combines a matrix of numbers with an unknown number or another matrix, by
+/// element or as a matrix product. Only sums, differences, products and
+/// quotients by a number and the element-wise operators are defined this way.
+fn symbolic_matrix(
+	lhd: &Data,
+	token: Token,
+	rhd: &Data,
+	(lhs_range, rhs_range): (Range<usize>, Range<usize>),
+) -> Result<Data, Error> {
+	let range = lhs_range.start..rhs_range.end;
+
+	let grid = |data: &Data| -> Option<Vec<Vec<Expression>>> {
+		let matrix = match data {
+			Data::Matrix(matrix) => matrix.clone(),
+			Data::Sparse(matrix) => matrix.to_dense(),
+			Data::Expression(Expression::Matrix(rows)) => return Some(rows.clone()),
+			_ => return None,
+		};
+
+		Some(
+			matrix
+				.iter_rows()
+				.map(|row| {
+					row
+						.iter()
+						.map(|&z| Expression::from_data(&Data::from(z)).unwrap())
+						.collect()
+				})
+				.collect(),
+		)
+	};
+
+	let not_defined = |data: &Data, range: Range<usize>| {
+		Err(TypeError::new(DataType::Number, data.ty(), range).to_error())
+	};
+
+	// The operator between single elements
+	let op = match &token {
+		Token::DotMul => Token::Mul,
+		Token::DotDiv => Token::Div,
+		Token::DotPow => Token::Pow,
+		op => op.clone(),
+	};
+
+	let rows = match (grid(lhd), grid(rhd)) {
+		(Some(a), Some(b)) => {
+			let shape = |rows: &Vec<Vec<Expression>>| (rows.len(), rows.first().map_or(0, Vec::len));
+			let (shape_a, shape_b) = (shape(&a), shape(&b));
+
+			match token {
+				Token::Mul => {
+					if shape_a.1 != shape_b.0 {
+						return Err(
+							ShapeError::new(
+								(shape_a.1, shape_b.1),
+								shape_b,
+								"number of rows of the right matrix must equal the number of columns of the left matrix",
+								range,
+							)
+							.to_error(),
+						);
+					}
+
+					(0..shape_a.0)
+						.map(|i| {
+							(0..shape_b.1)
+								.map(|j| {
+									(0..shape_a.1).fold(Expression::Float(Decimal::ZERO), |sum, k| {
+										Expression::binary(
+											sum,
+											Token::Add,
+											Expression::binary(a[i][k].clone(), Token::Mul, b[k][j].clone()),
+										)
+									})
+								})
+								.collect()
+						})
+						.collect()
+				}
+				Token::Add | Token::Sub | Token::DotMul | Token::DotDiv | Token::DotPow => {
+					if shape_a != shape_b {
+						return Err(
+							ShapeError::new(
+								shape_a,
+								shape_b,
+								"matrices must have the same dimensions for element-wise operations",
+								range,
+							)
+							.to_error(),
+						);
+					}
+
+					a.into_iter()
+						.zip(b)
+						.map(|(a, b)| {
+							a.into_iter()
+								.zip(b)
+								.map(|(a, b)| Expression::binary(a, op.clone(), b))
+								.collect()
+						})
+						.collect()
+				}
+				_ => return not_defined(rhd, rhs_range),
+			}
+		}
+		(Some(a), None) => {
+			let Some(b) = Expression::from_data(rhd) else {
+				return not_defined(rhd, rhs_range);
+			};
+
+			if !matches!(
+				token,
+				Token::Add
+					| Token::Sub
+					| Token::Mul
+					| Token::Div
+					| Token::DotMul
+					| Token::DotDiv
+					| Token::DotPow
+			) {
+				return not_defined(lhd, lhs_range);
+			}
+
+			a.into_iter()
+				.map(|row| {
+					row
+						.into_iter()
+						.map(|a| Expression::binary(a, op.clone(), b.clone()))
+						.collect()
+				})
+				.collect()
+		}
+		(None, Some(b)) => {
+			let Some(a) = Expression::from_data(lhd) else {
+				return not_defined(lhd, lhs_range);
+			};
+
+			if !matches!(
+				token,
+				Token::Add
+					| Token::Sub
+					| Token::Mul
+					| Token::Div
+					| Token::DotMul
+					| Token::DotDiv
+					| Token::DotPow
+			) {
+				return not_defined(rhd, rhs_range);
+			}
+
+			b.into_iter()
+				.map(|row| {
+					row
+						.into_iter()
+						.map(|b| Expression::binary(a.clone(), op.clone(), b))
+						.collect()
+				})
+				.collect()
+		}
+		(None, None) => unreachable!(),
+	};
+
+	Ok(Data::Expression(Expression::Matrix(rows)))
+}
+
 impl Expression {
 	#[inline(always)]
 	pub fn evaluate<'a, 'b>(
@@ -44,10 +501,16 @@ impl Expression {
 			Expression::Assignment((name, numbertype), expr) => {
 				let number = expr.evaluate(ctx, range.clone())?;
 
+				// Names without values, expressions and polynomials stand for numbers, and
+				// polynomials for expressions too, while matrices of expressions stand for
+				// matrices
 				if let Some(ty) = numbertype
-					&& number.ty() != numbertype.unwrap()
+					&& number.ty() != ty
+					&& !(ty == DataType::Number && number.is_symbolic())
+					&& !(ty == DataType::Expression && number.ty() == DataType::Polynomial)
+					&& !(ty == DataType::Matrix && matches!(number, Data::Expression(Expression::Matrix(..))))
 				{
-					return Err(TypeError::new(ty, number.ty(), 0..0).to_error());
+					return Err(TypeError::new(ty, number.ty(), range).to_error());
 				}
 
 				ctx.0.insert(name, Variable::new(number.clone(), true));
@@ -67,29 +530,35 @@ impl Expression {
 
 				Ok(Data::Ident(name))
 			}
+			// Declared names lose any value they had and stand for themselves
+			Expression::Symbols(names) => {
+				for name in &names {
+					ctx.0.remove(name);
+				}
+
+				Ok(match names.as_slice() {
+					[name] => Data::Ident(name.clone()),
+					names => Data::List(names.iter().cloned().map(Data::Ident).collect()),
+				})
+			}
 			Expression::Abs(expression) => {
 				let data = expression.evaluate(ctx, range.clone())?;
-				Ok(math::abs(&data))
-			}
-			Expression::Binary(lhs, token, rhs) => {
-				let lhd = lhs.evaluate(ctx, range.clone())?;
-				let rhd = rhs.evaluate(ctx, range.clone())?;
-
-				Ok(match token {
-					Token::Add => add(&lhd, &rhd),
-					Token::Sub => sub(&lhd, &rhd),
-					Token::Mul => mul(&lhd, &rhd),
-					Token::Div => div(&lhd, &rhd),
-					Token::Pow => pow(&lhd, &rhd),
-					Token::Rem => rem(&lhd, &rhd),
-					Token::IsEq => is_eq(&lhd, &rhd),
-					Token::NEq => neq(&lhd, &rhd),
-					Token::Gt => gt(&lhd, &rhd),
-					Token::GtEq => gteq(&lhd, &rhd),
-					Token::Lt => lt(&lhd, &rhd),
-					Token::LtEq => lteq(&lhd, &rhd),
-					_ => unreachable!(),
-				})
+
+				if matches!(data, Data::Ident(..) | Data::Expression(..))
+					&& let Some(expression) = Expression::from_data(&data)
+				{
+					return Ok(Data::Expression(Expression::Abs(Box::new(expression))));
+				}
+
+				math::abs(&data).map_err(|err| err.with_range(range))
+			}
+			Expression::Binary(lhs, token, rhs, operands) => {
+				let (lhs_range, rhs_range) = operands.or(&range);
+
+				let lhd = lhs.evaluate(ctx, lhs_range.clone())?;
+				let rhd = rhs.evaluate(ctx, rhs_range.clone())?;
+
+				operate(lhd, token, rhd, (lhs_range, rhs_range))
 			}
 			Expression::Branched(condition, then, otherwise) => {
 				let data = condition.evaluate(ctx, range.clone())?;
@@ -115,17 +584,175 @@ impl Expression {
 			Expression::Matrix(matrix) => {
 				let mut matrix_data = vec![];
 
+				let (rows, cols) = (
+					matrix.len(),
+					matrix.first().map(|row| row.len()).unwrap_or(0),
+				);
+
+				if let Some((i, row)) = matrix.iter().enumerate().find(|(_, row)| row.len() != cols) {
+					return Err(
+						ShapeError::new(
+							(1, cols),
+							(1, row.len()),
+							"every row of a matrix must have the same number of columns",
+							range,
+						)
+						.with_message(format!(
+							"row {} has {} elements, expected {cols}",
+							i + 1,
+							row.len()
+						))
+						.to_error(),
+					);
+				}
+
+				let mut elements = vec![];
+
 				for row in matrix {
-					let mut row_data = vec![];
 					for element in row {
-						let data = element.evaluate(ctx, range.clone())?;
-
-						row_data.push(data);
+						elements.push(element.evaluate(ctx, range.clone())?);
 					}
-					matrix_data.push(row_data);
 				}
 
-				Ok(Data::Matrix(matrix_data))
+				// A matrix of names or expressions, such as functions or the variables to
+				// differentiate them with, is kept as an expression
+				if elements
+					.iter()
+					.any(|data| matches!(data, Data::Ident(..) | Data::Expression(..)))
+					&& let Some(elements) = elements
+						.iter()
+						.map(Expression::from_data)
+						.collect::<Option<Vec<Expression>>>()
+				{
+					return Ok(Data::Expression(Expression::Matrix(
+						elements.chunks(cols).map(|row| row.to_vec()).collect(),
+					)));
+				}
+
+				for data in elements {
+					matrix_data.push(Complex::from_data(&data).map_err(|err| err.with_range(range.clone()))?);
+				}
+
+				Ok(Data::Matrix(Matrix::new(rows, cols, matrix_data)))
+			}
+			Expression::List(list) => {
+				let mut list_data = vec![];
+
+				for element in list {
+					list_data.push(element.evaluate(ctx, range.clone())?);
+				}
+
+				Ok(Data::List(list_data))
+			}
+			Expression::Index(expr, slices) => {
+				let data = expr.evaluate(ctx, range.clone())?;
+
+				let (rows, cols) = resolve_slices(&data, &slices, ctx)?;
+
+				let single = slices.iter().all(|(s, _)| matches!(s, Slice::Single(..)));
+
+				Ok(match data {
+					Data::List(list) => {
+						if single {
+							list[cols[0]].clone()
+						} else {
+							Data::List(cols.iter().map(|&j| list[j].clone()).collect())
+						}
+					}
+					Data::Matrix(matrix) => {
+						if single {
+							Data::from(matrix[(rows[0], cols[0])])
+						} else {
+							Data::Matrix(Matrix::from_fn(rows.len(), cols.len(), |i, j| {
+								matrix[(rows[i], cols[j])]
+							}))
+						}
+					}
+					_ => unreachable!(),
+				})
+			}
+			Expression::IndexedAssignment(name, slices, expr) => {
+				let Some(variable) = ctx.0.get(&name).cloned() else {
+					return Err(
+						ValueError::new(
+							format!("undefined variable: `{name}`"),
+							"declare the variable with `let` before assigning to its elements",
+							range,
+						)
+						.to_error(),
+					);
+				};
+
+				let value = expr.evaluate(ctx, range.clone())?;
+
+				let (rows, cols) = resolve_slices(&variable.value, &slices, ctx)?;
+
+				let is_list = variable.value.ty() == DataType::List;
+
+				// The value is either a single element which is copied into every selected
+				// position or has exactly the shape of the selection
+				let element = |i: usize, j: usize| -> Result<Data, Error> {
+					match &value {
+						Data::Matrix(matrix) => {
+							if matrix.shape() != (rows.len(), cols.len()) {
+								return Err(
+									ShapeError::new(
+										(rows.len(), cols.len()),
+										matrix.shape(),
+										"matrix assigned to a slice must have the shape of the slice",
+										range.clone(),
+									)
+									.to_error(),
+								);
+							}
+
+							Ok(Data::from(matrix[(i, j)]))
+						}
+						Data::List(list) if is_list => {
+							if list.len() != cols.len() {
+								return Err(
+									ShapeError::new(
+										(1, cols.len()),
+										(1, list.len()),
+										"list assigned to a slice must have the length of the slice",
+										range.clone(),
+									)
+									.to_error(),
+								);
+							}
+
+							Ok(list[j].clone())
+						}
+						data => Ok(data.clone()),
+					}
+				};
+
+				let data = match variable.value {
+					Data::List(mut list) => {
+						for (j, &col) in cols.iter().enumerate() {
+							list[col] = element(0, j)?;
+						}
+
+						Data::List(list)
+					}
+					Data::Matrix(mut matrix) => {
+						for (i, &row) in rows.iter().enumerate() {
+							for (j, &col) in cols.iter().enumerate() {
+								matrix[(row, col)] = Complex::from_data(&element(i, j)?)
+									.map_err(|err| err.with_range(range.clone()))?;
+							}
+						}
+
+						Data::Matrix(matrix)
+					}
+					_ => unreachable!(),
+				};
+
+				ctx
+					.0
+					.insert(name, Variable::new(data.clone(), variable.is_global));
+
+				Ok(data)
 			}
 			Expression::FunctionCall(name, exprs) => {
 				if !ctx.1.contains_key(&name) {
@@ -137,11 +764,31 @@ impl Expression {
 				let mut args = vec![];
 
 				for (expr, range) in exprs {
-					let data = expr.evaluate(ctx, range)?;
+					// A value bound to a name for this call, eg eval(y, x = 2), is passed
+					// on as the equation between them
+					let data = if let Expression::Assignment((name, None), value) = expr {
+						let value = value.evaluate(ctx, range.clone())?;
+
+						let Some(value) = Expression::from_data(&value) else {
+							return Err(Error::LogicError(format!(
+								"`{name}` can only be bound to a number or an expression"
+							)));
+						};
+
+						Data::Expression(Expression::Binary(
+							Box::new(Expression::Identifier(name)),
+							Token::Eq,
+							Box::new(value),
+							Operands::default(),
+						))
+					} else {
+						expr.evaluate(ctx, range)?
+					};
+
 					args.push(data);
 				}
 
-				f.execute(ctx, args)
+				f.execute(ctx, args).map_err(|err| err.with_range(range))
 			}
 		}
 	}
@@ -151,7 +798,8 @@ impl Expression {
 		match self {
 			Expression::Abs(expression) => expression.infer_datatype(),
 			Expression::Branched(_, e1, _) => e1.infer_datatype(),
-			Expression::Binary(lhs, _, rhs) => {
+			Expression::Binary(_, Token::PlusMinus, _, _) => Some(DataType::Measured),
+			Expression::Binary(lhs, _, rhs, _) => {
 				let lhs = Self::infer_datatype(lhs);
 				let rhs = Self::infer_datatype(rhs);
 
@@ -164,13 +812,24 @@ impl Expression {
 
 				match (lhs, rhs) {
 					(DataType::Number, DataType::Number) => Some(DataType::Number),
+					(DataType::Measured, DataType::Number | DataType::Measured)
+					| (DataType::Number, DataType::Measured) => Some(DataType::Measured),
 					(DataType::Matrix, _) | (_, DataType::Matrix) => Some(DataType::Matrix),
+					(DataType::Sparse, _) | (_, DataType::Sparse) => Some(DataType::Sparse),
+					(DataType::Polynomial, _) | (_, DataType::Polynomial) => Some(DataType::Polynomial),
+					(DataType::List, _) | (_, DataType::List) => Some(DataType::List),
 					_ => None,
 				}
 			}
-			Expression::Identifier(_) => None,
+			Expression::Identifier(_) | Expression::Symbols(..) => None,
 			Expression::Float(..) => Some(DataType::Number),
 			Expression::Matrix(..) => Some(DataType::Matrix),
+			Expression::List(..) => Some(DataType::List),
+			Expression::Index(..) | Expression::IndexedAssignment(..) => None,
+			// Element-wise functions return a value of the same type they are given
+			Expression::FunctionCall(ident, args) if is_elementwise(ident) => {
+				args.first().and_then(|(arg, _)| arg.infer_datatype())
+			}
 			Expression::FunctionCall(ident, _) => {
 				if is_std(ident) {
 					Some(internal_type_map(ident).1)
@@ -183,7 +842,71 @@ impl Expression {
 		}
 	}
 
+	/// The expression standing for a value, when it is a number, a name, a
+	/// polynomial or already an expression
+	#[inline(always)]
+	pub fn from_data(data: &Data) -> Option<Expression> {
+		match data {
+			Data::Number(re, im) if im.is_zero() => Some(Expression::Float(*re)),
+			// Complex numbers are written with the imaginary unit
+			Data::Number(re, im) => {
+				let imaginary = Expression::binary(
+					Expression::Float(im.abs()),
+					Token::Mul,
+					Expression::Identifier("i".to_string()),
+				);
+
+				Some(match (re.is_zero(), im.is_sign_negative()) {
+					(true, false) => imaginary,
+					(true, true) => {
+						Expression::binary(Expression::Float(-Decimal::ONE), Token::Mul, imaginary)
+					}
+					(false, false) => Expression::binary(Expression::Float(*re), Token::Add, imaginary),
+					(false, true) => Expression::binary(Expression::Float(*re), Token::Sub, imaginary),
+				})
+			}
+			Data::Ident(name) => Some(Expression::Identifier(name.clone())),
+			Data::Polynomial(polynomial) => Some(polynomial.expression()),
+			Data::Expression(expr) => Some(expr.clone()),
+			_ => None,
+		}
+	}
+
+	/// Builds `lhs op rhs`, folding numbers together and dropping the zeros and
+	/// ones which differentiation leaves behind.
+	pub fn binary(lhs: Expression, op: Token, rhs: Expression) -> Expression {
+		let zero = Expression::Float(Decimal::ZERO);
+		let one = Expression::Float(Decimal::ONE);
+
+		match (lhs, op, rhs) {
+			(Expression::Float(a), Token::Add, Expression::Float(b)) => Expression::Float(a + b),
+			(Expression::Float(a), Token::Sub, Expression::Float(b)) => Expression::Float(a - b),
+			(Expression::Float(a), Token::Mul | Token::DotMul, Expression::Float(b)) => {
+				Expression::Float(a * b)
+			}
+			(lhs, Token::Add, rhs) if lhs == zero => rhs,
+			(lhs, Token::Add | Token::Sub, rhs) if rhs == zero => lhs,
+			(lhs, Token::Mul | Token::DotMul, rhs) if lhs == zero || rhs == zero => zero,
+			(lhs, Token::Mul | Token::DotMul, rhs) if lhs == one => rhs,
+			(lhs, Token::Mul | Token::DotMul | Token::Div | Token::DotDiv, rhs) if rhs == one => lhs,
+			(lhs, Token::Div | Token::DotDiv, _) if lhs == zero => zero,
+			(lhs, Token::Div | Token::DotDiv, rhs) if lhs == rhs => one,
+			(lhs, Token::Pow | Token::DotPow, rhs) if rhs == one => lhs,
+			(_, Token::Pow | Token::DotPow, rhs) if rhs == zero => one,
+			(lhs, op, rhs) => Expression::Binary(Box::new(lhs), op, Box::new(rhs), Operands::default()),
+		}
+	}
+
 	#[inline(always)]
+	fn call(name: &str, args: Vec<Expression>) -> Expression {
+		Expression::FunctionCall(
+			name.to_string(),
+			args.into_iter().map(|arg| (arg, 0..0)).collect(),
+		)
+	}
+
+	/// Derivative with respect to the variable `wrt`, applying the chain rule
+	/// through every function called.
 	pub fn differentiate<'a, 'b>(
 		&self,
 		wrt: &Data,
@@ -198,157 +921,326 @@ impl Expression {
 			));
 		};
 
-		Ok(
-			match self {
-				t @ Expression::Binary(e1, op, e2) => match op {
-					Token::Add | Token::Sub => Expression::Binary(
-						Box::new(e1.differentiate(wrt, ctx)?),
-						op.to_owned(),
-						Box::new(e2.differentiate(wrt, ctx)?),
+		Ok(self.derivative(name, ctx)?.simplify())
+	}
+
+	fn derivative<'a, 'b>(
+		&self,
+		name: &str,
+		ctx: &'a mut InterpreterContext<'b>,
+	) -> Result<Expression, Error>
+	where
+		'b: 'a,
+	{
+		// Anything which does not involve the variable is constant
+		if !self.involves(name) {
+			return Ok(Expression::Float(Decimal::ZERO));
+		}
+
+		Ok(match self {
+			Expression::Identifier(_) => Expression::Float(Decimal::ONE),
+			Expression::Binary(f, op, g, _) => {
+				let (f, g) = (f.as_ref().clone(), g.as_ref().clone());
+				let (df, dg) = (f.derivative(name, ctx)?, g.derivative(name, ctx)?);
+
+				// Element-wise operators follow the same rules with element-wise
+				// products, quotients and powers
+				let (mul, div, pow) = match op {
+					Token::DotMul | Token::DotDiv | Token::DotPow => {
+						(Token::DotMul, Token::DotDiv, Token::DotPow)
+					}
+					_ => (Token::Mul, Token::Div, Token::Pow),
+				};
+
+				let product = |a, b| Expression::binary(a, mul.clone(), b);
+
+				match op {
+					Token::Add | Token::Sub => Expression::binary(df, op.clone(), dg),
+					Token::Mul | Token::DotMul => {
+						Expression::binary(product(df, g.clone()), Token::Add, product(f.clone(), dg))
+					}
+					Token::Div | Token::DotDiv => Expression::binary(
+						Expression::binary(product(df, g.clone()), Token::Sub, product(f, dg)),
+						div,
+						Expression::binary(g, pow, Expression::Float(Decimal::TWO)),
 					),
-					Token::Mul => Expression::Binary(
-						Box::new(Expression::Binary(
-							Box::new(e1.differentiate(wrt, ctx)?),
-							Token::Mul,
-							e2.to_owned(),
-						)),
-						Token::Add,
-						Box::new(Expression::Binary(
-							e1.to_owned(),
-							Token::Mul,
-							Box::new(e2.differentiate(wrt, ctx)?),
-						)),
+					Token::Pow | Token::DotPow if !g.involves(name) => product(
+						product(
+							g.clone(),
+							Expression::binary(
+								f,
+								pow,
+								Expression::binary(g, Token::Sub, Expression::Float(Decimal::ONE)),
+							),
+						),
+						df,
 					),
-					Token::Div => Expression::Binary(
-						Box::new(Expression::Binary(
-							Box::new(Expression::Binary(
-								Box::new(e1.differentiate(wrt, ctx)?),
-								Token::Mul,
-								e2.to_owned(),
-							)),
-							Token::Sub,
-							Box::new(Expression::Binary(
-								e1.to_owned(),
-								Token::Mul,
-								Box::new(e2.differentiate(wrt, ctx)?),
-							)),
-						)),
-						Token::Div,
-						Box::new(Expression::Binary(
-							e2.to_owned(),
-							Token::Pow,
-							Box::new(Expression::Float(Decimal::TWO)),
-						)),
+					Token::Pow | Token::DotPow if !f.involves(name) => {
+						product(product(self.clone(), Expression::call("ln", vec![f])), dg)
+					}
+					// (f^g)' = f^g (g' ln f + g f' / f)
+					Token::Pow | Token::DotPow => product(
+						self.clone(),
+						Expression::binary(
+							product(dg, Expression::call("ln", vec![f.clone()])),
+							Token::Add,
+							Expression::binary(product(g, df), div, f),
+						),
 					),
-					Token::Pow => match (*e1.to_owned(), *e2.to_owned()) {
-						(e @ Expression::Float(..), Expression::Identifier(ident)) => {
-							if name == &ident {
-								Expression::Binary(
-									Box::new(Expression::FunctionCall("ln".to_string(), vec![(e, 0..0)])),
-									Token::Mul,
-									Box::new(t.to_owned()),
-								)
-							} else {
-								Expression::Float(Decimal::ZERO)
-							}
-						}
-						(Expression::Identifier(ident), Expression::Float(n)) => {
-							if name == &ident {
-								Expression::Binary(
-									e2.to_owned(),
-									Token::Mul,
-									Box::new(Expression::Binary(
-										e1.to_owned(),
-										Token::Pow,
-										Box::new(Expression::Float(n - Decimal::ONE)),
-									)),
-								)
-							} else {
-								Expression::Float(Decimal::ZERO)
-							}
-						}
-						// TODO: Add case for f(x)^g(x)
-						_ => unimplemented!(),
-					},
-					_ => unimplemented!(),
-				},
-				Expression::Branched(_, _, _) => todo!(),
-				Expression::Identifier(ident) => {
-					let Data::Ident(name) = wrt else {
-						return Err(Error::LogicError(
-							"expected variable to differentiate with respect to".to_string(),
-						));
-					};
-					if ident != name {
+					// f % g = f - g floor(f / g), which is flat between the jumps
+					Token::Rem => Expression::binary(
+						df,
+						Token::Sub,
+						product(
+							dg,
+							Expression::call("floor", vec![Expression::binary(f, Token::Div, g)]),
+						),
+					),
+					// Comparisons only change where they jump between 0 and 1
+					Token::IsEq | Token::NEq | Token::Gt | Token::GtEq | Token::Lt | Token::LtEq => {
 						Expression::Float(Decimal::ZERO)
-					} else {
-						Expression::Float(Decimal::ONE)
 					}
+					_ => return Err(self.no_derivative()),
 				}
-				Expression::Float(_) => Expression::Float(Decimal::ZERO),
-				Expression::FunctionCall(name, args) => {
-					let func = &ctx.1.get(name);
-
-					if func.is_none() {
-						return Err(Error::LogicError(
-							"expected function to differentiate".to_string(),
-						));
+			}
+			Expression::Abs(f) => Expression::binary(
+				Expression::call("sign", vec![f.as_ref().clone()]),
+				Token::Mul,
+				f.derivative(name, ctx)?,
+			),
+			Expression::Branched(condition, then, otherwise) => Expression::Branched(
+				condition.clone(),
+				Box::new(then.derivative(name, ctx)?),
+				Box::new(otherwise.derivative(name, ctx)?),
+			),
+			Expression::Matrix(rows) => Expression::Matrix(
+				rows
+					.iter()
+					.map(|row| {
+						row
+							.iter()
+							.map(|element| element.derivative(name, ctx))
+							.collect::<Result<Vec<Expression>, Error>>()
+					})
+					.collect::<Result<Vec<Vec<Expression>>, Error>>()?,
+			),
+			Expression::List(elements) => Expression::List(
+				elements
+					.iter()
+					.map(|element| element.derivative(name, ctx))
+					.collect::<Result<Vec<Expression>, Error>>()?,
+			),
+			// (f(g₁, g₂, ...))' = ∂₁f g₁' + ∂₂f g₂' + ...
+			Expression::FunctionCall(function, args) => {
+				let Some(f) = ctx.1.get(function).cloned() else {
+					return Err(Error::LogicError(format!(
+						"undefined function: `{function}`"
+					)));
+				};
+
+				let mut derivative = Expression::Float(Decimal::ZERO);
+
+				for (i, (arg, _)) in args.iter().enumerate() {
+					if !arg.involves(name) {
+						continue;
 					}
 
-					let func = func.unwrap().clone();
+					let partial = match &f {
+						Function::STD(f) => f.partial(i, args)?,
+						Function::UserDefined(f) => {
+							let Some((param, _)) = f.params.get(i) else {
+								return Err(Error::LogicError(format!(
+									"`{function}` takes {} arguments, found {}",
+									f.params.len(),
+									args.len()
+								)));
+							};
 
-					let Data::Expression(mut expr) = func.differentiate(wrt, args, ctx)? else {
-						unreachable!()
-					};
+							let bindings = f
+								.params
+								.iter()
+								.zip(args)
+								.map(|((param, _), (arg, _))| (param.clone(), arg.clone()))
+								.collect::<Vec<(String, Expression)>>();
 
-					for (arg, _) in args {
-						expr = Expression::Binary(
-							Box::new(expr),
-							Token::Mul,
-							Box::new(arg.differentiate(wrt, ctx)?),
-						)
-					}
+							f.code.derivative(param, ctx)?.substitute(&bindings)
+						}
+					};
 
-					expr
+					derivative = Expression::binary(
+						derivative,
+						Token::Add,
+						Expression::binary(partial, Token::Mul, arg.derivative(name, ctx)?),
+					);
 				}
-				_ => unimplemented!(),
+
+				derivative
 			}
-			.simplify(),
-		)
+			_ => return Err(self.no_derivative()),
+		})
 	}
 
 	#[inline(always)]
-	pub fn simplify(&self) -> Expression {
+	fn no_derivative(&self) -> Error {
+		Error::LogicError(format!(
+			"attempt to differentiate `{self}` which has no derivative"
+		))
+	}
+
+	/// Replaces every identifier bound to a value with that value, all at once
+	/// so values referring to other bound names are left as they are.
+	pub fn substitute(&self, bindings: &[(String, Expression)]) -> Expression {
+		let substitute = |expr: &Expression| Box::new(expr.substitute(bindings));
+		let slices = |slices: &Slices| {
+			slices
+				.iter()
+				.map(|(slice, range)| {
+					(
+						match slice {
+							Slice::All => Slice::All,
+							Slice::Single(expr) => Slice::Single(expr.substitute(bindings)),
+							Slice::Range(lower, upper) => Slice::Range(
+								lower.as_ref().map(|expr| expr.substitute(bindings)),
+								upper.as_ref().map(|expr| expr.substitute(bindings)),
+							),
+						},
+						range.clone(),
+					)
+				})
+				.collect()
+		};
+
 		match self {
-			Expression::Binary(e1, op, e2) => match (*e1.to_owned(), op, *e2.to_owned()) {
-				(Expression::Float(a), Token::Add, Expression::Float(b)) => Expression::Float(a + b),
-				(Expression::Float(a), Token::Sub, Expression::Float(b)) => Expression::Float(a - b),
-				(Expression::Float(a), Token::Mul, Expression::Float(b)) => Expression::Float(a * b),
-				(Expression::Float(a), Token::Div, Expression::Float(b)) => Expression::Float(a / b),
-				(Expression::Float(a), Token::Pow, Expression::Float(b)) => Expression::Float(a.powd(b)),
-				(Expression::Float(a), Token::Mul, Expression::Binary(b, Token::Mul, c)) => {
-					match (*b.to_owned(), *c.to_owned()) {
-						(Expression::Float(x), Expression::Float(y)) => Expression::Float(a * x * y),
-						(Expression::Float(x), d) => {
-							Expression::Binary(Box::new(Expression::Float(x * a)), Token::Mul, Box::new(d))
-						}
-						(d, Expression::Float(x)) => {
-							Expression::Binary(Box::new(Expression::Float(x * a)), Token::Mul, Box::new(d))
-						}
-						_ => Expression::Binary(
-							Box::new(Expression::Float(a)),
-							Token::Mul,
-							Box::new(Expression::Binary(b, Token::Mul, c)),
-						),
-					}
-				}
-				_ => Expression::Binary(
-					Box::new(e1.simplify()),
-					op.to_owned(),
-					Box::new(e2.simplify()),
-				),
-			},
-			_ => self.to_owned(),
+			Expression::Identifier(name) => bindings
+				.iter()
+				.find(|(bound, _)| bound == name)
+				.map(|(_, value)| value.clone())
+				.unwrap_or_else(|| self.clone()),
+			Expression::Float(_) | Expression::Symbols(..) | Expression::FunctionDeclaration(..) => {
+				self.clone()
+			}
+			Expression::Assignment(target, expr) => {
+				Expression::Assignment(target.clone(), substitute(expr))
+			}
+			Expression::Abs(expr) => Expression::Abs(substitute(expr)),
+			Expression::Binary(lhs, op, rhs, operands) => Expression::Binary(
+				substitute(lhs),
+				op.clone(),
+				substitute(rhs),
+				operands.clone(),
+			),
+			Expression::Branched(condition, then, otherwise) => Expression::Branched(
+				substitute(condition),
+				substitute(then),
+				substitute(otherwise),
+			),
+			Expression::Matrix(rows) => Expression::Matrix(
+				rows
+					.iter()
+					.map(|row| row.iter().map(|expr| expr.substitute(bindings)).collect())
+					.collect(),
+			),
+			Expression::List(elements) => Expression::List(
+				elements
+					.iter()
+					.map(|expr| expr.substitute(bindings))
+					.collect(),
+			),
+			Expression::Index(expr, indices) => Expression::Index(substitute(expr), slices(indices)),
+			Expression::IndexedAssignment(name, indices, expr) => {
+				Expression::IndexedAssignment(name.clone(), slices(indices), substitute(expr))
+			}
+			Expression::FunctionCall(name, args) => Expression::FunctionCall(
+				name.clone(),
+				args
+					.iter()
+					.map(|(arg, range)| (arg.substitute(bindings), range.clone()))
+					.collect(),
+			),
+		}
+	}
+
+	/// Names of the identifiers the expression refers to, each once and in the
+	/// order they first appear.
+	pub fn identifiers(&self) -> Vec<String> {
+		fn indices(slices: &Slices) -> Vec<&Expression> {
+			slices
+				.iter()
+				.flat_map(|(slice, _)| match slice {
+					Slice::All => vec![],
+					Slice::Single(expr) => vec![expr],
+					Slice::Range(lower, upper) => lower.iter().chain(upper.iter()).collect(),
+				})
+				.collect()
+		}
+
+		let children: Vec<&Expression> = match self {
+			Expression::Identifier(name) => return vec![name.clone()],
+			Expression::Float(_) | Expression::Symbols(..) | Expression::FunctionDeclaration(..) => {
+				vec![]
+			}
+			Expression::Assignment(_, expr) | Expression::Abs(expr) => vec![expr],
+			Expression::Binary(lhs, _, rhs, _) => vec![lhs, rhs],
+			Expression::Branched(condition, then, otherwise) => vec![condition, then, otherwise],
+			Expression::Matrix(rows) => rows.iter().flatten().collect(),
+			Expression::List(elements) => elements.iter().collect(),
+			Expression::Index(expr, slices) => {
+				[expr.as_ref()].into_iter().chain(indices(slices)).collect()
+			}
+			Expression::IndexedAssignment(_, slices, expr) => {
+				indices(slices).into_iter().chain([expr.as_ref()]).collect()
+			}
+			Expression::FunctionCall(_, args) => args.iter().map(|(arg, _)| arg).collect(),
+		};
+
+		let mut names: Vec<String> = vec![];
+
+		for name in children.into_iter().flat_map(Expression::identifiers) {
+			if !names.contains(&name) {
+				names.push(name);
+			}
+		}
+
+		names
+	}
+
+	#[inline(always)]
+	pub fn involves(&self, name: &str) -> bool {
+		self
+			.identifiers()
+			.iter()
+			.any(|identifier| identifier == name)
+	}
+}
+
+/// How tightly an operator binds its operands, as in the parser
+#[inline(always)]
+fn op_precedence(op: &Token) -> u8 {
+	match op {
+		Token::Add | Token::Sub | Token::PlusMinus => 3,
+		Token::Mul | Token::Div | Token::Rem | Token::DotMul | Token::DotDiv => 5,
+		Token::Pow | Token::DotPow => 7,
+		_ => 1,
+	}
+}
+
+impl Expression {
+	/// Whether the expression is a number or product written with a leading minus
+	#[inline(always)]
+	pub(crate) fn is_negation(&self) -> bool {
+		match self {
+			Expression::Float(n) => n.is_sign_negative(),
+			Expression::Binary(lhs, Token::Mul | Token::Div, _, _) => lhs.is_negation(),
+			_ => false,
+		}
+	}
+
+	#[inline(always)]
+	fn precedence(&self) -> u8 {
+		match self {
+			_ if self.is_negation() => 3,
+			Expression::Binary(_, op, _, _) => op_precedence(op),
+			_ => 9,
 		}
 	}
 }
@@ -367,6 +1259,7 @@ impl Display for Expression {
 						String::new()
 					}
 				),
+				Expression::Symbols(names) => format!("sym {}", names.join(", ")),
 				Expression::FunctionDeclaration(name, params, return_type, expr, _) => format!(
 					"fn {name}({}): {return_type} = {expr}",
 					params
@@ -376,47 +1269,53 @@ impl Display for Expression {
 						.join(",")
 				),
 				Expression::Abs(expr) => format!("|{expr}|"),
-				Expression::Binary(e1, op, e2) => {
-					if *op == Token::Mul {
-						if Expression::Float(Decimal::ZERO) == *e1.to_owned()
-							|| Expression::Float(Decimal::ZERO) == *e2.to_owned()
-						{
-							String::new()
-						} else if Expression::Float(Decimal::ONE) == *e1.to_owned() {
-							format!("{e2}")
-						} else if Expression::Float(Decimal::ONE) == *e2.to_owned() {
-							format!("{e1}")
-						} else if let Expression::Float(t) = *e2.to_owned() {
-							format!("{t}{e1}")
-						} else if let Expression::Float(t) = *e1.to_owned() {
-							format!("{t}{e2}")
-						} else {
-							format!("{e1}*{e2}")
-						}
-					} else if *op == Token::Pow {
-						if Expression::Float(Decimal::ZERO) == *e2.to_owned() {
-							String::from("1")
-						} else if Expression::Float(Decimal::ONE) == *e2.to_owned() {
-							format!("{e1}")
-						} else {
-							format!("{e1}{op}{e2}")
-						}
+				Expression::Binary(lhs, op, rhs, _) => {
+					let precedence = op_precedence(op);
+
+					// Products and quotients of a negated factor read the same either way
+					let left = if lhs.precedence() < precedence
+						&& !(lhs.is_negation() && matches!(op, Token::Mul | Token::Div))
+						|| precedence == 7 && lhs.precedence() == 7
+					{
+						format!("({lhs})")
 					} else {
-						let s1 = format!("{e1}");
-						let s2 = format!("{e2}");
+						lhs.to_string()
+					};
 
-						if s1.is_empty() {
-							s2
-						} else if s2.is_empty() {
-							s1
-						} else {
-							format!("{e1}{op}{e2}")
+					let right = if rhs.precedence() < precedence
+						|| rhs.precedence() == precedence
+							&& !matches!(
+								(op, rhs.as_ref()),
+								(
+									Token::Add,
+									Expression::Binary(_, Token::Add | Token::Sub, _, _)
+								) | (
+									Token::Mul,
+									Expression::Binary(_, Token::Mul | Token::Div, _, _)
+								)
+							) {
+						format!("({rhs})")
+					} else {
+						rhs.to_string()
+					};
+
+					match (op, lhs.as_ref()) {
+						(Token::Mul, Expression::Float(n)) if *n == Decimal::NEGATIVE_ONE => {
+							format!("-{right}")
+						}
+						// A number multiplying a name or a bracket is written before it
+						(Token::Mul, Expression::Float(_))
+							if right.starts_with(|c: char| c.is_alphabetic() || c == '(') =>
+						{
+							format!("{left}{right}")
 						}
+						_ => format!("{left}{op}{right}"),
 					}
 				}
 				Expression::Branched(e1, e2, e3) => format!("if {e1} then {e2} else {e3} end"),
 				Expression::Identifier(ident) => ident.to_string(),
-				Expression::Float(n) => n.to_string(),
+				Expression::Float(n) => n.normalize().to_string(),
+				Expression::Matrix(matrix) if matrix.is_empty() || matrix[0].is_empty() => "[]".to_string(),
 				Expression::Matrix(matrix) => {
 					let mut highest_padding_required = 0;
 					let mut whitespace_index_map = vec![];
@@ -463,6 +1362,30 @@ impl Display for Expression {
 						" ".repeat(highest_padding_required),
 					)
 				}
+				Expression::List(list) => format!(
+					"{{{}}}",
+					list
+						.iter()
+						.map(|element| element.to_string())
+						.collect::<Vec<String>>()
+						.join(", ")
+				),
+				Expression::Index(expr, slices) => format!(
+					"{expr}[{}]",
+					slices
+						.iter()
+						.map(|(slice, _)| slice.to_string())
+						.collect::<Vec<String>>()
+						.join(", ")
+				),
+				Expression::IndexedAssignment(name, slices, expr) => format!(
+					"let {name}[{}] = {expr}",
+					slices
+						.iter()
+						.map(|(slice, _)| slice.to_string())
+						.collect::<Vec<String>>()
+						.join(", ")
+				),
 				Expression::FunctionCall(ident, args) => format!(
 					"{ident}({})",
 					args
@@ -0,0 +1,575 @@
+use rust_decimal::Decimal;
+
+use crate::{
+	errors::Error,
+	expr::Expression,
+	interpreter::InterpreterContext,
+	simplify::{Ratio, base_exponent, factors, map, number, power, product, ratio, sum, terms},
+	token::Token,
+	types::Data,
+};
+
+/// Most rewrites of an integral, by parts or into a sum, made in finding it,
+/// which keeps integrals that only turn into other integrals from going on
+const LARGEST_DEPTH: usize = 8;
+
+#[inline(always)]
+fn call(name: &str, args: Vec<Expression>) -> Expression {
+	Expression::FunctionCall(
+		name.to_string(),
+		args.into_iter().map(|arg| (arg, 0..0)).collect(),
+	)
+}
+
+/// `ln |u|`, whose derivative is `1/u` on either side of zero
+#[inline(always)]
+fn ln_abs(u: Expression) -> Expression {
+	call("ln", vec![Expression::Abs(Box::new(u))])
+}
+
+#[inline(always)]
+fn quotient(numerator: Expression, denominator: Expression) -> Expression {
+	product(vec![
+		numerator,
+		power(denominator, number(Decimal::NEGATIVE_ONE)),
+	])
+}
+
+/// Writes roots and `exp` as powers, so they are integrated by the rules for
+/// powers
+fn rewrite(expr: &Expression) -> Expression {
+	let expr = map(expr, rewrite);
+
+	let Expression::FunctionCall(name, args) = &expr else {
+		return expr;
+	};
+
+	match (name.as_str(), &args[..]) {
+		("sqrt", [(u, _)]) => power(
+			u.clone(),
+			quotient(number(Decimal::ONE), number(Decimal::TWO)),
+		),
+		("cbrt", [(u, _)]) => power(
+			u.clone(),
+			quotient(number(Decimal::ONE), number(Decimal::from(3))),
+		),
+		("nrt", [(u, _), (n, _)]) => power(u.clone(), quotient(number(Decimal::ONE), n.clone())),
+		("exp", [(u, _)]) => power(Expression::Identifier("e".to_string()), u.clone()),
+		_ => expr,
+	}
+}
+
+/// The inner function `u` of a factor `f(u)` along with the antiderivative of
+/// `f`, evaluated at `u`
+fn outer(factor: &Expression, x: &str) -> Option<(Expression, Expression)> {
+	let one = number(Decimal::ONE);
+
+	Some(match factor {
+		Expression::Identifier(name) if name == x => (
+			factor.clone(),
+			quotient(
+				power(factor.clone(), number(Decimal::TWO)),
+				number(Decimal::TWO),
+			),
+		),
+		Expression::Binary(base, Token::Pow, exponent, _) if !exponent.involves(x) => {
+			let u = base.as_ref().clone();
+
+			match (&u, ratio(exponent)) {
+				(_, Some(n)) if n == Ratio::ONE.neg() => (u.clone(), ln_abs(u)),
+				// 1/cos(u)^2 is the derivative of tan(u)
+				(Expression::FunctionCall(name, args), Some(n))
+					if name == "cos" && args.len() == 1 && n.integer() == Some(-2) =>
+				{
+					(args[0].0.clone(), call("tan", vec![args[0].0.clone()]))
+				}
+				_ => {
+					let raised = sum(vec![exponent.as_ref().clone(), one]);
+
+					(u.clone(), quotient(power(u, raised.clone()), raised))
+				}
+			}
+		}
+		Expression::Binary(base, Token::Pow, exponent, _) if !base.involves(x) => {
+			let u = exponent.as_ref().clone();
+
+			match base.as_ref() {
+				Expression::Identifier(name) if name == "e" => (u, factor.clone()),
+				base => (u, quotient(factor.clone(), call("ln", vec![base.clone()]))),
+			}
+		}
+		Expression::FunctionCall(name, args) if args.len() == 1 => {
+			let u = args[0].0.clone();
+
+			let integral = match name.as_str() {
+				"sin" => product(vec![
+					number(Decimal::NEGATIVE_ONE),
+					call("cos", vec![u.clone()]),
+				]),
+				"cos" => call("sin", vec![u.clone()]),
+				"tan" => product(vec![
+					number(Decimal::NEGATIVE_ONE),
+					ln_abs(call("cos", vec![u.clone()])),
+				]),
+				"sinh" => call("cosh", vec![u.clone()]),
+				"cosh" => call("sinh", vec![u.clone()]),
+				// ∫ ln u = u ln u - u
+				"ln" | "log10" => {
+					let integral = sum(vec![
+						product(vec![u.clone(), call("ln", vec![u.clone()])]),
+						product(vec![number(Decimal::NEGATIVE_ONE), u.clone()]),
+					]);
+
+					if name == "ln" {
+						integral
+					} else {
+						quotient(integral, call("ln", vec![number(Decimal::TEN)]))
+					}
+				}
+				// ∫ atan u = u atan u - ln(1 + u²) / 2
+				"atan" => sum(vec![
+					product(vec![u.clone(), call("atan", vec![u.clone()])]),
+					quotient(
+						call(
+							"ln",
+							vec![sum(vec![one, power(u.clone(), number(Decimal::TWO))])],
+						),
+						number(Decimal::from(-2)),
+					),
+				]),
+				_ => return None,
+			};
+
+			(u, integral)
+		}
+		_ => return None,
+	})
+}
+
+/// Every way of seeing a factor as `f(u)`, with a power of a call also seen as
+/// a power of `u`, the call itself
+#[inline(always)]
+fn candidates(factor: &Expression, x: &str) -> Vec<(Expression, Expression)> {
+	let mut candidates = outer(factor, x).into_iter().collect::<Vec<_>>();
+
+	if let (base @ Expression::FunctionCall(..), exponent) = base_exponent(factor.clone())
+		&& !exponent.involves(x)
+	{
+		let raised = sum(vec![exponent, number(Decimal::ONE)]);
+
+		if !ratio(&raised).is_some_and(Ratio::is_zero) {
+			candidates.push((base.clone(), quotient(power(base, raised.clone()), raised)));
+		}
+	}
+
+	candidates
+}
+
+/// Whether a factor is worth differentiating away by parts: logarithms and
+/// inverse functions before positive integer powers of the variable
+#[inline(always)]
+fn parts_rank(factor: &Expression, x: &str) -> Option<u8> {
+	let (base, exponent) = base_exponent(factor.clone());
+	let positive = ratio(&exponent)
+		.and_then(Ratio::integer)
+		.is_some_and(|n| n > 0);
+
+	match base {
+		Expression::FunctionCall(name, _)
+			if positive && ["ln", "log10", "atan"].contains(&name.as_str()) =>
+		{
+			Some(0)
+		}
+		Expression::Identifier(name) if positive && name == x => Some(1),
+		_ => None,
+	}
+}
+
+/// Integer power of sine, cosine or tangent, written with fewer of them:
+/// odd powers keep one factor for a substitution, even powers of sines and
+/// cosines are halved into cosines of twice the angle
+#[inline(always)]
+fn reduce(factor: &Expression, odd: bool) -> Option<Expression> {
+	let (base, exponent) = base_exponent(factor.clone());
+	let n = ratio(&exponent)
+		.and_then(Ratio::integer)
+		.filter(|&n| n >= 2)?;
+
+	let Expression::FunctionCall(name, args) = &base else {
+		return None;
+	};
+
+	let [(u, _)] = &args[..] else {
+		return None;
+	};
+
+	let one = number(Decimal::ONE);
+	let square = |name: &str| power(call(name, vec![u.clone()]), number(Decimal::TWO));
+	let half = |sign: Decimal| {
+		quotient(
+			sum(vec![
+				one.clone(),
+				product(vec![
+					number(sign),
+					call("cos", vec![product(vec![number(Decimal::TWO), u.clone()])]),
+				]),
+			]),
+			number(Decimal::TWO),
+		)
+	};
+
+	Some(match name.as_str() {
+		// sin²u = 1 - cos²u and cos²u = 1 - sin²u
+		"sin" | "cos" if n % 2 == 1 => {
+			let other = if name == "sin" { "cos" } else { "sin" };
+
+			product(vec![
+				base.clone(),
+				power(
+					sum(vec![
+						one,
+						product(vec![number(Decimal::NEGATIVE_ONE), square(other)]),
+					]),
+					number(Decimal::from(n / 2)),
+				),
+			])
+		}
+		// sin²u = (1 - cos 2u) / 2 and cos²u = (1 + cos 2u) / 2
+		"sin" if !odd => power(half(Decimal::NEGATIVE_ONE), number(Decimal::from(n / 2))),
+		"cos" if !odd => power(half(Decimal::ONE), number(Decimal::from(n / 2))),
+		// tan²u = 1/cos²u - 1
+		"tan" => product(vec![
+			power(base.clone(), number(Decimal::from(n - 2))),
+			sum(vec![
+				power(call("cos", vec![u.clone()]), number(Decimal::from(-2))),
+				number(Decimal::NEGATIVE_ONE),
+			]),
+		]),
+		_ => return None,
+	})
+}
+
+/// Coefficients of a polynomial in `x` from the highest power down, when they
+/// are all numbers
+#[inline(always)]
+fn rational_coefficients(f: &Expression, x: &str) -> Option<Vec<Ratio>> {
+	f.coefficients(x).ok()?.iter().map(ratio).collect()
+}
+
+/// `e^u sin v` or `e^u cos v` with linear `u` and `v`, which integrating by
+/// parts twice turns back into itself
+fn exponential_trig(
+	varying: &[Expression],
+	x: &str,
+	ctx: &mut InterpreterContext,
+) -> Result<Option<Expression>, Error> {
+	let wrt = Data::Ident(x.to_string());
+
+	let (exponential, trig) = match varying {
+		[a, b @ Expression::FunctionCall(..)] => (a, b),
+		[a @ Expression::FunctionCall(..), b] => (b, a),
+		_ => return Ok(None),
+	};
+
+	let (Expression::Binary(base, Token::Pow, u, _), Expression::FunctionCall(name, args)) =
+		(exponential, trig)
+	else {
+		return Ok(None);
+	};
+
+	let [(v, _)] = &args[..] else {
+		return Ok(None);
+	};
+
+	if base.involves(x) || (name != "sin" && name != "cos") {
+		return Ok(None);
+	}
+
+	// Rates of growth of the exponential and of the angle
+	let a = product(vec![
+		u.differentiate(&wrt, ctx)?,
+		call("ln", vec![base.as_ref().clone()]),
+	])
+	.simplify();
+	let b = v.differentiate(&wrt, ctx)?;
+
+	if a.involves(x) || b.involves(x) {
+		return Ok(None);
+	}
+
+	let (sin, cos) = (call("sin", vec![v.clone()]), call("cos", vec![v.clone()]));
+
+	// ∫ e^u sin v = e^u (a sin v - b cos v) / (a² + b²) and
+	// ∫ e^u cos v = e^u (a cos v + b sin v) / (a² + b²)
+	let combination = if name == "sin" {
+		sum(vec![
+			product(vec![a.clone(), sin]),
+			product(vec![number(Decimal::NEGATIVE_ONE), b.clone(), cos]),
+		])
+	} else {
+		sum(vec![
+			product(vec![a.clone(), cos]),
+			product(vec![b.clone(), sin]),
+		])
+	};
+
+	let two = || number(Decimal::TWO);
+
+	Ok(Some(quotient(
+		product(vec![exponential.clone(), combination]),
+		sum(vec![power(a, two()), power(b, two())]),
+	)))
+}
+
+/// `(px + r) / (ax² + bx + c)` by completing the square in the denominator
+fn quadratic(varying: &[Expression], x: &str) -> Option<Expression> {
+	let position = varying.iter().position(|factor| {
+		let (base, exponent) = base_exponent(factor.clone());
+
+		ratio(&exponent) == Some(Ratio::ONE.neg())
+			&& matches!(base, Expression::Binary(_, Token::Add | Token::Sub, _, _))
+	})?;
+
+	let (denominator, _) = base_exponent(varying[position].clone());
+	let numerator = product(
+		varying
+			.iter()
+			.enumerate()
+			.filter(|(i, _)| *i != position)
+			.map(|(_, factor)| factor.clone())
+			.collect(),
+	);
+
+	let [a, b, c] = rational_coefficients(&denominator, x)?[..] else {
+		return None;
+	};
+	let (p, r) = match rational_coefficients(&numerator, x)?[..] {
+		[r] => (Ratio::ZERO, r),
+		[p, r] => (p, r),
+		_ => return None,
+	};
+
+	let variable = Expression::Identifier(x.to_string());
+	let two_a = a.mul(Ratio::new(Decimal::TWO, Decimal::ONE)?);
+
+	// 2ax + b, the derivative of the denominator
+	let slope = sum(vec![
+		product(vec![two_a.expression(), variable]),
+		b.expression(),
+	]);
+
+	// The numerator is a multiple of the derivative plus a constant left over
+	let multiple = p.mul(two_a.recip()?);
+	let constant = r.add(multiple.mul(b).neg());
+
+	let discriminant = b
+		.mul(b)
+		.add(a.mul(c).mul(Ratio::new(Decimal::from(-4), Decimal::ONE)?));
+	let root = |d: Ratio| call("sqrt", vec![d.expression()]);
+
+	let reciprocal = if discriminant.is_zero() {
+		quotient(number(Decimal::from(-2)), slope)
+	} else if discriminant.is_negative() {
+		let root = root(discriminant.neg());
+
+		quotient(
+			product(vec![
+				number(Decimal::TWO),
+				call("atan", vec![quotient(slope, root.clone())]),
+			]),
+			root,
+		)
+	} else {
+		let root = root(discriminant);
+
+		quotient(
+			ln_abs(quotient(
+				sum(vec![
+					slope.clone(),
+					product(vec![number(Decimal::NEGATIVE_ONE), root.clone()]),
+				]),
+				sum(vec![slope, root.clone()]),
+			)),
+			root,
+		)
+	};
+
+	Some(sum(vec![
+		product(vec![multiple.expression(), ln_abs(denominator)]),
+		product(vec![constant.expression(), reciprocal]),
+	]))
+}
+
+/// Antiderivative of a sum, one term at a time
+fn integral(
+	f: &Expression,
+	x: &str,
+	ctx: &mut InterpreterContext,
+	depth: usize,
+) -> Result<Option<Expression>, Error> {
+	let mut integrals = vec![];
+
+	for term in terms(f.clone()) {
+		match term_integral(&term, x, ctx, depth)? {
+			Some(integral) => integrals.push(integral),
+			None => return Ok(None),
+		}
+	}
+
+	Ok(Some(sum(integrals)))
+}
+
+fn term_integral(
+	term: &Expression,
+	x: &str,
+	ctx: &mut InterpreterContext,
+	depth: usize,
+) -> Result<Option<Expression>, Error> {
+	let variable = Expression::Identifier(x.to_string());
+
+	if !term.involves(x) {
+		return Ok(Some(product(vec![term.clone(), variable])));
+	}
+
+	let (constant, varying): (Vec<Expression>, Vec<Expression>) = factors(term.clone())
+		.into_iter()
+		.partition(|factor| !factor.involves(x));
+	let constant = product(constant);
+	let scaled = |integral: Expression| Ok(Some(product(vec![constant.clone(), integral])));
+
+	// ∫ f(u) u' = F(u), when the rest of the product is a multiple of u'
+	for (i, factor) in varying.iter().enumerate() {
+		let rest = product(
+			varying
+				.iter()
+				.enumerate()
+				.filter(|(j, _)| *j != i)
+				.map(|(_, factor)| factor.clone())
+				.collect(),
+		);
+
+		for (u, antiderivative) in candidates(factor, x) {
+			let du = u.differentiate(&Data::Ident(x.to_string()), ctx)?;
+
+			if ratio(&du).is_some_and(Ratio::is_zero) {
+				continue;
+			}
+
+			let multiple = quotient(rest.clone(), du).simplify();
+
+			if !multiple.involves(x) {
+				return scaled(product(vec![multiple, antiderivative]));
+			}
+		}
+	}
+
+	if let Some(integral) = quadratic(&varying, x) {
+		return scaled(integral);
+	}
+
+	if let Some(integral) = exponential_trig(&varying, x, ctx)? {
+		return scaled(integral);
+	}
+
+	if depth == LARGEST_DEPTH {
+		return Ok(None);
+	}
+
+	// Powers of trigonometric functions are written with fewer of them
+	let odd = varying.iter().any(|factor| {
+		matches!(&base_exponent(factor.clone()), (Expression::FunctionCall(name, _), exponent)
+			if (name == "sin" || name == "cos")
+				&& ratio(exponent).and_then(Ratio::integer).is_some_and(|n| n >= 3 && n % 2 == 1))
+	});
+	let reduced = varying
+		.iter()
+		.map(|factor| reduce(factor, odd))
+		.collect::<Vec<Option<Expression>>>();
+
+	if reduced.iter().any(Option::is_some) {
+		let rewritten = product(
+			varying
+				.iter()
+				.zip(reduced)
+				.map(|(factor, reduced)| reduced.unwrap_or(factor.clone()))
+				.collect(),
+		);
+
+		if let Some(integral) = integral(&rewritten.expand(), x, ctx, depth + 1)? {
+			return scaled(integral);
+		}
+	}
+
+	// Products of sums are multiplied out
+	let expanded = product(varying.clone()).expand();
+
+	if terms(expanded.clone()).len() > 1
+		&& let Some(integral) = integral(&expanded, x, ctx, depth + 1)?
+	{
+		return scaled(integral);
+	}
+
+	// ∫ u v' = u v - ∫ u' v, with `u` the factor which gets simpler by
+	// differentiating
+	let Some(position) = (0..varying.len())
+		.filter(|&i| parts_rank(&varying[i], x).is_some())
+		.min_by_key(|&i| parts_rank(&varying[i], x))
+	else {
+		return Ok(None);
+	};
+
+	let u = varying[position].clone();
+	let dv = product(
+		varying
+			.iter()
+			.enumerate()
+			.filter(|(i, _)| *i != position)
+			.map(|(_, factor)| factor.clone())
+			.collect(),
+	);
+
+	// A power of the variable on its own is never integrated by parts
+	if ratio(&dv).is_some() && parts_rank(&u, x) == Some(1) {
+		return Ok(None);
+	}
+
+	let Some(v) = integral(&dv, x, ctx, depth + 1)? else {
+		return Ok(None);
+	};
+
+	let du = u.differentiate(&Data::Ident(x.to_string()), ctx)?;
+	let remaining = product(vec![v.clone(), du]).expand();
+
+	Ok(integral(&remaining, x, ctx, depth + 1)?.map(|integral| {
+		product(vec![
+			constant,
+			sum(vec![
+				product(vec![u, v]),
+				product(vec![number(Decimal::NEGATIVE_ONE), integral]),
+			]),
+		])
+	}))
+}
+
+impl Expression {
+	/// Antiderivative with respect to `x`, found by linearity, the integrals of
+	/// powers and elementary functions, substitutions `∫ f(u) u' = F(u)`,
+	/// completing the square under a quadratic and integrating by parts.
+	pub fn integrate<'a, 'b>(
+		&self,
+		x: &str,
+		ctx: &'a mut InterpreterContext<'b>,
+	) -> Result<Expression, Error>
+	where
+		'b: 'a,
+	{
+		let f = rewrite(&self.simplify()).simplify();
+
+		match integral(&f, x, ctx, 0)? {
+			Some(integral) => Ok(integral.simplify()),
+			None => Err(Error::LogicError(format!(
+				"no closed form found for the integral of `{f}` with respect to `{x}`"
+			))),
+		}
+	}
+}
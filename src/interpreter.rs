@@ -3,14 +3,20 @@ use std::{collections::HashMap, ops::Range, str::FromStr};
 pub type InterpreterContext<'a> = (
 	&'a mut HashMap<String, Variable>,
 	&'a mut HashMap<String, Function>,
+	&'a mut Random,
 );
 
 use rust_decimal::Decimal;
 
 use crate::{
-	errors::{Error, TypeError},
-	expr::Expression,
-	standardlibrary::{io, iter, math, operators},
+	errors::{Error, TypeError, ValueError},
+	expr::{Expression, Operands},
+	matrix::{Complex, Matrix},
+	random::Random,
+	standardlibrary::{
+		calculus, io, is_elementwise, is_symbolic, iter, linalg, list, math, ode, operators, optimize,
+		polynomial, probability, sparse, stats, symbolic,
+	},
 	token::Token,
 	types::{Data, DataType},
 };
@@ -19,6 +25,7 @@ use crate::{
 pub struct Interpreter {
 	pub globals: HashMap<String, Variable>,
 	pub functions: HashMap<String, Function>,
+	pub random: Random,
 }
 
 impl Default for Interpreter {
@@ -33,15 +40,16 @@ impl Interpreter {
 		let mut globals = HashMap::new();
 		let mut functions = HashMap::new();
 
-		[
+		for (global, data) in [
 			("i", Data::new_img(Decimal::ONE)),
 			("pi", Data::new_real(Decimal::PI)),
 			("π", Data::new_real(Decimal::PI)),
 			("e", Data::new_real(Decimal::E)),
-		]
-		.map(|(global, data)| globals.insert(global.to_string(), Variable::new(data, true)));
+		] {
+			globals.insert(global.to_string(), Variable::new(data, true));
+		}
 
-		[
+		for name in [
 			"print",
 			"read",
 			"int",
@@ -59,6 +67,7 @@ impl Interpreter {
 			"lt",
 			"lteq",
 			"abs",
+			"sign",
 			"round",
 			"ceil",
 			"floor",
@@ -76,32 +85,115 @@ impl Interpreter {
 			"sqrt",
 			"cbrt",
 			"nrt",
+			"gamma",
+			"lgamma",
+			"beta",
+			"erf",
+			"erfc",
+			"besselj",
+			"bessely",
+			"zeta",
+			"digamma",
 			"graph",
 			"transpose",
 			"determinant",
 			"adj",
 			"inverse",
+			"solve",
+			"lu",
+			"qr",
+			"rank",
+			"rref",
+			"nullspace",
+			"trace",
+			"identity",
+			"zeros",
+			"ones",
+			"eigvals",
+			"eig",
+			"svd",
+			"norm",
+			"cond",
+			"pinv",
+			"sparse",
+			"dense",
+			"mean",
+			"median",
+			"mode",
+			"variance",
+			"pvariance",
+			"stddev",
+			"pstddev",
+			"quantile",
+			"min",
+			"max",
+			"cov",
+			"corr",
+			"linreg",
+			"polyfit",
+			"seed",
+			"rand",
+			"randn",
+			"randint",
+			"pdf",
+			"cdf",
 			"sum",
 			"prod",
 			"map",
 			"differentiate",
+			"diff",
+			"grad",
+			"jacobian",
+			"hessian",
+			"simplify",
+			"trigsimp",
+			"expand",
+			"factor",
+			"collect",
+			"coeffs",
+			"subs",
+			"eval",
+			"integrate",
+			"limit",
+			"taylor",
 			"quadroot",
-		]
-		.map(|name| {
+			"roots",
+			"poly",
+			"degree",
+			"divmod",
+			"gcd",
+			"findroot",
+			"bisect",
+			"ode",
+			"minimize",
+			"maximize",
+			"len",
+			"push",
+			"concat",
+			"range",
+			"linspace",
+			"reverse",
+			"sort",
+			"unique",
+		] {
 			functions.insert(
 				name.to_string(),
 				Function::STD(STDFunction {
 					name: name.to_string(),
 				}),
-			)
-		});
+			);
+		}
 
-		Self { globals, functions }
+		Self {
+			globals,
+			functions,
+			random: Random::default(),
+		}
 	}
 
 	#[inline(always)]
 	pub fn interpret(&mut self, ast: Vec<(Expression, Range<usize>)>) -> Result<(), Error> {
-		let ctx = &mut (&mut self.globals, &mut self.functions);
+		let ctx = &mut (&mut self.globals, &mut self.functions, &mut self.random);
 
 		for (expr, range) in ast {
 			expr.evaluate(ctx, range)?;
@@ -182,17 +274,35 @@ impl UserDefinedFunction {
 	pub fn execute<'a, 'b>(
 		&self,
 		ctx: &'a mut InterpreterContext<'b>,
-		mut args: Vec<Data>,
+		args: Vec<Data>,
 	) -> Result<Data, Error>
 	where
 		'b: 'a,
 	{
-		let mut param_names = vec![];
+		if args.len() != self.params.len() {
+			return Err(
+				ValueError::new(
+					format!(
+						"expected {} argument{}, found {}",
+						self.params.len(),
+						if self.params.len() == 1 { "" } else { "s" },
+						args.len()
+					),
+					"give a value for every parameter of the function",
+					0..0,
+				)
+				.to_error(),
+			);
+		}
 
-		for (i, (arg, numbertype)) in self.params.iter().enumerate() {
-			let r = args.remove(i);
+		let mut param_names = vec![];
 
-			if r.ty() != *numbertype {
+		for ((arg, numbertype), r) in self.params.iter().zip(args) {
+			// Measured values are numbers carrying an uncertainty, and names without
+			// values, expressions and polynomials stand for numbers
+			if r.ty() != *numbertype
+				&& !(*numbertype == DataType::Number && (r.ty() == DataType::Measured || r.is_symbolic()))
+			{
 				return Err(TypeError::new(*numbertype, r.ty(), 0..0).to_error());
 			}
 
@@ -240,7 +350,6 @@ pub struct STDFunction {
 }
 
 impl STDFunction {
-	#[inline(always)]
 	pub fn execute<'a, 'b>(
 		&self,
 		ctx: &'a mut InterpreterContext<'b>,
@@ -249,125 +358,483 @@ impl STDFunction {
 	where
 		'b: 'a,
 	{
+		if args.iter().any(|arg| arg.ty() == DataType::Measured)
+			&& args
+				.iter()
+				.all(|arg| [DataType::Number, DataType::Measured].contains(&arg.ty()))
+			&& ![
+				"print", "add", "sub", "mul", "div", "pow", "abs", "is_eq", "neq", "gt", "gteq", "lt",
+				"lteq",
+			]
+			.contains(&self.name.as_str())
+		{
+			return self.propagate(ctx, args);
+		}
+
+		if is_symbolic(&self.name)
+			&& args.iter().any(Data::is_symbolic)
+			&& let Some(args) = args
+				.iter()
+				.map(Expression::from_data)
+				.collect::<Option<Vec<Expression>>>()
+		{
+			return Ok(Data::Expression(Expression::FunctionCall(
+				self.name.clone(),
+				args.into_iter().map(|arg| (arg, 0..0)).collect(),
+			)));
+		}
+
+		if is_elementwise(&self.name)
+			&& matches!(
+				args[0],
+				Data::Matrix(..) | Data::Sparse(..) | Data::List(..)
+			) {
+			return self.elementwise(ctx, args);
+		}
+
 		Ok(match self.name.as_str() {
 			"print" => io::print(args),
 			"read" => io::read(ctx)?,
-			"add" => operators::add(&args[0], &args[1]),
-			"sub" => operators::sub(&args[0], &args[1]),
-			"mul" => operators::mul(&args[0], &args[1]),
-			"div" => operators::div(&args[0], &args[1]),
-			"pow" => operators::pow(&args[0], &args[1]),
-			"rem" => operators::rem(&args[0], &args[1]),
-			"is_eq" => operators::is_eq(&args[0], &args[1]),
-			"neq" => operators::neq(&args[0], &args[1]),
-			"gt" => operators::gt(&args[0], &args[1]),
-			"gteq" => operators::gteq(&args[0], &args[1]),
-			"lt" => operators::lt(&args[0], &args[1]),
-			"lteq" => operators::lteq(&args[0], &args[1]),
-			"abs" => math::abs(&args[0]),
-			"round" => math::round(&args[0]),
-			"ceil" => math::ceil(&args[0]),
-			"floor" => math::floor(&args[0]),
-			"exp" => math::exp(&args[0]),
+			"add" => operators::add(&args[0], &args[1])?,
+			"sub" => operators::sub(&args[0], &args[1])?,
+			"mul" => operators::mul(&args[0], &args[1])?,
+			"div" => operators::div(&args[0], &args[1])?,
+			"pow" => operators::pow(&args[0], &args[1])?,
+			"rem" => operators::rem(&args[0], &args[1])?,
+			"is_eq" => operators::is_eq(&args[0], &args[1])?,
+			"neq" => operators::neq(&args[0], &args[1])?,
+			"gt" => operators::gt(&args[0], &args[1])?,
+			"gteq" => operators::gteq(&args[0], &args[1])?,
+			"lt" => operators::lt(&args[0], &args[1])?,
+			"lteq" => operators::lteq(&args[0], &args[1])?,
+			"abs" => math::abs(&args[0])?,
+			"sign" => math::sign(&args[0])?,
+			"round" => math::round(&args[0])?,
+			"ceil" => math::ceil(&args[0])?,
+			"floor" => math::floor(&args[0])?,
+			"exp" => math::exp(&args[0])?,
 			"ln" => math::ln(&args[0]),
-			"log10" => math::log10(&args[0]),
-			"log" => math::log(&args[0], &args[1]),
-			"sin" => math::sin(&args[0]),
-			"sinh" => math::sinh(&args[0]),
-			"cos" => math::cos(&args[0]),
-			"cosh" => math::cosh(&args[0]),
-			"tan" => math::tan(&args[0]),
+			"log10" => math::log10(&args[0])?,
+			"log" => math::log(&args[0], &args[1])?,
+			"sin" => math::sin(&args[0])?,
+			"sinh" => math::sinh(&args[0])?,
+			"cos" => math::cos(&args[0])?,
+			"cosh" => math::cosh(&args[0])?,
+			"tan" => math::tan(&args[0])?,
 			"atan" => math::atan(&args[0]),
 			"atan2" => math::atan2(&args[0], &args[0]),
 			"sqrt" => math::sqrt(&args[0]),
-			"nrt" => math::nrt(&args[0], &args[1]),
-			"transpose" => math::transpose(&args[0]),
-			"determinant" => math::determinant(&args[0]),
-			"adj" => math::adj(&args[0]),
-			"inverse" => math::inverse(&args[0]),
+			"cbrt" => math::nrt(&args[0], &Data::new_real(Decimal::from(3)))?,
+			"nrt" => math::nrt(&args[0], &args[1])?,
+			"gamma" => math::gamma(&args[0])?,
+			"lgamma" => math::lgamma(&args[0])?,
+			"beta" => math::beta(&args[0], &args[1])?,
+			"erf" => math::erf(&args[0])?,
+			"erfc" => math::erfc(&args[0])?,
+			"besselj" => math::besselj(&args[0], &args[1])?,
+			"bessely" => math::bessely(&args[0], &args[1])?,
+			"zeta" => math::zeta(&args[0])?,
+			"digamma" => math::digamma(&args[0])?,
+			"transpose" => math::transpose(&args[0])?,
+			"determinant" => linalg::determinant(&args[0])?,
+			"adj" => math::adj(&args[0])?,
+			"inverse" => linalg::inverse(&args[0])?,
+			"solve" if !matches!(args[0], Data::Matrix(..) | Data::Sparse(..)) => {
+				symbolic::solve(&args[0], &args[1], ctx)?
+			}
+			"solve" if args[0].ty() == DataType::Sparse => sparse::solve(&args[0], &args[1])?,
+			"solve" => linalg::solve(&args[0], &args[1])?,
+			"lu" => linalg::lu(&args[0])?,
+			"qr" => linalg::qr(&args[0])?,
+			"rank" => linalg::rank(&args[0])?,
+			"rref" => linalg::rref(&args[0])?,
+			"nullspace" => linalg::nullspace(&args[0])?,
+			"trace" => linalg::trace(&args[0])?,
+			"identity" => linalg::identity(&args[0])?,
+			"zeros" => linalg::zeros(&args[0], &args[1])?,
+			"ones" => linalg::ones(&args[0], &args[1])?,
+			"eigvals" => linalg::eigvals(&args[0])?,
+			"eig" => linalg::eig(&args[0])?,
+			"svd" => linalg::svd(&args[0])?,
+			"norm" if args.len() == 1 => linalg::frobenius(&args[0])?,
+			"norm" => linalg::norm(&args[0], &args[1])?,
+			"cond" => linalg::cond(&args[0])?,
+			"pinv" => linalg::pinv(&args[0])?,
+			"sparse" if args.len() == 1 => sparse::from_dense(&args[0])?,
+			"sparse" => sparse::sparse(&args[0], &args[1], &args[2], &args[3], &args[4])?,
+			"dense" => sparse::dense(&args[0])?,
+			"mean" => stats::mean(&args[0])?,
+			"median" => stats::median(&args[0])?,
+			"mode" => stats::mode(&args[0])?,
+			"variance" => stats::variance(&args[0])?,
+			"pvariance" => stats::pvariance(&args[0])?,
+			"stddev" => stats::stddev(&args[0])?,
+			"pstddev" => stats::pstddev(&args[0])?,
+			"min" => stats::min(&args[0])?,
+			"max" => stats::max(&args[0])?,
+			"quantile" if args[0].ty() == DataType::Ident => probability::quantile(&args)?,
+			"quantile" => stats::quantile(&args[0], &args[1])?,
+			"cov" => stats::cov(&args[0], &args[1])?,
+			"corr" => stats::corr(&args[0], &args[1])?,
+			"linreg" => stats::linreg(&args[0], &args[1])?,
+			"polyfit" => stats::polyfit(&args[0], &args[1], &args[2])?,
+			"seed" => probability::seed(&args[0], ctx)?,
+			"rand" => probability::rand(&args, ctx)?,
+			"randn" => probability::randn(&args, ctx)?,
+			"randint" => probability::randint(&args[0], &args[1], ctx)?,
+			"pdf" => probability::pdf(&args)?,
+			"cdf" => probability::cdf(&args)?,
 			"graph" => math::graph(&args[0], ctx)?,
+			"sum" if args.len() == 1 => list::sum(&args[0])?,
 			"sum" => math::sum(&args[0], &args[1], &args[2], ctx)?,
+			"prod" if args.len() == 1 => list::prod(&args[0])?,
 			"prod" => math::prod(&args[0], &args[1], &args[2], ctx)?,
 			"map" => iter::map(&args[0], &args[1], ctx)?,
 			"differentiate" => math::differentiate(&args[0], &args[1], ctx)?,
+			"diff" if args[0].ty() == DataType::Polynomial => polynomial::derivative(&args)?,
+			"diff" => calculus::diff(&args, ctx)?,
+			"grad" => calculus::grad(&args, ctx)?,
+			"jacobian" => calculus::jacobian(&args, ctx)?,
+			"hessian" => calculus::hessian(&args, ctx)?,
+			"simplify" => symbolic::simplify(&args[0], ctx)?,
+			"trigsimp" => symbolic::trigsimp(&args[0], ctx)?,
+			"expand" => symbolic::expand(&args[0], ctx)?,
+			"factor" => symbolic::factor(&args[0], ctx)?,
+			"collect" => symbolic::collect(&args[0], &args[1], ctx)?,
+			"coeffs" if args.len() == 1 => polynomial::coeffs(&args[0])?,
+			"coeffs" => symbolic::coeffs(&args[0], &args[1], ctx)?,
+			"subs" => symbolic::subs(&args[0], &args[1], &args[2], ctx)?,
+			"eval" if args[0].ty() == DataType::Polynomial => polynomial::eval(&args[0], &args[1], ctx)?,
+			"eval" => symbolic::eval(&args[0], &args[1..], ctx)?,
+			"integrate" if args[0].ty() == DataType::Polynomial => polynomial::integrate(&args)?,
+			"integrate" if args.len() == 2 => calculus::antiderivative(&args[0], &args[1], ctx)?,
+			"integrate" => math::integrate(&args[0], &args[1], &args[2], ctx)?,
+			"limit" => calculus::limit(&args, ctx)?,
+			"taylor" => calculus::taylor(&args, ctx)?,
+			"quadroot" if args.len() == 3 => math::quadratic(&args[0], &args[1], &args[2])?,
 			"quadroot" => math::quadroot(&args[0], ctx)?,
+			"roots" if args[0].ty() == DataType::Polynomial => polynomial::roots(&args[0])?,
+			"roots" => math::roots(&args[0])?,
+			"poly" => polynomial::poly(&args, ctx)?,
+			"degree" => polynomial::degree(&args[0])?,
+			"divmod" => polynomial::divmod(&args[0], &args[1])?,
+			"gcd" => polynomial::gcd(&args[0], &args[1])?,
+			"findroot" => math::findroot(&args[0], &args[1], ctx)?,
+			"bisect" => math::bisect(&args[0], &args[1], &args[2], ctx)?,
+			"ode" if args.len() == 4 => ode::dormand_prince(&args[0], &args[1], &args[2], &args[3], ctx)?,
+			"ode" => ode::rk4(&args[0], &args[1], &args[2], &args[3], &args[4], ctx)?,
+			"minimize" => optimize::minimize(&args, ctx)?,
+			"maximize" => optimize::maximize(&args, ctx)?,
+			"len" => list::len(&args[0])?,
+			"push" => list::push(&args[0], &args[1])?,
+			"concat" => list::concat(&args[0], &args[1])?,
+			"range" if args.len() == 2 => list::range(&args[0], &args[1], &Data::new_real(Decimal::ONE))?,
+			"range" => list::range(&args[0], &args[1], &args[2])?,
+			"linspace" => list::linspace(&args[0], &args[1], &args[2])?,
+			"reverse" => list::reverse(&args[0])?,
+			"sort" => list::sort(&args[0])?,
+			"unique" => list::unique(&args[0])?,
 			_ => unreachable!(),
 		})
 	}
 
+	/// Applies the function to every element of the matrix or list given as its
+	/// first argument, the remaining arguments are passed along unchanged. Sparse
+	/// matrices are made dense, as the function need not keep zeros zero.
+	fn elementwise<'a, 'b>(
+		&self,
+		ctx: &'a mut InterpreterContext<'b>,
+		args: Vec<Data>,
+	) -> Result<Data, Error>
+	where
+		'b: 'a,
+	{
+		let mut apply = |element: Data| {
+			let mut args = args.clone();
+			args[0] = element;
+
+			self.execute(ctx, args)
+		};
+
+		Ok(match &args[0] {
+			Data::Sparse(matrix) => {
+				let mut args = args.clone();
+				args[0] = Data::Matrix(matrix.to_dense());
+
+				return self.elementwise(ctx, args);
+			}
+			Data::Matrix(matrix) => Data::Matrix(Matrix::new(
+				matrix.rows(),
+				matrix.cols(),
+				matrix
+					.elements()
+					.iter()
+					.map(|&z| Complex::from_data(&apply(Data::from(z))?))
+					.collect::<Result<_, _>>()?,
+			)),
+			Data::List(list) => Data::List(
+				list
+					.iter()
+					.map(|element| apply(element.clone()))
+					.collect::<Result<_, _>>()?,
+			),
+			_ => unreachable!(),
+		})
+	}
+
+	/// Evaluates the function at the nominal values of its arguments and carries
+	/// their uncertainty through using the derivative of the function.
+	fn propagate<'a, 'b>(
+		&self,
+		ctx: &'a mut InterpreterContext<'b>,
+		args: Vec<Data>,
+	) -> Result<Data, Error>
+	where
+		'b: 'a,
+	{
+		let nominal_args = args
+			.iter()
+			.map(|arg| match arg {
+				Data::Measured(value, _) => Data::new_real(*value),
+				arg => arg.clone(),
+			})
+			.collect::<Vec<Data>>();
+
+		// Uncertainties are only carried by real numbers, so a function which
+		// leaves the real line here, eg `sqrt` of a negative value, is rejected
+		let nominal = match self.execute(ctx, nominal_args.clone())? {
+			Data::Number(value, im) if im.is_zero() => value,
+			nominal => {
+				return Err(
+					ValueError::new(
+						format!(
+							"cannot propagate uncertainty through `{}`, as its value `{nominal}` is not a real number",
+							self.name
+						),
+						"measured values can only be passed to functions with real values at them",
+						0..0,
+					)
+					.to_error(),
+				);
+			}
+		};
+
+		let at = nominal_args
+			.iter()
+			.map(|arg| Expression::from_data(arg).map(|arg| (arg, 0..0)))
+			.collect::<Option<Vec<_>>>()
+			.unwrap();
+
+		let mut terms = vec![];
+
+		for (i, arg) in args.iter().enumerate() {
+			let Data::Measured(value, sigma) = arg else {
+				continue;
+			};
+
+			if sigma.is_zero() {
+				continue;
+			}
+
+			let partial = self.partial(i, &at).map_err(|_| {
+				ValueError::new(
+					format!("cannot propagate uncertainty through `{}`", self.name),
+					"the function has no derivative with respect to this argument",
+					0..0,
+				)
+				.to_error()
+			})?;
+
+			let partial = match partial.evaluate(ctx, 0..0) {
+				Ok(Data::Number(partial, im)) if im.is_zero() => partial,
+				_ => return Err(operators::undefined_derivative(&self.name, *value)),
+			};
+
+			terms.push((partial, *sigma));
+		}
+
+		Ok(operators::propagate(nominal, &terms))
+	}
+
+	/// Derivative of a call to the function with respect to `wrt`, where the
+	/// function is called on the variable itself when no arguments are given.
 	#[inline(always)]
 	pub fn differentiate<'a, 'b>(
 		&self,
 		wrt: &Data,
 		args: &[(Expression, Range<usize>)],
-		_ctx: &'a mut InterpreterContext<'b>,
+		ctx: &'a mut InterpreterContext<'b>,
 	) -> Result<Data, Error>
 	where
 		'b: 'a,
 	{
-		let Data::Ident(_) = wrt else {
+		let Data::Ident(name) = wrt else {
 			return Err(Error::LogicError(
 				"expected variable to differentiate".to_string(),
 			));
 		};
 
-		Ok(match self.name.as_str() {
-			"exp" => Data::Expression(Expression::FunctionCall("exp".to_string(), args.to_vec())),
-			"ln" => Data::Expression(Expression::Binary(
-				Box::new(Expression::Float(Decimal::ONE)),
+		let args = if args.is_empty() {
+			vec![(Expression::Identifier(name.clone()), 0..0)]
+		} else {
+			args.to_vec()
+		};
+
+		Ok(Data::Expression(
+			Expression::FunctionCall(self.name.clone(), args).differentiate(wrt, ctx)?,
+		))
+	}
+
+	/// Partial derivative of a call to the function with respect to its `i`-th
+	/// argument.
+	#[inline(always)]
+	pub fn partial(
+		&self,
+		i: usize,
+		args: &[(Expression, Range<usize>)],
+	) -> Result<Expression, Error> {
+		let arg = |i: usize| args[i].0.clone();
+		let call = |name: &str, args: Vec<Expression>| {
+			Expression::FunctionCall(
+				name.to_string(),
+				args.into_iter().map(|arg| (arg, 0..0)).collect(),
+			)
+		};
+		let binary =
+			|lhs, op, rhs| Expression::Binary(Box::new(lhs), op, Box::new(rhs), Operands::default());
+		let float = |n: Decimal| Expression::Float(n);
+		let square = |x| binary(x, Token::Pow, float(Decimal::TWO));
+
+		Ok(match (self.name.as_str(), i) {
+			("exp", 0) => call("exp", vec![arg(0)]),
+			("ln", 0) => binary(float(Decimal::ONE), Token::Div, arg(0)),
+			("log10", 0) => binary(
+				float(Decimal::from_str("0.4342944819032518276511289188").unwrap()),
 				Token::Div,
-				Box::new(args[0].0.clone()),
-			)),
-			"log10" => Data::Expression(Expression::Binary(
-				Box::new(Expression::Float(
-					Decimal::from_str("0.4342944819032518276511289188").unwrap(),
-				)),
+				arg(0),
+			),
+			// log(a, b) = ln a / ln b
+			("log", 0) => binary(
+				float(Decimal::ONE),
 				Token::Div,
-				Box::new(args[0].0.clone()),
-			)),
-			"sin" => Data::Expression(Expression::FunctionCall("cos".to_string(), args.to_vec())),
-			"cos" => Data::Expression(Expression::Binary(
-				Box::new(Expression::Float(Decimal::NEGATIVE_ONE)),
+				binary(arg(0), Token::Mul, call("ln", vec![arg(1)])),
+			),
+			("log", 1) => binary(
+				binary(
+					float(Decimal::NEGATIVE_ONE),
+					Token::Mul,
+					call("ln", vec![arg(0)]),
+				),
+				Token::Div,
+				binary(arg(1), Token::Mul, square(call("ln", vec![arg(1)]))),
+			),
+			("sin", 0) => call("cos", vec![arg(0)]),
+			("cos", 0) => binary(
+				float(Decimal::NEGATIVE_ONE),
 				Token::Mul,
-				Box::new(Expression::FunctionCall("sin".to_string(), args.to_vec())),
-			)),
-			"sinh" => Data::Expression(Expression::FunctionCall("cosh".to_string(), args.to_vec())),
-			"cosh" => Data::Expression(Expression::FunctionCall("sinh".to_string(), args.to_vec())),
-			"tan" => Data::Expression(Expression::Binary(
-				Box::new(Expression::Float(Decimal::ONE)),
+				call("sin", vec![arg(0)]),
+			),
+			("sinh", 0) => call("cosh", vec![arg(0)]),
+			("cosh", 0) => call("sinh", vec![arg(0)]),
+			("tan", 0) => binary(
+				float(Decimal::ONE),
 				Token::Div,
-				Box::new(Expression::Binary(
-					Box::new(Expression::FunctionCall("cos".to_string(), args.to_vec())),
-					Token::Pow,
-					Box::new(Expression::Float(Decimal::TWO)),
-				)),
-			)),
-			"atan" => Data::Expression(Expression::Binary(
-				Box::new(Expression::Float(Decimal::ONE)),
+				square(call("cos", vec![arg(0)])),
+			),
+			("atan", 0) => binary(
+				float(Decimal::ONE),
 				Token::Div,
-				Box::new(Expression::Binary(
-					Box::new(Expression::Float(Decimal::ONE)),
-					Token::Add,
-					Box::new(Expression::Binary(
-						Box::new(args[0].0.clone()),
-						Token::Pow,
-						Box::new(Expression::Float(Decimal::TWO)),
-					)),
-				)),
-			)),
-			"sqrt" => Data::Expression(Expression::Binary(
-				Box::new(Expression::Float(Decimal::ONE)),
+				binary(float(Decimal::ONE), Token::Add, square(arg(0))),
+			),
+			("sqrt", 0) => binary(
+				float(Decimal::ONE),
 				Token::Div,
-				Box::new(Expression::Binary(
-					Box::new(Expression::Float(Decimal::TWO)),
+				binary(float(Decimal::TWO), Token::Mul, call("sqrt", vec![arg(0)])),
+			),
+			("cbrt", 0) => binary(
+				float(Decimal::ONE),
+				Token::Div,
+				binary(
+					float(Decimal::from(3)),
 					Token::Mul,
-					Box::new(Expression::FunctionCall("sqrt".to_string(), args.to_vec())),
-				)),
-			)),
+					square(call("cbrt", vec![arg(0)])),
+				),
+			),
+			// nrt(a, n) = a^(1 / n)
+			("nrt", 0) => binary(
+				call("nrt", vec![arg(0), arg(1)]),
+				Token::Div,
+				binary(arg(1), Token::Mul, arg(0)),
+			),
+			("nrt", 1) => binary(
+				binary(
+					binary(
+						float(Decimal::NEGATIVE_ONE),
+						Token::Mul,
+						call("nrt", vec![arg(0), arg(1)]),
+					),
+					Token::Mul,
+					call("ln", vec![arg(0)]),
+				),
+				Token::Div,
+				square(arg(1)),
+			),
+			("abs", 0) => call("sign", vec![arg(0)]),
+			("sign" | "round" | "ceil" | "floor", 0) => float(Decimal::ZERO),
+			("erf" | "erfc", 0) => binary(
+				float(
+					Decimal::from_str("1.1283791670955125738961589031").unwrap()
+						* if self.name == "erf" {
+							Decimal::ONE
+						} else {
+							Decimal::NEGATIVE_ONE
+						},
+				),
+				Token::Mul,
+				call(
+					"exp",
+					vec![binary(
+						float(Decimal::NEGATIVE_ONE),
+						Token::Mul,
+						square(arg(0)),
+					)],
+				),
+			),
+			("gamma", 0) => binary(
+				call("gamma", vec![arg(0)]),
+				Token::Mul,
+				call("digamma", vec![arg(0)]),
+			),
+			("lgamma", 0) => call("digamma", vec![arg(0)]),
+			// ∂B(a, b)/∂a = B(a, b)(ψ(a) - ψ(a + b))
+			("beta", 0 | 1) => binary(
+				call("beta", vec![arg(0), arg(1)]),
+				Token::Mul,
+				binary(
+					call("digamma", vec![arg(i)]),
+					Token::Sub,
+					call("digamma", vec![binary(arg(0), Token::Add, arg(1))]),
+				),
+			),
+			// Only the argument has a derivative, not the order
+			("besselj" | "bessely", 1) => {
+				let order = |op| binary(arg(0), op, float(Decimal::ONE));
+
+				binary(
+					binary(
+						call(&self.name, vec![order(Token::Sub), arg(1)]),
+						Token::Sub,
+						call(&self.name, vec![order(Token::Add), arg(1)]),
+					),
+					Token::Div,
+					float(Decimal::TWO),
+				)
+			}
 			_ => {
-				return Err(Error::LogicError(
-					"attempt to differentiate standard library function which has no derivative".to_string(),
-				));
+				return Err(Error::LogicError(format!(
+					"attempt to differentiate standard library function `{}` which has no derivative",
+					self.name
+				)));
 			}
 		})
 	}
@@ -15,20 +15,18 @@ impl<'a> Lexer<'a> {
 		let mut location = 1;
 		let mut tokeninfos = vec![];
 
+		// Ranges are counted from the start of the contents, so each line starts
+		// one past the newline ending the line before it
 		for line in self.contents.lines() {
-			if line.starts_with("//") || line.is_empty() {
-				location += 1;
-
-				continue;
-			}
+			let start = location;
 
-			let tokens = self.tokenize_line(line, location);
+			location += line.len() + 1;
 
-			if let Some(token) = tokens.last() {
-				location += token.range.end;
+			if line.starts_with("//") || line.is_empty() {
+				continue;
 			}
 
-			tokeninfos.push(tokens);
+			tokeninfos.push(self.tokenize_line(line, start));
 		}
 
 		tokeninfos
@@ -78,9 +76,14 @@ impl<'a> Lexer<'a> {
 				let to_insert_mul;
 
 				loop {
+					// A dot only continues the number when a digit follows it, so that
+					// ranges like `1..3` are split into separate tokens
+					let is_fraction = line.clone().next() == Some('.')
+						&& line.clone().nth(1).is_some_and(|c| c.is_ascii_digit());
+
 					let char = line.peek();
 
-					if char.is_none() || (!char.unwrap().is_ascii_digit() && *char.unwrap() != '.') {
+					if char.is_none() || (!char.unwrap().is_ascii_digit() && !is_fraction) {
 						to_insert_mul = char.is_some() && char.unwrap().is_ascii_alphabetic();
 						break;
 					}
@@ -106,6 +109,11 @@ impl<'a> Lexer<'a> {
 			} else {
 				token.push(char);
 
+				// Dots either form a range or mark an element-wise operator
+				if char == '.' && matches!(line.peek(), Some('.' | '*' | '/' | '^')) {
+					token.push(line.next().unwrap());
+				}
+
 				let punctuation = ['.', '(', ')', '{', '}', '[', ']', '|', ',', ';'];
 
 				loop {
@@ -124,11 +132,21 @@ impl<'a> Lexer<'a> {
 
 					token.push(char.unwrap());
 				}
-				let size = token.len();
+				// A run of symbols is split into the longest operators it starts with
+				// in turn, so `+-` is always `±` while `*-` is `*` followed by `-`
+				let mut rest = token.as_str();
 
-				tokens.push(TokenInfo::new(token, c..c + size));
+				while let Some(first) = rest.chars().next() {
+					let size = ["!=", "==", ">=", "<=", "+-", ".*", "./", ".^", ".."]
+						.into_iter()
+						.find(|operator| rest.starts_with(operator))
+						.map_or(first.len_utf8(), str::len);
 
-				c += size;
+					tokens.push(TokenInfo::new(rest[..size].to_string(), c..c + size));
+
+					rest = &rest[size..];
+					c += size;
+				}
 			}
 		}
 
@@ -1,8 +1,16 @@
+mod algebra;
 pub mod errors;
 pub mod expr;
+mod integral;
 pub mod interpreter;
 pub mod lexer;
+pub mod matrix;
 pub mod parser;
+mod polynomial;
+pub mod random;
+mod series;
+mod simplify;
+mod solve;
 mod standardlibrary;
 mod token;
 mod types;
@@ -59,20 +67,1073 @@ pub fn run(input: &str, debug: bool, time: bool) {
 
 #[cfg(test)]
 mod tests {
-	use crate::run;
+	use rust_decimal::{Decimal, dec};
+
+	use crate::{
+		errors::Error,
+		expr::Expression,
+		interpreter::{Function, Interpreter, InterpreterContext},
+		lexer::Lexer,
+		matrix::Matrix,
+		parser::Parser,
+		run,
+		types::Data,
+	};
+
+	fn interpret(contents: &str) -> Result<(), Error> {
+		let tokens = Lexer::new(contents).tokens();
+
+		Interpreter::new().interpret(Parser::new(&tokens).ast()?)
+	}
+
+	/// Interpreter left behind by running `contents`, to inspect its globals and
+	/// functions
+	fn interpreted(contents: &str) -> Interpreter {
+		let tokens = Lexer::new(contents).tokens();
+		let mut interpreter = Interpreter::new();
+
+		interpreter
+			.interpret(Parser::new(&tokens).ast().unwrap())
+			.unwrap();
+
+		interpreter
+	}
+
+	/// Numbers held by `data` in order, each rounded to `dp` decimal places to
+	/// compare results which are found numerically
+	fn rounded(data: &Data, dp: u32) -> Vec<String> {
+		match data {
+			Data::Number(re, im) => {
+				vec![Data::Number(re.round_dp(dp).normalize(), im.round_dp(dp).normalize()).to_string()]
+			}
+			Data::List(list) => list.iter().flat_map(|data| rounded(data, dp)).collect(),
+			Data::Matrix(matrix) => matrix
+				.elements()
+				.iter()
+				.flat_map(|&z| rounded(&Data::from(z), dp))
+				.collect(),
+			_ => unreachable!(),
+		}
+	}
+
+	/// Runs `source` after `setup` and checks the numbers held by `r`, rounded to
+	/// `dp` decimal places
+	fn assert_rounded(setup: &str, cases: &[(&str, &[&str])], dp: u32) {
+		for (source, expected) in cases {
+			let interpreter = interpreted(&format!("{setup}\nlet r = {source}"));
+
+			assert_eq!(
+				rounded(&interpreter.globals["r"].value, dp),
+				*expected,
+				"{source}"
+			);
+		}
+	}
+
+	/// Globals, functions and random numbers of `interpreter` to evaluate in
+	fn context(interpreter: &mut Interpreter) -> InterpreterContext<'_> {
+		(
+			&mut interpreter.globals,
+			&mut interpreter.functions,
+			&mut interpreter.random,
+		)
+	}
+
+	/// The first expression of `source`
+	fn parse(source: &str) -> Expression {
+		let tokens = Lexer::new(source).tokens();
+
+		Parser::new(&tokens).ast().unwrap()[0].0.clone()
+	}
 
 	#[test]
 	fn assignment() {
 		run("tests/assignment.cal", false, false);
+
+		for source in ["let x: foo = 1", "fn f(x: q) = x", "fn f(x): q = x"] {
+			assert!(
+				matches!(interpret(source), Err(Error::SyntaxError(..))),
+				"{source}"
+			);
+		}
 	}
 
 	#[test]
 	fn function_declaration() {
 		run("tests/function_declaration.cal", false, false);
+
+		for source in ["f(1)", "f(1, 2, 3)"] {
+			let error = interpret(&format!("fn f(x, y) = x + y\nprint({source})")).unwrap_err();
+
+			assert!(matches!(error, Error::ValueError(..)), "{source}");
+			assert_ne!(error.range(), 0..0, "{source}");
+		}
 	}
 
 	#[test]
 	fn matrix() {
 		run("tests/matrix.cal", false, false);
+
+		// Elements are complex numbers, so measured values and lists are rejected
+		for source in ["let g = 1 +- 0.1\nprint([g, 1])", "print([{1, 2}, 3])"] {
+			assert!(
+				matches!(interpret(source), Err(Error::TypeError(..))),
+				"{source}"
+			);
+		}
+	}
+
+	#[test]
+	fn index() {
+		run("tests/index.cal", false, false);
+
+		// Assignments to a slice of the wrong shape or to an undefined variable
+		// point at the assignment
+		for source in [
+			"let A = [1, 2; 3, 4]\nlet A[1, :] = [1, 2, 3]",
+			"let l = {1, 2, 3}\nlet l[1..2] = {1, 2, 3}",
+			"print([1, 2; 3, 4][1])",
+			"print([1, 2; 3, 4][1, 2, 1])",
+			"print({1, 2}[1, 2])",
+		] {
+			let error = interpret(source).unwrap_err();
+
+			assert!(matches!(error, Error::ShapeError(..)), "{source}");
+			assert_ne!(error.range(), 0..0, "{source}");
+		}
+
+		// Indices which are not whole numbers are not reported as out of bounds
+		for source in [
+			"print([1, 2; 3, 4][1.5, 1])",
+			"print({1, 2}[i])",
+			"let l = {1, 2}\nlet l[0.5] = 1",
+		] {
+			let error = interpret(source).unwrap_err();
+
+			assert!(matches!(error, Error::ValueError(..)), "{source}");
+			assert_ne!(error.range(), 0..0, "{source}");
+		}
+
+		assert!(matches!(
+			interpret("print({1, 2}[3])"),
+			Err(Error::IndexError(..))
+		));
+
+		let error = interpret("let B[1, 1] = 2").unwrap_err();
+
+		assert!(matches!(error, Error::ValueError(..)));
+		assert_ne!(error.range(), 0..0);
+	}
+
+	#[test]
+	fn list() {
+		run("tests/list.cal", false, false);
+
+		for (source, expected) in [
+			("range(1, 10, 3)", "{1, 4, 7, 10}"),
+			("range(0, 1, 0.3)", "{0, 0.3, 0.6, 0.9}"),
+			("range(5, 1, 0-2)", "{5, 3, 1}"),
+			("range(2, 1, 1)", "{}"),
+			("linspace(0, 1, 5)", "{0, 0.25, 0.5, 0.75, 1}"),
+			("linspace(2, 3, 1)", "{2}"),
+			("push({}, 7)", "{7}"),
+			("concat({}, {1})", "{1}"),
+			("len({})", "0"),
+			("sum({})", "0"),
+			("prod({})", "1"),
+			("reverse({})", "{}"),
+			("sort({3, 1, 2, 1})", "{1, 1, 2, 3}"),
+			("unique({3, 1, 2, 1})", "{3, 1, 2}"),
+			("unique({})", "{}"),
+		] {
+			let interpreter = interpreted(&format!("let r = {source}\nlet e = {expected}"));
+
+			assert_eq!(
+				interpreter.globals["r"].value, interpreter.globals["e"].value,
+				"{source}"
+			);
+		}
+
+		for source in [
+			"print(range(1, 2, 0))",
+			"print(linspace(0, 1, 0.5))",
+			"print(sort({1, i}))",
+		] {
+			let error = interpret(source).unwrap_err();
+
+			assert!(matches!(error, Error::ValueError(..)), "{source}");
+			assert_ne!(error.range(), 0..0, "{source}");
+		}
+	}
+
+	#[test]
+	fn linalg() {
+		run("tests/linalg.cal", false, false);
+
+		assert_rounded(
+			"let A = [2, 1, 0-1; 0-3, 0-1, 2; 0-2, 1, 2]",
+			&[
+				("solve(A, [8; 0-11; 0-3])", &["2", "3", "-1"]),
+				(
+					"solve(A, [1, 0; 0, 1; 0, 0])",
+					&["4", "3", "-2", "-2", "5", "4"],
+				),
+				("solve([i, 1; 1, i], [1; 0])", &["-0.5i", "0.5"]),
+				("determinant(A)", &["-1"]),
+				("inverse([4, 7; 2, 6])", &["0.6", "-0.7", "-0.2", "0.4"]),
+				("rank([1, 2, 3; 2, 4, 6; 1, 0, 1])", &["2"]),
+			],
+			15,
+		);
+
+		// Entries are only negligible next to the rest of the matrix
+		for (source, expected) in [
+			("determinant([a, 0; 0, 1])", "a"),
+			("solve([a, 0; 0, 1], [a; 1])", "[1; 1]"),
+			("rank([a, 0; 0, 1])", "2"),
+			("rank([1, 2; 2, 4] * a)", "1"),
+			("determinant([1, 2, 3; 4, 5, 6; 7, 8, 9])", "0"),
+		] {
+			let interpreter = interpreted(&format!(
+				"let a = 0.0000000000000000000001\nlet r = {source}\nlet e = {expected}"
+			));
+
+			assert_eq!(
+				interpreter.globals["r"].value, interpreter.globals["e"].value,
+				"{source}"
+			);
+		}
+
+		for source in [
+			"print(identity(100000000000))",
+			"print(zeros(100000000000, 100000000000))",
+			"print(ones(1.5, 2))",
+		] {
+			let error = interpret(source).unwrap_err();
+
+			assert!(matches!(error, Error::ValueError(..)), "{source}");
+			assert_ne!(error.range(), 0..0, "{source}");
+		}
+	}
+
+	#[test]
+	fn spectral() {
+		run("tests/spectral.cal", false, false);
+
+		// Values are repeated as often as eigvals repeats them, and defective
+		// matrices repeat their last independent eigenvector
+		for (source, values, vectors) in [
+			("[1, 1; 0, 1]", "{1, 1}", "[1, 1; 0, 0]"),
+			(
+				"[1, 1, 0; 0, 1, 1; 0, 0, 1]",
+				"{1, 1, 1}",
+				"[1, 1, 1; 0, 0, 0; 0, 0, 0]",
+			),
+			(
+				"[2, 1, 0; 0, 2, 0; 0, 0, 3]",
+				"{3, 2, 2}",
+				"[0, 1, 1; 0, 0, 0; 1, 0, 0]",
+			),
+			("[2, 0; 0, 2]", "{2, 2}", "[1, 0; 0, 1]"),
+			("[0, 0; 0, 0]", "{0, 0}", "[1, 0; 0, 1]"),
+			("[2, 0; 0, 3]", "{3, 2}", "[0, 1; 1, 0]"),
+			(
+				"[0.000000001, 0; 0, 0.000000002]",
+				"{0.000000002, 0.000000001}",
+				"[0, 1; 1, 0]",
+			),
+			(
+				"[3, 1; 0, 2]",
+				"{3, 2}",
+				"[1, 0-0.7071067811865475244; 0, 0.7071067811865475244]",
+			),
+		] {
+			let interpreter = interpreted(&format!(
+				"let A = {source}\nlet e = eig(A)\nlet l = eigvals(A)\nlet values = {values}\nlet vectors = {vectors}"
+			));
+
+			let Data::List(e) = &interpreter.globals["e"].value else {
+				unreachable!()
+			};
+
+			assert_eq!(e[0], interpreter.globals["l"].value, "{source}");
+			assert_eq!(e[0], interpreter.globals["values"].value, "{source}");
+			assert_eq!(e[1], interpreter.globals["vectors"].value, "{source}");
+		}
+
+		for (source, expected) in [
+			("norm([3, 0; 4, 5], inf)", "9"),
+			("norm({3, 0-4}, inf)", "4"),
+			("norm([3, 0; 4, 5], 1)", "7"),
+			("norm({3, 4})", "5"),
+		] {
+			let interpreter = interpreted(&format!("let r = {source}\nlet e = {expected}"));
+
+			assert_eq!(
+				interpreter.globals["r"].value, interpreter.globals["e"].value,
+				"{source}"
+			);
+		}
+	}
+
+	#[test]
+	fn sparse() {
+		run("tests/sparse.cal", false, false);
+
+		// Conjugate gradient is only run on symmetric positive definite matrices
+		for source in [
+			"print(solve(sparse([1, 2; 3, 4]), [1; 2]))",
+			"print(solve(sparse([1, 0; 0, 0-1]), [1; 1]))",
+		] {
+			let error = interpret(source).unwrap_err();
+
+			assert!(matches!(error, Error::ValueError(..)), "{source}");
+			assert_ne!(error.range(), 0..0, "{source}");
+		}
+
+		// Operations a sparse matrix has no sparse result for act on it as dense
+		for (source, expected) in [
+			("S + 1", "[2, 1; 1, 3]"),
+			("S - 1", "[0, 0-1; 0-1, 1]"),
+			("2 - S", "[1, 2; 2, 0]"),
+			("S ^ 2", "sparse([1, 0; 0, 4])"),
+			("S ^ 0", "sparse([1, 0; 0, 1])"),
+			("S ^ (0-1)", "[1, 0; 0, 0.5]"),
+			("abs(S)", "2"),
+			("map(floor, S + 0.5)", "[1, 0; 0, 2]"),
+			("cos(S) == map(cos, [1, 0; 0, 2])", "1"),
+			("S == [1, 0; 0, 2]", "1"),
+			("S % 2", "[1, 0; 0, 0]"),
+		] {
+			let interpreter = interpreted(&format!(
+				"let S = sparse([1, 0; 0, 2])\nlet r = {source}\nlet e = {expected}"
+			));
+
+			assert_eq!(
+				interpreter.globals["r"].value, interpreter.globals["e"].value,
+				"{source}"
+			);
+		}
+
+		for source in ["S > 1", "S + poly(1, 2)"] {
+			let error = interpret(&format!("let S = sparse([1, 0; 0, 2])\nprint({source})")).unwrap_err();
+
+			assert!(matches!(error, Error::TypeError(..)), "{source}");
+			assert_ne!(error.range(), 0..0, "{source}");
+		}
+	}
+
+	#[test]
+	fn elementwise() {
+		run("tests/elementwise.cal", false, false);
+
+		for (source, expected) in [
+			("2 / A", "[2, 1; 2 / 3, 0.5]"),
+			("A % 3", "[1, 2; 0, 1]"),
+			("7 % A", "[0, 1; 1, 3]"),
+			("A ^ 5", "A * A * A * A * A"),
+			("A ^ (0 - 2)", "inverse(A) * inverse(A)"),
+			("A ^ 0", "[1, 0; 0, 1]"),
+			("A .* [2, 2; 2, 2]", "[2, 4; 6, 8]"),
+			("A ./ [2, 4; 6, 8]", "[0.5, 0.5; 0.5, 0.5]"),
+			("A .^ [2, 0; 1, 0-1]", "[1, 1; 3, 0.25]"),
+			("2 .^ A", "[2, 4; 8, 16]"),
+			("A .^ 2 - A * A", "[0-6, 0-6; 0-6, 0-6]"),
+			("{1, 2} .* {3, 4}", "{3, 8}"),
+			("{1, 2} ./ {4, 8}", "{0.25, 0.25}"),
+			("2 .^ {1, 2}", "{2, 4}"),
+		] {
+			let interpreter = interpreted(&format!(
+				"let A = [1, 2; 3, 4]\nlet r = {source}\nlet e = {expected}"
+			));
+
+			assert_eq!(
+				interpreter.globals["r"].value, interpreter.globals["e"].value,
+				"{source}"
+			);
+		}
+
+		for source in ["A ^ 0.5", "A ^ 100000000000000000000"] {
+			let error = interpret(&format!("let A = [1, 2; 3, 4]\nprint({source})")).unwrap_err();
+
+			assert!(matches!(error, Error::ValueError(..)), "{source}");
+			assert_ne!(error.range(), 0..0, "{source}");
+		}
+
+		let error = interpret("let A = [1, 2; 3, 4]\nprint(A > 2)").unwrap_err();
+
+		assert!(matches!(error, Error::TypeError(..)));
+		assert_ne!(error.range(), 0..0);
+
+		for source in ["A / 0", "2 / [1, 0]", "A % 0"] {
+			assert!(
+				interpret(&format!("let A = [1, 2; 3, 4]\nprint({source})")).is_err(),
+				"{source}"
+			);
+		}
+	}
+
+	#[test]
+	fn special() {
+		run("tests/special.cal", false, false);
+
+		// Poles and arguments outside the domain point at the call
+		for source in [
+			"print(gamma(0-1))",
+			"print(lgamma(0))",
+			"print(beta(0-2, 1))",
+			"print(digamma(0-3))",
+			"print(zeta(1))",
+			"print(bessely(0, 0))",
+			"print(besselj(0.5, 1))",
+			"print(besselj(10, 70i))",
+		] {
+			let error = interpret(source).unwrap_err();
+
+			assert!(matches!(error, Error::ValueError(..)), "{source}");
+			assert_ne!(error.range(), 0..0, "{source}");
+		}
+	}
+
+	#[test]
+	fn integrate() {
+		run("tests/integrate.cal", false, false);
+
+		for source in [
+			"fn k(x) = 1 / x\nprint(integrate(k, 0, 1))",
+			"fn g(x) = e^(0 - x)\nprint(integrate(g, 0, 0-inf))",
+		] {
+			let err = interpret(source).unwrap_err();
+
+			assert!(err.error_message().starts_with("integral did not converge"));
+		}
+
+		// Only values the integrand is undefined at are integrated around, any
+		// other error is reported as is
+		let err = interpret("fn f(x) = nosuch(x)\nprint(integrate(f, 0, 1))").unwrap_err();
+
+		assert_eq!(err.error_message(), "undefined function: `nosuch`");
+
+		let err = interpret("fn f(x) = [x, 1] * [1; 2; 3]\nprint(integrate(f, 0, 1))").unwrap_err();
+
+		assert!(!err.error_message().contains("integral did not converge"));
+	}
+
+	#[test]
+	fn roots() {
+		run("tests/roots.cal", false, false);
+
+		assert_rounded(
+			"fn quadratic(x) = x^2 - 3*x + 2\nfn fixed(x) = cos(x) - x\nfn cubic(x) = x^3 - x",
+			&[
+				("quadroot(quadratic)", &["1", "2"]),
+				("quadroot(1, 2, 5)", &["-1 + -2i", "-1 + 2i"]),
+				("roots({1, 0-6, 11, 0-6})", &["1", "2", "3"]),
+				("roots({1, 0-3, 3, 0-1})", &["1", "1", "1"]),
+				(
+					"roots({1, 0, 0, 0, 1})",
+					&[
+						"-0.707106781187 + -0.707106781187i",
+						"-0.707106781187 + 0.707106781187i",
+						"0.707106781187 + -0.707106781187i",
+						"0.707106781187 + 0.707106781187i",
+					],
+				),
+				("findroot(fixed, 1)", &["0.739085133215"]),
+				("findroot(sin, 3)", &["3.14159265359"]),
+				("bisect(cubic, 0.5, 2)", &["1"]),
+			],
+			12,
+		);
+	}
+
+	#[test]
+	fn ode() {
+		run("tests/ode.cal", false, false);
+
+		// The last row holds the time and value at the end of the interval
+		for (source, expected, dp) in [
+			(
+				"fn f(t, y) = 0-y\nlet r = ode(f, 1, 0, 1, 0.1)",
+				["1", "0.36788"].as_slice(),
+				5,
+			),
+			(
+				"fn f(t, y) = t * y\nlet r = ode(f, 1, 0, 2)",
+				&["2", "7.389056"],
+				6,
+			),
+			(
+				"fn f(t, y: M): M = [y[2, 1]; 0-y[1, 1]]\nlet r = ode(f, [1; 0], 0, pi)",
+				&["3.141593", "-1", "0"],
+				6,
+			),
+			(
+				"fn f(t, y: M): M = [y[2, 1]; 0-y[1, 1]]\nlet r = ode(f, [1; 0], 0, 0-1, 0.25)",
+				&["-1", "0.54", "0.841"],
+				3,
+			),
+		] {
+			let interpreter = interpreted(source);
+
+			let Data::Matrix(solution) = &interpreter.globals["r"].value else {
+				unreachable!()
+			};
+
+			let end = solution.row(solution.rows() - 1);
+
+			assert_eq!(
+				rounded(&Data::Matrix(Matrix::new(1, end.len(), end.to_vec())), dp),
+				expected,
+				"{source}"
+			);
+		}
+
+		// The right hand side must be a function of both `t` and `y`
+		for source in [
+			"fn d(y) = y\nprint(ode(d, 1, 0, 1))",
+			"print(ode(sin, 1, 0, 1))",
+		] {
+			assert!(
+				matches!(interpret(source), Err(Error::ValueError(..))),
+				"{source}"
+			);
+		}
+	}
+
+	#[test]
+	fn optimize() {
+		run("tests/optimize.cal", false, false);
+
+		assert_rounded(
+			"fn parabola(x) = (x - 3)^2 + 1\nfn bowl(x, y) = (x - 1)^2 + 10*(y + 2)^2\nfn rosenbrock(x, y) = (1 - x)^2 + 100*(y - x^2)^2\nfn hill(x) = 4*x - x^2\nfn line(x) = x",
+			&[
+				("minimize(parabola, 0)", &["3"]),
+				("minimize(bowl, {0, 0})", &["1", "-2"]),
+				("minimize(rosenbrock, [0-1.2, 1])", &["1", "1"]),
+				("minimize(cos, 3)", &["3.141593"]),
+				("maximize(hill, 0)", &["2"]),
+				("minimize(parabola, 0, 10)", &["3"]),
+				("minimize(line, 0-2, 5)", &["-2"]),
+				("maximize(line, 0-2, 5)", &["5"]),
+				("minimize(bowl, {2, 0}, {3, 1})", &["2", "0"]),
+				("minimize(rosenbrock, {0-2, 0-2}, {2, 2})", &["1", "1"]),
+				("minimize(rosenbrock, {2, 2}, {3, 3})", &["2", "3"]),
+			],
+			6,
+		);
+
+		for source in [
+			"fn f(x) = x^2 - 4 * x\nprint(maximize(f, 1))",
+			"fn f(x) = x^3\nprint(minimize(f, 1))",
+			"fn f(x) = e^x\nprint(maximize(f, 0))",
+			"fn f(x, y) = x * y\nprint(minimize(f, [1, 2]))",
+		] {
+			let error = interpret(source).unwrap_err();
+
+			assert!(matches!(error, Error::ValueError(..)));
+			assert_ne!(error.range(), 0..0);
+		}
+	}
+
+	#[test]
+	fn calculus() {
+		run("tests/calculus.cal", false, false);
+
+		for order in ["1000000000000000000000", "0 - 1", "1.5", "{2}"] {
+			assert!(interpret(&format!("print(diff(x^2, x, {order}))")).is_err());
+		}
+	}
+
+	#[test]
+	fn derivatives() {
+		let h = Decimal::new(1, 8);
+
+		for (source, x) in [
+			("x^x", dec!(1.5)),
+			("2^x", dec!(0.7)),
+			("e^(2 * x)", dec!(0.3)),
+			("x^3 - 4 * x", dec!(2)),
+			("x % 3", dec!(4.2)),
+			("|x - 1|", dec!(0.4)),
+			("|x - 1|", dec!(2.5)),
+			("if x > 1 then x^2 else 3 * x end", dec!(0.5)),
+			("if x > 1 then x^2 else 3 * x end", dec!(2)),
+			("log(x, 2) + log(3, x)", dec!(1.7)),
+			("nrt(x, 3) + nrt(2, x)", dec!(2.4)),
+			("sin(x^2) / cos(x)", dec!(0.6)),
+			("tan(x) * atan(x) + sinh(x) - cosh(x)", dec!(0.9)),
+			("sqrt(x) * cbrt(x) * ln(x)", dec!(3.1)),
+			("exp(erf(x)) + gamma(x) + beta(x, 2)", dec!(1.3)),
+			("g(x^2) * g(sin(x))", dec!(0.8)),
+		] {
+			let mut interpreter = interpreted(&format!("fn g(t) = t * e^t\nfn f(x) = {source}"));
+
+			let Some(Function::UserDefined(f)) = interpreter.functions.get("f").cloned() else {
+				unreachable!()
+			};
+			let ctx = &mut context(&mut interpreter);
+
+			let derivative = f
+				.code
+				.differentiate(&Data::Ident("x".to_string()), ctx)
+				.unwrap()
+				.substitute(&[("x".to_string(), Expression::Float(x))])
+				.evaluate(ctx, 0..0)
+				.unwrap()
+				.to_real();
+
+			let mut at = |x: Decimal| {
+				Function::UserDefined(f.clone())
+					.execute(ctx, vec![Data::new_real(x)])
+					.unwrap()
+					.to_real()
+			};
+			let estimate = (at(x + h) - at(x - h)) / (Decimal::TWO * h);
+
+			assert!(
+				(derivative - estimate).abs() <= dec!(1e-6) * estimate.abs().max(Decimal::ONE),
+				"{source} at {x}: {derivative} != {estimate}"
+			);
+		}
+	}
+
+	#[test]
+	fn antiderivatives() {
+		let h = Decimal::new(1, 8);
+
+		for (source, x) in [
+			("3 * x^2 - 2 * x + 5", dec!(1.2)),
+			("1 / x", dec!(-0.7)),
+			("sqrt(x) + cbrt(x) + exp(3 * x + 1)", dec!(0.8)),
+			("2^x + 1 / (2 * x + 1)^2", dec!(0.4)),
+			("sin(3 * x) * cos(3 * x)^4 + tan(x)", dec!(0.5)),
+			("x * e^(x^2) + cos(x) * e^sin(x)", dec!(0.9)),
+			("x^2 * sin(2 * x) + x * ln(x)", dec!(1.4)),
+			("ln(x)^2 + atan(x) + ln(2 * x + 1)", dec!(2.1)),
+			("sin(x)^2 * cos(x)^2 + sin(x)^3 + tan(x)^3", dec!(0.6)),
+			("(2 * x + 3) / (x^2 + 2 * x + 5)", dec!(0.3)),
+			("1 / (x^2 - 1) + 1 / (x^2 + 2 * x + 1)", dec!(2.5)),
+			("e^(2 * x) * sin(3 * x) + 2^x * cos(x)", dec!(1.1)),
+			("(x + 1)^3 * (x - 2) + sinh(x) / g(2)", dec!(0.7)),
+		] {
+			let mut interpreter = interpreted(&format!("fn g(t) = t * e^t\nfn f(x) = {source}"));
+
+			let Some(Function::UserDefined(f)) = interpreter.functions.get("f").cloned() else {
+				unreachable!()
+			};
+			let ctx = &mut context(&mut interpreter);
+
+			let antiderivative = f.code.integrate("x", ctx).unwrap();
+
+			let mut at = |x: Decimal| {
+				antiderivative
+					.substitute(&[("x".to_string(), Expression::Float(x))])
+					.evaluate(ctx, 0..0)
+					.unwrap()
+					.to_real()
+			};
+			let estimate = (at(x + h) - at(x - h)) / (Decimal::TWO * h);
+
+			let value = Function::UserDefined(f.clone())
+				.execute(ctx, vec![Data::new_real(x)])
+				.unwrap()
+				.to_real();
+
+			assert!(
+				(value - estimate).abs() <= dec!(1e-6) * value.abs().max(Decimal::ONE),
+				"{source} at {x}: {antiderivative} has derivative {estimate} != {value}"
+			);
+		}
+
+		let mut interpreter = Interpreter::new();
+		let ctx = &mut context(&mut interpreter);
+
+		assert!(parse("e^(x^2) + sin(x) / x").integrate("x", ctx).is_err());
+	}
+
+	#[test]
+	fn simplify() {
+		for (source, simplified) in [
+			("x + x", "2x"),
+			("x * 0 + 1 * y - 0", "y"),
+			("2 * x^1 + 0", "2x"),
+			("x^2 * x^3 / x", "x^4"),
+			("x^a * x^b", "x^(a+b)"),
+			("(x^2)^3", "x^6"),
+			("(2 * x * y)^2", "4x^2*y^2"),
+			("y + x^2 + 1 + 2 * x", "x^2+2x+y+1"),
+			("x * y^2 + y * x^2 + y^2", "x^2*y+x*y^2+y^2"),
+			("3 * x - x * 3", "0"),
+			("x / 3 + x / 6", "x/2"),
+			("2 / 4", "1/2"),
+			("0.5 * x + x / 4", "3x/4"),
+			("1 / x + 2 / x", "3/x"),
+			("x / (2 * y) * 4", "2x/y"),
+			("0 - x / y", "-x/y"),
+			("(x + 1) * 2 - 2", "2x"),
+			("(x + 1) * y", "y*(x+1)"),
+			("(x - y) - (x - y)", "0"),
+			("ln(e) * e^(ln(x))", "x"),
+			("exp(0) + sin(0) + cos(0)", "2"),
+			("sqrt(16 / 9)", "4/3"),
+			("2^10 / 2^3", "128"),
+			("x^2 / x^5", "1/x^3"),
+			("if 1 > 2 then x else y end", "y"),
+			("|0 - 3| + 7 % 4", "6"),
+		] {
+			let expr = parse(source);
+
+			assert_eq!(expr.simplify().to_string(), simplified, "{source}");
+			assert_eq!(expr.simplify().simplify(), expr.simplify(), "{source}");
+		}
+
+		for (source, simplified) in [
+			("sin(x)^2 + cos(x)^2", "1"),
+			("3 * y * sin(2 * x)^2 + 3 * cos(2 * x)^2 * y", "3y"),
+			("cos(x)^2 - sin(x)^2", "cos(2x)"),
+			("sin(x) / cos(x)", "tan(x)"),
+			("2 * sin(x) * cos(x)", "sin(2x)"),
+			("tan(x) * cos(x) + sin(0 - x)", "0"),
+			("cos(0 - x)", "cos(x)"),
+		] {
+			let expr = parse(source);
+
+			assert_eq!(expr.simplify_trig().to_string(), simplified, "{source}");
+		}
+	}
+
+	#[test]
+	fn algebra() {
+		run("tests/algebra.cal", false, false);
+
+		for (source, expanded) in [
+			("(x + 1)^3", "x^3+3x^2+3x+1"),
+			("(x + y) * (x - y)", "x^2-y^2"),
+			("(x + y)^2 - (x - y)^2", "4x*y"),
+			("2 * (x + 1) * sin(x)", "2x*sin(x)+2sin(x)"),
+			("(x + 1)^a", "(x+1)^a"),
+		] {
+			assert_eq!(parse(source).expand().to_string(), expanded, "{source}");
+		}
+
+		for (source, collected) in [
+			("x * y + 3 * x + x^2 * y + sin(y)", "x^2*y+(y+3)*x+sin(y)"),
+			("a * x - x + b", "(a-1)*x+b"),
+		] {
+			assert_eq!(
+				parse(source).collect("x").to_string(),
+				collected,
+				"{source}"
+			);
+		}
+
+		for (source, factored) in [
+			("x^2 - 4", "(x+2)*(x-2)"),
+			("2 * x^2 - 8", "2(x+2)*(x-2)"),
+			("6 * x^2 + x - 2", "(2x-1)*(3x+2)"),
+			("x^3 - 3 * x^2 + 3 * x - 1", "(x-1)^3"),
+			("x^4 + 3 * x^2 + 2", "(x^2+1)*(x^2+2)"),
+			("x^4 + 4", "(x^2+2x+2)*(x^2-2x+2)"),
+			("x^3 - x^2", "x^2*(x-1)"),
+			("x^2 + 1", "x^2+1"),
+		] {
+			let factor = parse(source).factor().unwrap();
+
+			assert_eq!(factor.to_string(), factored, "{source}");
+			assert_eq!(factor.expand(), parse(source).expand(), "{source}");
+		}
+
+		assert!(parse("x * y + 1").factor().is_err());
+		assert!(parse("sin(x) + 1").coefficients("x").is_err());
+	}
+
+	#[test]
+	fn solve() {
+		run("tests/solve.cal", false, false);
+
+		assert!(parse("x + 1 == x + 1").solve("x").is_err());
+		assert!(parse("sin(x) == 0").solve("x").is_err());
+		assert!(parse("a * x^3 + 1").solve("x").is_err());
+
+		let system = |equations: &[&str]| {
+			crate::solve::solve_linear(
+				&equations
+					.iter()
+					.map(|equation| parse(equation))
+					.collect::<Vec<Expression>>(),
+				&["x".to_string(), "y".to_string()],
+			)
+		};
+
+		assert!(system(&["x + y == 1", "x + y == 2"]).is_err());
+		assert!(system(&["x * y == 1", "x - y == 2"]).is_err());
+	}
+
+	#[test]
+	fn symbolic() {
+		run("tests/symbolic.cal", false, false);
+
+		let interpreter = interpreted(
+			"let y = x^2 + 3x\nlet a = subs(y, x, 2)\nlet b = eval(y, x = -1)\nlet k = 2\nlet c = eval(y * k, x = 1)\nsym k",
+		);
+
+		assert!(matches!(
+			interpreter.globals["y"].value,
+			Data::Expression(..)
+		));
+		assert!(!interpreter.globals.contains_key("k"));
+
+		for (name, value) in [("a", dec!(10)), ("b", dec!(-2)), ("c", dec!(8))] {
+			assert_eq!(interpreter.globals[name].value.to_real(), value);
+		}
+
+		assert!(interpret("let y = x^2\nprint(eval(y, 2))").is_err());
+		assert!(interpret("print(subs(x * w, [x, w], {1}))").is_err());
+		assert!(interpret("print(eval(x, x = [1, 2]))").is_err());
+
+		// Complex numbers are written with `i`, and matrices of numbers become
+		// matrices of expressions
+		for (source, expected) in [
+			("x + i", "x + i"),
+			("x * 2i", "x * 2 * i"),
+			("x ^ i", "x^i"),
+			("x - (1 - 2i)", "x - (1 - 2i)"),
+			("x + [1, 2]", "[x + 1, x + 2]"),
+			("sin(x) * [1,2]", "[sin(x), sin(x) * 2]"),
+			("[x, 1; 0, x] * [1, 1; 1, 1]", "[x + 1, x + 1; x, x]"),
+			("x + {1, 2}", "{x + 1, x + 2}"),
+		] {
+			let interpreter = interpreted(&format!("let r = {source}"));
+
+			let expected = match parse(expected) {
+				Expression::List(list) => Data::List(list.into_iter().map(Data::Expression).collect()),
+				expected => Data::Expression(expected),
+			};
+
+			assert_eq!(
+				interpreter.globals["r"].value.to_string(),
+				expected.to_string(),
+				"{source}"
+			);
+		}
+
+		let interpreter = interpreted("let r = eval(x + i, x = 1)");
+
+		assert_eq!(
+			interpreter.globals["r"].value,
+			Data::Number(dec!(1), dec!(1))
+		);
+
+		for (source, operand) in [
+			("x ^ [1, 2]", "[1, 2]"),
+			("[x, 1] + [1, 2, 3]", "[x, 1] + [1, 2, 3]"),
+		] {
+			let error = interpret(&format!("print({source})")).unwrap_err();
+			let start = source.find(operand).unwrap() + 7;
+
+			assert_eq!(error.range(), start..start + operand.len(), "{source}");
+		}
+	}
+
+	#[test]
+	fn series() {
+		run("tests/series.cal", false, false);
+
+		let interpreter = interpreted(
+			"let a = limit(sin(x) / x, x, 0)\nlet b = limit((x^2 - 1) / (x - 1), x, 1)\nlet c = limit(atan(x), x, inf)\nlet d = subs(taylor(cos(x), x, 0, 4), x, 1)",
+		);
+
+		for (name, value) in [
+			("a", dec!(1)),
+			("b", dec!(2)),
+			("c", Decimal::HALF_PI),
+			("d", dec!(13) / dec!(24)),
+		] {
+			let error = (interpreter.globals[name].value.to_real() - value).abs();
+
+			assert!(error < dec!(1e-20), "{name}");
+		}
+
+		assert!(interpret("print(limit(|x| / x, x, 0))").is_err());
+		assert!(interpret("print(limit(sin(x), x, inf))").is_err());
+		assert!(interpret("print(limit(1 / x, x, 0, 0))").is_err());
+		assert!(interpret("print(taylor(sin(x), x, 0, 1.5))").is_err());
+		assert!(interpret("print(taylor(1 / x, x, 0, 2))").is_err());
+	}
+
+	#[test]
+	fn polynomial() {
+		run("tests/polynomial.cal", false, false);
+
+		let interpreter = interpreted(
+			"let p = poly({1, -3, 2})\nlet q = divmod(p * poly(x + 4) + 3, poly(x + 4))\nlet g = gcd(p, poly({1, 0, -1}))\nlet a = eval(p, 5)\nlet b = integrate(p, 0, 3)\nlet n = degree(diff(p^4))",
+		);
+
+		let value = |name: &str| interpreter.globals[name].value.clone();
+
+		assert_eq!(value("q").to_string(), "{x^2-3x+2, 3}");
+		assert_eq!(value("g").to_string(), "x-1");
+
+		for (name, n) in [("a", dec!(12)), ("b", dec!(1.5)), ("n", dec!(7))] {
+			assert_eq!(value(name).to_real(), n);
+		}
+
+		assert!(interpret("print(poly({1, 2}) / poly({1, 1}))").is_err());
+		assert!(interpret("print(divmod(poly({1, 2}), poly({0})))").is_err());
+		assert!(interpret("print(poly({1, 2}) + poly({1, 2}, t))").is_err());
+		assert!(interpret("print(poly(x * y))").is_err());
+		assert!(interpret("print(poly({1, 2})^0.5)").is_err());
+	}
+
+	#[test]
+	fn stats() {
+		run("tests/stats.cal", false, false);
+
+		assert_rounded(
+			"let x = {1, 2, 3, 4, 5}\nlet y = {2.1, 3.9, 6.2, 7.8, 10.1}",
+			&[
+				("polyfit(x, x .^ 2 + 1, 2)", &["1", "0", "1"]),
+				(
+					"polyfit({0, 1, 2, 3}, {1, 3, 11, 31}, 3)",
+					&["1", "0", "1", "1"],
+				),
+				("polyfit({0, 1, 2}, {1, 3, 5}, 1)", &["2", "1"]),
+				("polyfit(x, y, 1)", &["1.99", "0.05"]),
+				("cov(x, y)", &["4.975"]),
+				("variance({2, 4, 4, 4, 5, 5, 7, 9})", &["4.571428571428571"]),
+			],
+			15,
+		);
+
+		for source in [
+			"print(mean({}))",
+			"print(mean({1, i}))",
+			"print(variance({1}))",
+			"print(quantile({1, 2}, 2))",
+			"print(corr({1, 1}, {1, 2}))",
+			"print(linreg({1, 1}, {1, 2}))",
+			"print(polyfit({1, 2}, {1, 2}, 2))",
+			"print(polyfit({1, 2}, {1, 2}, 0.5))",
+			"print(polyfit({1, 2}, {1, 2}, 100000000000000000000000))",
+		] {
+			let error = interpret(source).unwrap_err();
+
+			assert!(matches!(error, Error::ValueError(..)), "{source}");
+			assert_ne!(error.range(), 0..0, "{source}");
+		}
+	}
+
+	#[test]
+	fn probability() {
+		run("tests/probability.cal", false, false);
+
+		assert!(interpret("print(randint(0, 100000000000000000000000))").is_err());
+	}
+
+	#[test]
+	fn matrix_shapes() {
+		for source in [
+			"let A = [1, 2; 3]",
+			"[1, 2; 3, 4] + [1, 2, 3; 4, 5, 6]",
+			"[1, 2; 3, 4] - [1; 2]",
+			"[1, 2, 3; 4, 5, 6] * [1, 2; 3, 4]",
+			"[1, 2, 3; 4, 5, 6]^2",
+			"determinant([1, 2, 3; 4, 5, 6])",
+			"inverse([1, 2])",
+			"solve([1, 2; 3, 4], [1; 2; 3])",
+			"eigvals([1, 2, 3; 4, 5, 6])",
+			"{1, 2} + {1, 2, 3}",
+			"sparse({1, 2}, {1}, {1, 2}, 2, 2)",
+			"sparse([1, 0; 0, 1]) * [1, 2, 3]",
+			"solve(sparse([2, 0; 0, 2]), [1; 2; 3])",
+			"[1, 2] .* [1, 2, 3]",
+			"[1; 2] .^ [1, 2]",
+			"cov({1, 2}, {1, 2, 3})",
+		] {
+			assert!(
+				matches!(interpret(source), Err(Error::ShapeError(..))),
+				"{source}"
+			);
+		}
+
+		let error = interpret("let A = [1, 2; 3, 4; 5]").unwrap_err();
+
+		assert!(
+			error
+				.error_message()
+				.contains("row 3 has 1 elements, expected 2")
+		);
+
+		// Mismatched operands are pointed at on their own line, ranges start at 1
+		let source = "let A = [1, 2; 3, 4]\n// B has too few rows\nlet B = [1, 2, 3]\nprint(1 + A * B)";
+		let start = source.find("A * B").unwrap() + 1;
+
+		assert_eq!(interpret(source).unwrap_err().range(), start..start + 5);
+	}
+
+	#[test]
+	fn uncertainty() {
+		run("tests/uncertainty.cal", false, false);
+
+		let interpreter = interpreted(
+			"let g = 3 +- 0.4\nlet a = g * 2 + 1\nlet b = log(100 +- 2, 10)\nlet c = nrt(g, 2 +- 0.1)\nlet d = 3 + -2\nlet e = g > 2.9\nlet f = 3+-2",
+		);
+
+		let measured = |name: &str| match interpreter.globals[name].value {
+			Data::Measured(value, sigma) => (value, sigma),
+			ref data => panic!("expected `{name}` to be measured, found {data}"),
+		};
+
+		assert_eq!(measured("a"), (dec!(7), dec!(0.8)));
+
+		// ∂/∂a log(a, 10) = 1 / (a ln 10)
+		let (value, sigma) = measured("b");
+		assert_eq!(value.round_dp(20), dec!(2));
+		assert_eq!(sigma.round_dp(6), dec!(0.008686));
+
+		// nrt(a, n) = a^(1 / n) with both a and n uncertain
+		let (value, sigma) = measured("c");
+		assert_eq!(value.round_dp(6), dec!(1.732051));
+		assert_eq!(sigma.round_dp(6), dec!(0.124885));
+
+		assert_eq!(interpreter.globals["d"].value, Data::new_real(dec!(1)));
+		assert_eq!(interpreter.globals["e"].value, Data::new_real(dec!(1)));
+		assert_eq!(measured("f"), (dec!(3), dec!(2)));
+
+		for source in [
+			"let g = 9.81 +- 0.02\nprint([1, 2] +- 1)",
+			"let g = 9.81 +- 0.02\nprint([1, 2] * g)",
+			"let g = 9.81 +- 0.02\nprint(g + x)",
+			"print(sqrt(0 +- 0.1))",
+			"print(besselj(1 +- 0.1, 2))",
+		] {
+			assert!(interpret(source).is_err(), "{source}");
+		}
+
+		// Uncertainties are only carried through real values
+		for source in [
+			"print(sqrt(-4 +- 0.1))",
+			"print(ln(-4 +- 0.1))",
+			"print((-2 +- 0.1)^0.5)",
+		] {
+			let error = interpret(source).unwrap_err();
+
+			assert!(matches!(error, Error::ValueError(..)), "{source}");
+			assert_ne!(error.range(), 0..0, "{source}");
+		}
+
+		let interpreter = interpreted("let r = (-2)^0.5\nlet s = 3*-2\nlet t = 2^-1");
+
+		assert_eq!(
+			interpreter.globals["r"].value.to_real().round_dp(20),
+			dec!(0)
+		);
+		assert_eq!(
+			interpreter.globals["r"].value.to_img().round_dp(6),
+			dec!(1.414214)
+		);
+		assert_eq!(interpreter.globals["s"].value, Data::new_real(dec!(-6)));
+		assert_eq!(interpreter.globals["t"].value, Data::new_real(dec!(0.5)));
 	}
 }
@@ -0,0 +1,622 @@
+use std::ops::{Add, Div, Index, IndexMut, Mul, Neg, Sub};
+
+use rust_decimal::{Decimal, MathematicalOps};
+
+use crate::{
+	errors::{Error, TypeError},
+	types::{Data, DataType},
+};
+
+/// Complex number held by every element of a matrix
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Complex {
+	pub re: Decimal,
+	pub im: Decimal,
+}
+
+impl Complex {
+	pub const ZERO: Complex = Complex::new(Decimal::ZERO, Decimal::ZERO);
+	pub const ONE: Complex = Complex::new(Decimal::ONE, Decimal::ZERO);
+	pub const TWO: Complex = Complex::new(Decimal::TWO, Decimal::ZERO);
+
+	#[inline(always)]
+	pub const fn new(re: Decimal, im: Decimal) -> Self {
+		Self { re, im }
+	}
+
+	#[inline(always)]
+	pub const fn real(re: Decimal) -> Self {
+		Self::new(re, Decimal::ZERO)
+	}
+
+	/// Reads a number out of `data`, any other value is a type error.
+	#[inline(always)]
+	pub fn from_data(data: &Data) -> Result<Self, Error> {
+		match data {
+			Data::Number(a, b) => Ok(Self::new(*a, *b)),
+			_ => Err(TypeError::new(DataType::Number, data.ty(), 0..0).to_error()),
+		}
+	}
+
+	#[inline(always)]
+	pub fn is_real(self) -> bool {
+		self.im.is_zero()
+	}
+
+	#[inline(always)]
+	pub fn conj(self) -> Self {
+		Self::new(self.re, -self.im)
+	}
+
+	/// Largest of the absolute values of both parts, which is cheaper than the
+	/// modulus when all that matters is comparing sizes.
+	#[inline(always)]
+	pub fn magnitude(self) -> Decimal {
+		self.re.abs().max(self.im.abs())
+	}
+
+	#[inline(always)]
+	pub fn modulus(self) -> Decimal {
+		length(&[self])
+	}
+
+	/// Principal square root, only the larger of the two parts is found through
+	/// a square root so the other does not pick up the rounding error of one.
+	#[inline(always)]
+	pub fn sqrt(self) -> Self {
+		if self.magnitude().is_zero() {
+			return Self::ZERO;
+		}
+
+		let w = ((self.modulus() + self.re.abs()) / Decimal::TWO)
+			.sqrt()
+			.unwrap();
+
+		if self.re >= Decimal::ZERO {
+			Self::new(w, self.im / (Decimal::TWO * w))
+		} else if self.im.is_sign_negative() {
+			Self::new(self.im.abs() / (Decimal::TWO * w), -w)
+		} else {
+			Self::new(self.im.abs() / (Decimal::TWO * w), w)
+		}
+	}
+}
+
+impl From<Complex> for Data {
+	fn from(value: Complex) -> Self {
+		Data::Number(value.re, value.im)
+	}
+}
+
+impl Add for Complex {
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self {
+		Self::new(self.re + rhs.re, self.im + rhs.im)
+	}
+}
+
+impl Sub for Complex {
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self {
+		Self::new(self.re - rhs.re, self.im - rhs.im)
+	}
+}
+
+impl Mul for Complex {
+	type Output = Self;
+
+	fn mul(self, rhs: Self) -> Self {
+		Self::new(
+			self.re * rhs.re - self.im * rhs.im,
+			self.re * rhs.im + self.im * rhs.re,
+		)
+	}
+}
+
+impl Div for Complex {
+	type Output = Self;
+
+	/// Smith's algorithm, which avoids squaring the parts of `rhs`.
+	fn div(self, rhs: Self) -> Self {
+		if rhs.re.abs() >= rhs.im.abs() {
+			let ratio = rhs.im / rhs.re;
+			let denominator = rhs.re + rhs.im * ratio;
+
+			Self::new(
+				(self.re + self.im * ratio) / denominator,
+				(self.im - self.re * ratio) / denominator,
+			)
+		} else {
+			let ratio = rhs.re / rhs.im;
+			let denominator = rhs.re * ratio + rhs.im;
+
+			Self::new(
+				(self.re * ratio + self.im) / denominator,
+				(self.im * ratio - self.re) / denominator,
+			)
+		}
+	}
+}
+
+impl Neg for Complex {
+	type Output = Self;
+
+	fn neg(self) -> Self {
+		Self::new(-self.re, -self.im)
+	}
+}
+
+/// Euclidean length of `x`, scaled by its largest part first since squaring
+/// small decimals loses most of their digits.
+#[inline(always)]
+pub fn length(x: &[Complex]) -> Decimal {
+	let scale = x.iter().map(|z| z.magnitude()).max().unwrap_or_default();
+
+	if scale.is_zero() {
+		return Decimal::ZERO;
+	}
+
+	let sum = x
+		.iter()
+		.map(|z| {
+			let (re, im) = (z.re / scale, z.im / scale);
+
+			re * re + im * im
+		})
+		.sum::<Decimal>();
+
+	scale * sum.sqrt().unwrap()
+}
+
+/// Dense matrix of complex numbers stored contiguously in row-major order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+	rows: usize,
+	cols: usize,
+	data: Vec<Complex>,
+}
+
+impl Matrix {
+	#[inline(always)]
+	pub fn new(rows: usize, cols: usize, data: Vec<Complex>) -> Self {
+		assert_eq!(
+			rows * cols,
+			data.len(),
+			"matrix data does not fit its shape"
+		);
+
+		Self { rows, cols, data }
+	}
+
+	#[inline(always)]
+	pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> Complex) -> Self {
+		let mut data = Vec::with_capacity(rows * cols);
+
+		for i in 0..rows {
+			for j in 0..cols {
+				data.push(f(i, j));
+			}
+		}
+
+		Self { rows, cols, data }
+	}
+
+	/// Builds a matrix out of rows which must all have the same length.
+	#[inline(always)]
+	pub fn from_rows(rows: Vec<Vec<Complex>>) -> Self {
+		let cols = rows.first().map(|row| row.len()).unwrap_or(0);
+
+		Self::new(rows.len(), cols, rows.concat())
+	}
+
+	#[inline(always)]
+	pub fn zeros(rows: usize, cols: usize) -> Self {
+		Self::new(rows, cols, vec![Complex::ZERO; rows * cols])
+	}
+
+	#[inline(always)]
+	pub fn identity(n: usize) -> Self {
+		Self::from_fn(
+			n,
+			n,
+			|i, j| if i == j { Complex::ONE } else { Complex::ZERO },
+		)
+	}
+
+	#[inline(always)]
+	pub fn rows(&self) -> usize {
+		self.rows
+	}
+
+	#[inline(always)]
+	pub fn cols(&self) -> usize {
+		self.cols
+	}
+
+	#[inline(always)]
+	pub fn shape(&self) -> (usize, usize) {
+		(self.rows, self.cols)
+	}
+
+	#[inline(always)]
+	pub fn is_empty(&self) -> bool {
+		self.data.is_empty()
+	}
+
+	#[inline(always)]
+	pub fn elements(&self) -> &[Complex] {
+		&self.data
+	}
+
+	#[inline(always)]
+	pub fn row(&self, i: usize) -> &[Complex] {
+		&self.data[i * self.cols..(i + 1) * self.cols]
+	}
+
+	#[inline(always)]
+	pub fn row_mut(&mut self, i: usize) -> &mut [Complex] {
+		&mut self.data[i * self.cols..(i + 1) * self.cols]
+	}
+
+	#[inline(always)]
+	pub fn iter_rows(&self) -> impl Iterator<Item = &[Complex]> {
+		(0..self.rows).map(|i| self.row(i))
+	}
+
+	#[inline(always)]
+	pub fn swap_rows(&mut self, a: usize, b: usize) {
+		for j in 0..self.cols {
+			self.data.swap(a * self.cols + j, b * self.cols + j);
+		}
+	}
+
+	#[inline(always)]
+	pub fn is_real(&self) -> bool {
+		self.data.iter().all(|z| z.is_real())
+	}
+
+	#[inline(always)]
+	pub fn map(&self, f: impl Fn(Complex) -> Complex) -> Self {
+		Self::new(
+			self.rows,
+			self.cols,
+			self.data.iter().map(|&z| f(z)).collect(),
+		)
+	}
+
+	/// Combines corresponding elements of two matrices of the same shape.
+	#[inline(always)]
+	pub fn zip_map(&self, other: &Matrix, f: impl Fn(Complex, Complex) -> Complex) -> Self {
+		debug_assert_eq!(self.shape(), other.shape());
+
+		Self::new(
+			self.rows,
+			self.cols,
+			self
+				.data
+				.iter()
+				.zip(&other.data)
+				.map(|(&a, &b)| f(a, b))
+				.collect(),
+		)
+	}
+
+	#[inline(always)]
+	pub fn transpose(&self) -> Self {
+		Self::from_fn(self.cols, self.rows, |i, j| self[(j, i)])
+	}
+
+	/// Matrix product, the columns of `self` must match the rows of `other`.
+	///
+	/// Walks both operands row by row so every inner loop runs over contiguous
+	/// memory, and skips the imaginary parts entirely when both are real.
+	#[inline(always)]
+	pub fn matmul(&self, other: &Matrix) -> Self {
+		debug_assert_eq!(self.cols, other.rows);
+
+		let (rows, cols) = (self.rows, other.cols);
+
+		if self.is_real() && other.is_real() {
+			let mut data = vec![Decimal::ZERO; rows * cols];
+
+			for (i, row) in self.iter_rows().enumerate() {
+				let out = &mut data[i * cols..(i + 1) * cols];
+
+				for (a, b_row) in row.iter().zip(other.iter_rows()) {
+					if a.re.is_zero() {
+						continue;
+					}
+
+					for (out, b) in out.iter_mut().zip(b_row) {
+						*out += a.re * b.re;
+					}
+				}
+			}
+
+			return Self::new(rows, cols, data.into_iter().map(Complex::real).collect());
+		}
+
+		let mut data = vec![Complex::ZERO; rows * cols];
+
+		for (i, row) in self.iter_rows().enumerate() {
+			let out = &mut data[i * cols..(i + 1) * cols];
+
+			for (&a, b_row) in row.iter().zip(other.iter_rows()) {
+				for (out, &b) in out.iter_mut().zip(b_row) {
+					*out = *out + a * b;
+				}
+			}
+		}
+
+		Self::new(rows, cols, data)
+	}
+}
+
+impl Index<(usize, usize)> for Matrix {
+	type Output = Complex;
+
+	fn index(&self, (i, j): (usize, usize)) -> &Complex {
+		&self.data[i * self.cols + j]
+	}
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+	fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Complex {
+		&mut self.data[i * self.cols + j]
+	}
+}
+
+/// Matrix of complex numbers in compressed sparse row form, only the non-zero
+/// elements are stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix {
+	rows: usize,
+	cols: usize,
+	/// Position in `indices` and `values` at which every row starts, followed by
+	/// the number of stored elements
+	offsets: Vec<usize>,
+	indices: Vec<usize>,
+	values: Vec<Complex>,
+}
+
+impl SparseMatrix {
+	/// Builds a matrix out of `(row, column, value)` entries which must lie
+	/// inside its shape, values given for the same position are summed.
+	#[inline(always)]
+	pub fn from_triplets(
+		rows: usize,
+		cols: usize,
+		mut triplets: Vec<(usize, usize, Complex)>,
+	) -> Self {
+		triplets.sort_by_key(|&(i, j, _)| (i, j));
+
+		let mut merged: Vec<(usize, usize, Complex)> = Vec::with_capacity(triplets.len());
+
+		for (i, j, z) in triplets {
+			assert!(i < rows && j < cols, "sparse entry lies outside the matrix");
+
+			match merged.last_mut() {
+				Some((k, l, sum)) if (*k, *l) == (i, j) => *sum = *sum + z,
+				_ => merged.push((i, j, z)),
+			}
+		}
+
+		let mut offsets = vec![0; rows + 1];
+		let mut indices = Vec::with_capacity(merged.len());
+		let mut values = Vec::with_capacity(merged.len());
+
+		for (i, j, z) in merged {
+			if z != Complex::ZERO {
+				offsets[i + 1] += 1;
+				indices.push(j);
+				values.push(z);
+			}
+		}
+
+		for i in 0..rows {
+			offsets[i + 1] += offsets[i];
+		}
+
+		Self {
+			rows,
+			cols,
+			offsets,
+			indices,
+			values,
+		}
+	}
+
+	#[inline(always)]
+	pub fn from_dense(matrix: &Matrix) -> Self {
+		let (rows, cols) = matrix.shape();
+
+		Self::from_triplets(
+			rows,
+			cols,
+			(0..rows)
+				.flat_map(|i| (0..cols).map(move |j| (i, j)))
+				.map(|(i, j)| (i, j, matrix[(i, j)]))
+				.filter(|&(.., z)| z != Complex::ZERO)
+				.collect(),
+		)
+	}
+
+	#[inline(always)]
+	pub fn to_dense(&self) -> Matrix {
+		let mut matrix = Matrix::zeros(self.rows, self.cols);
+
+		for (i, j, z) in self.entries() {
+			matrix[(i, j)] = z;
+		}
+
+		matrix
+	}
+
+	#[inline(always)]
+	pub fn rows(&self) -> usize {
+		self.rows
+	}
+
+	#[inline(always)]
+	pub fn cols(&self) -> usize {
+		self.cols
+	}
+
+	#[inline(always)]
+	pub fn shape(&self) -> (usize, usize) {
+		(self.rows, self.cols)
+	}
+
+	/// Number of stored, non-zero elements
+	#[inline(always)]
+	pub fn nnz(&self) -> usize {
+		self.values.len()
+	}
+
+	/// Columns and values of the stored elements of row `i`
+	#[inline(always)]
+	pub fn row(&self, i: usize) -> (&[usize], &[Complex]) {
+		let range = self.offsets[i]..self.offsets[i + 1];
+
+		(&self.indices[range.clone()], &self.values[range])
+	}
+
+	/// Stored elements as `(row, column, value)` in row-major order
+	#[inline(always)]
+	pub fn entries(&self) -> impl Iterator<Item = (usize, usize, Complex)> + '_ {
+		(0..self.rows).flat_map(move |i| {
+			let (indices, values) = self.row(i);
+
+			indices.iter().zip(values).map(move |(&j, &z)| (i, j, z))
+		})
+	}
+
+	/// Applies `f` to every stored element, elements which become zero are
+	/// dropped.
+	#[inline(always)]
+	pub fn map(&self, f: impl Fn(Complex) -> Complex) -> Self {
+		Self::from_triplets(
+			self.rows,
+			self.cols,
+			self.entries().map(|(i, j, z)| (i, j, f(z))).collect(),
+		)
+	}
+
+	#[inline(always)]
+	pub fn transpose(&self) -> Self {
+		Self::from_triplets(
+			self.cols,
+			self.rows,
+			self.entries().map(|(i, j, z)| (j, i, z)).collect(),
+		)
+	}
+
+	/// Product with the vector `x`, which must have an element for every column.
+	#[inline(always)]
+	pub fn mul_vector(&self, x: &[Complex]) -> Vec<Complex> {
+		debug_assert_eq!(self.cols, x.len());
+
+		(0..self.rows)
+			.map(|i| {
+				let (indices, values) = self.row(i);
+
+				indices
+					.iter()
+					.zip(values)
+					.fold(Complex::ZERO, |acc, (&j, &z)| acc + z * x[j])
+			})
+			.collect()
+	}
+
+	/// Product with a dense matrix, the columns of `self` must match the rows of
+	/// `other`.
+	#[inline(always)]
+	pub fn mul_dense(&self, other: &Matrix) -> Matrix {
+		debug_assert_eq!(self.cols, other.rows());
+
+		let cols = other.cols();
+		let mut data = vec![Complex::ZERO; self.rows * cols];
+
+		for i in 0..self.rows {
+			let out = &mut data[i * cols..(i + 1) * cols];
+			let (indices, values) = self.row(i);
+
+			for (&k, &a) in indices.iter().zip(values) {
+				for (out, &b) in out.iter_mut().zip(other.row(k)) {
+					*out = *out + a * b;
+				}
+			}
+		}
+
+		Matrix::new(self.rows, cols, data)
+	}
+
+	/// Product of two sparse matrices, each row of the result is gathered in a
+	/// dense accumulator which only the touched columns are read back from.
+	#[inline(always)]
+	pub fn matmul(&self, other: &SparseMatrix) -> Self {
+		debug_assert_eq!(self.cols, other.rows);
+
+		let mut accumulator = vec![Complex::ZERO; other.cols];
+		let mut touched = vec![false; other.cols];
+		let mut triplets = vec![];
+
+		for i in 0..self.rows {
+			let mut columns = vec![];
+			let (indices, values) = self.row(i);
+
+			for (&k, &a) in indices.iter().zip(values) {
+				let (indices, values) = other.row(k);
+
+				for (&j, &b) in indices.iter().zip(values) {
+					if !touched[j] {
+						touched[j] = true;
+						columns.push(j);
+					}
+
+					accumulator[j] = accumulator[j] + a * b;
+				}
+			}
+
+			for j in columns {
+				triplets.push((i, j, accumulator[j]));
+				accumulator[j] = Complex::ZERO;
+				touched[j] = false;
+			}
+		}
+
+		Self::from_triplets(self.rows, other.cols, triplets)
+	}
+}
+
+impl Matrix {
+	/// Product with a sparse matrix, the columns of `self` must match the rows of
+	/// `other`.
+	#[inline(always)]
+	pub fn mul_sparse(&self, other: &SparseMatrix) -> Self {
+		debug_assert_eq!(self.cols, other.rows());
+
+		let cols = other.cols();
+		let mut data = vec![Complex::ZERO; self.rows * cols];
+
+		for (i, row) in self.iter_rows().enumerate() {
+			let out = &mut data[i * cols..(i + 1) * cols];
+
+			for (k, &a) in row.iter().enumerate() {
+				if a == Complex::ZERO {
+					continue;
+				}
+
+				let (indices, values) = other.row(k);
+
+				for (&j, &b) in indices.iter().zip(values) {
+					out[j] = out[j] + a * b;
+				}
+			}
+		}
+
+		Self::new(self.rows, cols, data)
+	}
+}
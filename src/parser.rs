@@ -2,7 +2,7 @@ use std::{iter::Peekable, ops::Range, slice::Iter};
 
 use crate::{
 	errors::{EOLError, Error, SyntaxError, TypeError},
-	expr::Expression,
+	expr::{Expression, Operands, Slice, Slices},
 	token::{Token, TokenInfo},
 	types::DataType,
 };
@@ -74,13 +74,40 @@ impl<'a> Parser<'a> {
 
 				next_token = tokens.next();
 
+				if next_token.is_some() && next_token.unwrap().token == Token::LSquare {
+					let slices;
+
+					(slices, end) = self.parse_index(tokens)?;
+
+					next_token = tokens.next();
+
+					if next_token.is_none() || Token::Eq != next_token.unwrap().token {
+						return Err(match next_token {
+							Some(next_token) => SyntaxError::new(
+								Token::Eq,
+								next_token.token.clone(),
+								next_token.range.clone(),
+							)
+							.to_error(),
+							None => EOLError::new(end..end + 1).to_error(),
+						});
+					}
+
+					let (exp, range) = self.parser(tokens, 0)?;
+
+					return Ok((
+						Expression::IndexedAssignment(name.to_string(), slices, Box::new(exp)),
+						start..range.end,
+					));
+				}
+
 				if next_token.is_some() && next_token.unwrap().token == Token::Colon {
 					next_token = tokens.next();
 
 					if next_token.is_some()
 						&& let Token::Ident(ident) = &next_token.unwrap().token
 					{
-						datatype = Some(DataType::parse(ident));
+						datatype = Some(Self::datatype(ident, next_token.unwrap())?);
 						next_token = tokens.next();
 					} else {
 						let tokeninfo = next_token.unwrap();
@@ -120,10 +147,10 @@ impl<'a> Parser<'a> {
 					datatype = expr_type
 				}
 
-				if let Some(expression_type) = expr_type {
-					if expr_type.unwrap() != datatype.unwrap() {
-						return Err(TypeError::new(datatype.unwrap(), expression_type, range).to_error());
-					}
+				if let Some(expression_type) = expr_type
+					&& expression_type != datatype.unwrap()
+				{
+					return Err(TypeError::new(datatype.unwrap(), expression_type, range).to_error());
 				}
 
 				expr = Some(Expression::Assignment(
@@ -132,6 +159,44 @@ impl<'a> Parser<'a> {
 				));
 				end = range.end;
 			}
+			Token::Sym => {
+				let mut names = vec![];
+
+				// A list of names separated by commas, eg sym x, y
+				loop {
+					let next_token = tokens.next();
+
+					let name = if next_token.is_some()
+						&& let Token::Ident(name) = &next_token.unwrap().token
+					{
+						name
+					} else {
+						return Err(match next_token {
+							Some(next_token) => SyntaxError::new(
+								Token::Ident("ident".to_string()),
+								next_token.token.clone(),
+								next_token.range.clone(),
+							)
+							.to_error(),
+							None => EOLError::new(end..end + 1).to_error(),
+						});
+					};
+
+					names.push(name.to_string());
+					end = next_token.unwrap().range.end;
+
+					if tokens
+						.peek()
+						.is_none_or(|token| token.token != Token::Comma)
+					{
+						break;
+					}
+
+					end = tokens.next().unwrap().range.end;
+				}
+
+				expr = Some(Expression::Symbols(names));
+			}
 			Token::Fn => {
 				let next_token = tokens.next();
 
@@ -186,7 +251,7 @@ impl<'a> Parser<'a> {
 						if next_token.is_some()
 							&& let Token::Ident(ident) = &next_token.unwrap().token
 						{
-							datatype = Some(DataType::parse(ident))
+							datatype = Some(Self::datatype(ident, next_token.unwrap())?)
 						} else {
 							let tokeninfo = tokens.next().unwrap();
 
@@ -222,7 +287,7 @@ impl<'a> Parser<'a> {
 					if next_token.is_some()
 						&& let Token::Ident(ident) = &next_token.unwrap().token
 					{
-						return_type = Some(DataType::parse(ident));
+						return_type = Some(Self::datatype(ident, next_token.unwrap())?);
 						next_token = tokens.next();
 					} else {
 						let tokeninfo = tokens.next().unwrap();
@@ -258,10 +323,10 @@ impl<'a> Parser<'a> {
 
 				let expr_type = exp.infer_datatype();
 
-				if let Some(expression_type) = expr_type {
-					if expression_type != return_type.unwrap() {
-						return Err(TypeError::new(return_type.unwrap(), expression_type, range).to_error());
-					}
+				if let Some(expression_type) = expr_type
+					&& expression_type != return_type.unwrap()
+				{
+					return Err(TypeError::new(return_type.unwrap(), expression_type, range).to_error());
 				}
 
 				expr = Some(Expression::FunctionDeclaration(
@@ -274,12 +339,12 @@ impl<'a> Parser<'a> {
 				end = range.end;
 			}
 			Token::Ident(i) => {
-				// An identifier can either be a function call, in multiplication with a mod
-				// or simply an identifier, eg read(), a|b|, c
+				// An identifier can either be a function call, in multiplication with a mod,
+				// indexed or simply an identifier, eg read(), a|b|, A[1, 2], c
 				let peeked_token = tokens.peek();
 				if peeked_token.is_some()
 					&& self.infix_binding_power(&peeked_token.unwrap().token) == (0, 0)
-					&& ![Token::RParen, Token::Abs].contains(&peeked_token.unwrap().token)
+					&& ![Token::RParen, Token::Abs, Token::LSquare].contains(&peeked_token.unwrap().token)
 				{
 					let exp;
 
@@ -308,6 +373,8 @@ impl<'a> Parser<'a> {
 
 				let mut row_tokens: Vec<TokenInfo> = vec![];
 
+				let mut depth = 0;
+
 				loop {
 					let t = tokens.peek();
 
@@ -317,7 +384,13 @@ impl<'a> Parser<'a> {
 
 					let t = tokens.next().unwrap();
 
-					if t.token == Token::RSquare {
+					// Brackets nested inside an element, eg `[A[1, 1], f(2, 3)]`, belong to
+					// that element
+					if [Token::LParen, Token::LSquare, Token::LCurly].contains(&t.token) {
+						depth += 1;
+					} else if [Token::RParen, Token::RSquare, Token::RCurly].contains(&t.token) && depth > 0 {
+						depth -= 1;
+					} else if t.token == Token::RSquare {
 						if !row_tokens.is_empty() {
 							let exp;
 
@@ -329,9 +402,7 @@ impl<'a> Parser<'a> {
 						end = t.range.end;
 						matrix.push(row);
 						break;
-					}
-
-					if t.token == Token::Semi {
+					} else if t.token == Token::Semi && depth == 0 {
 						if !row_tokens.is_empty() {
 							let exp;
 
@@ -344,9 +415,7 @@ impl<'a> Parser<'a> {
 						matrix.push(row.clone());
 						row.clear();
 						continue;
-					}
-
-					if t.token == Token::Comma {
+					} else if t.token == Token::Comma && depth == 0 {
 						let exp;
 
 						(exp, _) = self.parser(&mut row_tokens.iter().peekable(), 0)?;
@@ -362,6 +431,13 @@ impl<'a> Parser<'a> {
 
 				expr = Some(Expression::Matrix(matrix));
 			}
+			Token::LCurly => {
+				let exp;
+
+				(exp, end) = self.parse_list(tokens)?;
+
+				expr = Some(exp);
+			}
 			Token::Abs => {
 				let exp;
 				let range;
@@ -405,6 +481,18 @@ impl<'a> Parser<'a> {
 				break;
 			}
 
+			// Indexing binds tighter than any infix operator, eg A[1, 1]^2
+			if op.unwrap().token == Token::LSquare && expr.is_some() {
+				tokens.next();
+
+				let slices;
+
+				(slices, end) = self.parse_index(tokens)?;
+
+				expr = Some(Expression::Index(Box::new(expr.unwrap()), slices));
+				continue;
+			}
+
 			let (lbp, rbp) = self.infix_binding_power(&op.unwrap().token);
 
 			if lbp < prec {
@@ -419,7 +507,12 @@ impl<'a> Parser<'a> {
 			(rhs, range) = match self.parser(tokens, rbp) {
 				Ok(t) => t,
 				Err(t) => match t {
-					Error::SyntaxError(..) | Error::TypeError(..) | Error::EOLError(..) => {
+					Error::SyntaxError(..)
+					| Error::TypeError(..)
+					| Error::EOLError(..)
+					| Error::IndexError(..)
+					| Error::ShapeError(..)
+					| Error::ValueError(..) => {
 						return Err(t);
 					}
 					Error::LogicError(..) => {
@@ -428,12 +521,13 @@ impl<'a> Parser<'a> {
 				},
 			};
 
-			end = range.end;
 			expr = Some(Expression::Binary(
 				Box::new(expr.unwrap()),
 				op.token.clone(),
 				Box::new(rhs),
+				Operands(start..end, range.clone()),
 			));
+			end = range.end;
 		}
 
 		if expr.is_none() {
@@ -453,7 +547,6 @@ impl<'a> Parser<'a> {
 		let mut params = vec![];
 		let mut expression = vec![];
 
-		let start = tokens.peek().unwrap().range.start;
 		let mut end = tokens.next().unwrap().range.end;
 
 		loop {
@@ -469,13 +562,12 @@ impl<'a> Parser<'a> {
 
 			end = tokeninfo.range.end;
 
-			if *token == Token::RParen {
+			if [Token::RParen, Token::RSquare, Token::RCurly].contains(token) {
 				if depth == 0 {
 					if !expression.is_empty() && depth == 0 {
-						let mut lex = expression.iter().peekable();
-						let data = self.parser(&mut lex, 0)?.0;
+						let data = self.parse_argument(&expression)?;
 
-						params.push((data, start..end));
+						params.push((data, Self::span(&expression)));
 						expression.clear();
 					}
 					break;
@@ -483,15 +575,14 @@ impl<'a> Parser<'a> {
 				depth -= 1;
 			}
 
-			if *token == Token::LParen {
+			if [Token::LParen, Token::LSquare, Token::LCurly].contains(token) {
 				depth += 1;
 			}
 
 			if *token == Token::Comma && depth == 0 {
-				let mut lex = expression.iter().peekable();
-				let data = self.parser(&mut lex, 0)?.0;
+				let data = self.parse_argument(&expression)?;
 
-				params.push((data, start..end));
+				params.push((data, Self::span(&expression)));
 
 				expression.clear();
 				continue;
@@ -501,16 +592,211 @@ impl<'a> Parser<'a> {
 		}
 
 		if !expression.is_empty() {
-			let mut lex = expression.iter().peekable();
-			let data = self.parser(&mut lex, 0)?.0;
+			let data = self.parse_argument(&expression)?;
 
-			params.push((data, start..end));
+			params.push((data, Self::span(&expression)));
 			expression.clear();
 		}
 
 		Ok((Expression::FunctionCall(i.to_string(), params), end))
 	}
 
+	/// Type named after a `:`
+	#[inline(always)]
+	fn datatype(ident: &str, tokeninfo: &TokenInfo) -> Result<DataType, Error> {
+		DataType::parse(ident).ok_or_else(|| {
+			SyntaxError::new(
+				Token::Ident("type".to_string()),
+				tokeninfo.token.clone(),
+				tokeninfo.range.clone(),
+			)
+			.to_error()
+		})
+	}
+
+	/// Range covering every token of an argument
+	#[inline(always)]
+	fn span(tokens: &[TokenInfo]) -> Range<usize> {
+		tokens.first().unwrap().range.start..tokens.last().unwrap().range.end
+	}
+
+	/// An argument of a function call, where `name = value` binds a value to a
+	/// name for that call only, eg eval(y, x = 2)
+	#[inline(always)]
+	fn parse_argument(&self, tokens: &[TokenInfo]) -> Result<Expression, Error> {
+		if let [name, eq, value @ ..] = tokens
+			&& let Token::Ident(name) = &name.token
+			&& eq.token == Token::Eq
+			&& !value.is_empty()
+		{
+			let (value, _) = self.parser(&mut value.iter().peekable(), 0)?;
+
+			return Ok(Expression::Assignment(
+				(name.to_string(), None),
+				Box::new(value),
+			));
+		}
+
+		Ok(self.parser(&mut tokens.iter().peekable(), 0)?.0)
+	}
+
+	#[inline(always)]
+	pub fn parse_list<'b>(
+		&'b self,
+		tokens: &mut Peekable<Iter<'b, TokenInfo>>,
+	) -> Result<(Expression, usize), Error> {
+		let mut depth = 0;
+		let mut list = vec![];
+		let mut expression = vec![];
+
+		let mut end = 0;
+
+		loop {
+			let tokeninfo = tokens.next();
+
+			if tokeninfo.is_none() {
+				return Err(EOLError::new(end..end + 1).to_error());
+			}
+
+			let tokeninfo = &tokeninfo.unwrap();
+
+			let token = &tokeninfo.token;
+
+			end = tokeninfo.range.end;
+
+			if [Token::RCurly, Token::RParen, Token::RSquare].contains(token) {
+				if depth == 0 {
+					if !expression.is_empty() {
+						let mut lex = expression.iter().peekable();
+						list.push(self.parser(&mut lex, 0)?.0);
+					}
+					break;
+				}
+				depth -= 1;
+			}
+
+			if [Token::LCurly, Token::LParen, Token::LSquare].contains(token) {
+				depth += 1;
+			}
+
+			if *token == Token::Comma && depth == 0 {
+				let mut lex = expression.iter().peekable();
+				list.push(self.parser(&mut lex, 0)?.0);
+
+				expression.clear();
+				continue;
+			}
+
+			expression.push((*tokeninfo).to_owned());
+		}
+
+		Ok((Expression::List(list), end))
+	}
+
+	/// Parses the indices between square brackets after the opening bracket has
+	/// been consumed, eg `i, j`, `:, 1` or `1..3, :`
+	#[inline(always)]
+	pub fn parse_index<'b>(
+		&'b self,
+		tokens: &mut Peekable<Iter<'b, TokenInfo>>,
+	) -> Result<(Slices, usize), Error> {
+		let mut depth = 0;
+		let mut slices = vec![];
+		let mut expression = vec![];
+
+		let mut end = 0;
+
+		loop {
+			let tokeninfo = tokens.next();
+
+			if tokeninfo.is_none() {
+				return Err(EOLError::new(end..end + 1).to_error());
+			}
+
+			let tokeninfo = &tokeninfo.unwrap();
+
+			let token = &tokeninfo.token;
+
+			end = tokeninfo.range.end;
+
+			if [Token::RSquare, Token::RParen, Token::RCurly].contains(token) {
+				if depth == 0 {
+					slices.push(self.parse_slice(&expression, tokeninfo)?);
+					break;
+				}
+				depth -= 1;
+			}
+
+			if [Token::LSquare, Token::LParen, Token::LCurly].contains(token) {
+				depth += 1;
+			}
+
+			if *token == Token::Comma && depth == 0 {
+				slices.push(self.parse_slice(&expression, tokeninfo)?);
+
+				expression.clear();
+				continue;
+			}
+
+			expression.push((*tokeninfo).to_owned());
+		}
+
+		Ok((slices, end))
+	}
+
+	#[inline(always)]
+	fn parse_slice(
+		&self,
+		tokens: &[TokenInfo],
+		delimiter: &TokenInfo,
+	) -> Result<(Slice, Range<usize>), Error> {
+		let (Some(first), Some(last)) = (tokens.first(), tokens.last()) else {
+			return Err(
+				SyntaxError::new(
+					Token::Ident("index".to_string()),
+					delimiter.token.clone(),
+					delimiter.range.clone(),
+				)
+				.to_error(),
+			);
+		};
+
+		let range = first.range.start..last.range.end;
+
+		if tokens.len() == 1 && first.token == Token::Colon {
+			return Ok((Slice::All, range));
+		}
+
+		let mut depth = 0;
+
+		for (i, tokeninfo) in tokens.iter().enumerate() {
+			if [Token::LSquare, Token::LParen, Token::LCurly].contains(&tokeninfo.token) {
+				depth += 1;
+			} else if [Token::RSquare, Token::RParen, Token::RCurly].contains(&tokeninfo.token) {
+				depth -= 1;
+			} else if tokeninfo.token == Token::DotDot && depth == 0 {
+				let lower = if i == 0 {
+					None
+				} else {
+					Some(self.parser(&mut tokens[..i].iter().peekable(), 0)?.0)
+				};
+
+				let upper = if i + 1 == tokens.len() {
+					None
+				} else {
+					Some(self.parser(&mut tokens[i + 1..].iter().peekable(), 0)?.0)
+				};
+
+				return Ok((Slice::Range(lower, upper), range));
+			}
+		}
+
+		Ok((
+			Slice::Single(self.parser(&mut tokens.iter().peekable(), 0)?.0),
+			range,
+		))
+	}
+
 	#[inline(always)]
 	pub fn parse_if<'b>(
 		&'b self,
@@ -578,10 +864,11 @@ impl<'a> Parser<'a> {
 
 	fn infix_binding_power(&self, op: &Token) -> (u16, u16) {
 		match op {
-			Token::Add | Token::Sub => (1, 2),
-			Token::Mul | Token::Div | Token::Rem => (3, 4),
-			Token::Pow => (5, 6),
-			Token::IsEq | Token::NEq | Token::Gt | Token::Lt | Token::GtEq | Token::LtEq => (7, 8),
+			// Comparisons bind loosest, so both sides of an equation are whole
+			Token::IsEq | Token::NEq | Token::Gt | Token::Lt | Token::GtEq | Token::LtEq => (1, 2),
+			Token::Add | Token::Sub | Token::PlusMinus => (3, 4),
+			Token::Mul | Token::Div | Token::Rem | Token::DotMul | Token::DotDiv => (5, 6),
+			Token::Pow | Token::DotPow => (7, 8),
 			Token::If | Token::Then | Token::Else | Token::End => (9, 10),
 			_ => (0, 0),
 		}
@@ -0,0 +1,212 @@
+use rust_decimal::Decimal;
+
+use crate::{
+	algebra::{divide, multiply},
+	errors::Error,
+	expr::Expression,
+	simplify::{Ratio, number, power, product, ratio, sum},
+};
+
+/// Polynomial of one variable with exact coefficients, from the highest power
+/// down to the constant and without leading zeros
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+	variable: String,
+	coefficients: Vec<Ratio>,
+}
+
+impl Polynomial {
+	/// Leading zeros are dropped, and the zero polynomial keeps a single one
+	#[inline(always)]
+	pub(crate) fn new(variable: &str, coefficients: Vec<Ratio>) -> Polynomial {
+		let mut coefficients = coefficients
+			.into_iter()
+			.skip_while(|c| c.is_zero())
+			.collect::<Vec<Ratio>>();
+
+		if coefficients.is_empty() {
+			coefficients.push(Ratio::ZERO);
+		}
+
+		Polynomial {
+			variable: variable.to_string(),
+			coefficients,
+		}
+	}
+
+	#[inline(always)]
+	pub(crate) fn constant(variable: &str, c: Ratio) -> Polynomial {
+		Polynomial::new(variable, vec![c])
+	}
+
+	/// Polynomial in `x` an expression expands to, when its coefficients are
+	/// numbers
+	pub(crate) fn from_expression(expr: &Expression, x: &str) -> Result<Polynomial, Error> {
+		let coefficients = expr
+			.coefficients(x)?
+			.iter()
+			.map(ratio)
+			.collect::<Option<Vec<Ratio>>>()
+			.ok_or_else(|| {
+				Error::LogicError(format!(
+					"expected a polynomial in `{x}` whose coefficients are numbers, found `{expr}`"
+				))
+			})?;
+
+		Ok(Polynomial::new(x, coefficients))
+	}
+
+	#[inline(always)]
+	pub fn variable(&self) -> &str {
+		&self.variable
+	}
+
+	#[inline(always)]
+	pub(crate) fn coefficients(&self) -> &[Ratio] {
+		&self.coefficients
+	}
+
+	#[inline(always)]
+	pub fn degree(&self) -> usize {
+		self.coefficients.len() - 1
+	}
+
+	#[inline(always)]
+	pub fn is_zero(&self) -> bool {
+		self.coefficients[0].is_zero()
+	}
+
+	/// The polynomial written out as a sum of powers of its variable
+	pub fn expression(&self) -> Expression {
+		let x = Expression::Identifier(self.variable.clone());
+		let degree = self.degree();
+
+		sum(
+			self
+				.coefficients
+				.iter()
+				.enumerate()
+				.map(|(i, c)| {
+					product(vec![
+						c.expression(),
+						power(x.clone(), number(Decimal::from(degree - i))),
+					])
+				})
+				.collect(),
+		)
+		.collect(&self.variable)
+	}
+
+	pub(crate) fn add(&self, other: &Polynomial) -> Polynomial {
+		let len = self.coefficients.len().max(other.coefficients.len());
+		let padded = |f: &Polynomial| {
+			let mut coefficients = vec![Ratio::ZERO; len - f.coefficients.len()];
+			coefficients.extend(&f.coefficients);
+			coefficients
+		};
+
+		Polynomial::new(
+			&self.variable,
+			padded(self)
+				.into_iter()
+				.zip(padded(other))
+				.map(|(a, b)| a.add(b))
+				.collect(),
+		)
+	}
+
+	#[inline(always)]
+	pub(crate) fn neg(&self) -> Polynomial {
+		self.scale(Ratio::ONE.neg())
+	}
+
+	#[inline(always)]
+	pub(crate) fn mul(&self, other: &Polynomial) -> Polynomial {
+		Polynomial::new(
+			&self.variable,
+			multiply(&self.coefficients, &other.coefficients),
+		)
+	}
+
+	#[inline(always)]
+	pub(crate) fn scale(&self, c: Ratio) -> Polynomial {
+		Polynomial::new(
+			&self.variable,
+			self.coefficients.iter().map(|&a| a.mul(c)).collect(),
+		)
+	}
+
+	pub(crate) fn pow(&self, n: u32) -> Polynomial {
+		(0..n).fold(Polynomial::constant(&self.variable, Ratio::ONE), |f, _| {
+			f.mul(self)
+		})
+	}
+
+	/// Quotient and remainder of long division, unless dividing by zero
+	#[inline(always)]
+	pub(crate) fn divide(&self, other: &Polynomial) -> Option<(Polynomial, Polynomial)> {
+		if other.is_zero() {
+			return None;
+		}
+
+		let (quotient, remainder) = divide(&self.coefficients, &other.coefficients)?;
+
+		Some((
+			Polynomial::new(&self.variable, quotient),
+			Polynomial::new(&self.variable, remainder),
+		))
+	}
+
+	/// Greatest common divisor by the Euclidean algorithm, scaled to a leading
+	/// coefficient of one
+	pub(crate) fn gcd(&self, other: &Polynomial) -> Polynomial {
+		let (mut f, mut g) = (self.clone(), other.clone());
+
+		while let Some((_, remainder)) = f.divide(&g) {
+			(f, g) = (g, remainder);
+		}
+
+		match f.coefficients[0].recip() {
+			Some(lead) => f.scale(lead),
+			None => f,
+		}
+	}
+
+	pub(crate) fn derivative(&self) -> Polynomial {
+		let degree = self.degree();
+
+		Polynomial::new(
+			&self.variable,
+			self.coefficients[..degree]
+				.iter()
+				.enumerate()
+				.map(|(i, c)| c.mul(Ratio::new(Decimal::from(degree - i), Decimal::ONE).unwrap()))
+				.collect(),
+		)
+	}
+
+	/// Antiderivative whose constant term is zero
+	pub(crate) fn integral(&self) -> Polynomial {
+		let degree = self.degree();
+
+		let mut coefficients = self
+			.coefficients
+			.iter()
+			.enumerate()
+			.map(|(i, c)| c.mul(Ratio::new(Decimal::ONE, Decimal::from(degree - i + 1)).unwrap()))
+			.collect::<Vec<Ratio>>();
+
+		coefficients.push(Ratio::ZERO);
+
+		Polynomial::new(&self.variable, coefficients)
+	}
+
+	/// Value at a number by Horner's method
+	#[inline(always)]
+	pub(crate) fn value(&self, x: Ratio) -> Ratio {
+		self
+			.coefficients
+			.iter()
+			.fold(Ratio::ZERO, |value, &c| value.mul(x).add(c))
+	}
+}
@@ -0,0 +1,79 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rust_decimal::Decimal;
+
+/// Pseudo random number generator (xoshiro256**) which produces the same
+/// sequence every time it is given the same seed.
+#[derive(Debug, Clone)]
+pub struct Random {
+	state: [u64; 4],
+}
+
+impl Default for Random {
+	/// Seeded from the current time, so every run differs until `seed` is used.
+	fn default() -> Self {
+		Self::new(
+			SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.unwrap()
+				.as_nanos() as u64,
+		)
+	}
+}
+
+impl Random {
+	/// Spreads `seed` over the whole state with splitmix64 so that similar seeds
+	/// still start far apart.
+	#[inline(always)]
+	pub fn new(mut seed: u64) -> Self {
+		let mut state = [0; 4];
+
+		for word in &mut state {
+			seed = seed.wrapping_add(0x9e3779b97f4a7c15);
+
+			let mut z = seed;
+			z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+			z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+			*word = z ^ (z >> 31);
+		}
+
+		Self { state }
+	}
+
+	#[inline(always)]
+	pub fn next_u64(&mut self) -> u64 {
+		let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+		let t = self.state[1] << 17;
+
+		self.state[2] ^= self.state[0];
+		self.state[3] ^= self.state[1];
+		self.state[1] ^= self.state[2];
+		self.state[0] ^= self.state[3];
+		self.state[2] ^= t;
+		self.state[3] = self.state[3].rotate_left(45);
+
+		result
+	}
+
+	/// Uniformly distributed number in `[0, 1)` with 64 random bits.
+	#[inline(always)]
+	pub fn uniform(&mut self) -> Decimal {
+		Decimal::from(self.next_u64()) / Decimal::from(u64::MAX as u128 + 1)
+	}
+
+	/// Uniformly distributed integer in `[0, n)`, found by rejecting the values
+	/// which would favour the smaller results.
+	#[inline(always)]
+	pub fn below(&mut self, n: u64) -> u64 {
+		let zone = u64::MAX - u64::MAX % n;
+
+		loop {
+			let x = self.next_u64();
+
+			if x < zone {
+				return x % n;
+			}
+		}
+	}
+}
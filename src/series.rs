@@ -0,0 +1,984 @@
+use rust_decimal::{Decimal, MathematicalOps, prelude::ToPrimitive};
+
+use crate::{
+	errors::Error,
+	expr::{Expression, Operands},
+	interpreter::{Function, InterpreterContext},
+	simplify::{Ratio, negate, number, power, product, ratio, sum},
+	token::Token,
+	types::Data,
+};
+
+/// Most derivatives taken in a row in finding a limit, by L'Hôpital's rule or
+/// to find the side a quantity approaches zero from
+const LARGEST_DEPTH: usize = 8;
+
+/// Largest difference between the limits from either side of a point which
+/// is put down to rounding
+const TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 18);
+
+/// Powers of `t` past the first which a series is worked out to
+const ORDER: Decimal = Decimal::from_parts(8, 0, 0, false, 0);
+
+/// Order of a series which is exact, such as a polynomial
+const EXACT: Decimal = Decimal::from_parts(1_000_000, 0, 0, false, 0);
+
+/// Most terms of a series substituted into the series of a function
+const LARGEST_TERMS: usize = 24;
+
+/// Coefficients of a series smaller than this are put down to rounding
+const NEGLIGIBLE: Decimal = Decimal::from_parts(1, 0, 0, false, 20);
+
+/// Terms `c t^e` of a series in increasing powers of `t`, as `t` approaches
+/// zero from above, which are exact below the power `order`
+#[derive(Debug, Clone)]
+struct Series {
+	terms: Vec<(Ratio, Decimal)>,
+	order: Decimal,
+}
+
+impl Series {
+	/// Series of the terms with like powers collected and those past the order
+	/// dropped
+	fn new(terms: Vec<(Ratio, Decimal)>, order: Decimal) -> Option<Series> {
+		let mut terms = terms;
+		terms.sort_by_key(|(a, _)| a.value());
+
+		let mut collected: Vec<(Ratio, Decimal)> = vec![];
+
+		for (e, c) in terms {
+			if e.value() >= order {
+				continue;
+			}
+
+			match collected.last_mut() {
+				Some((last, sum)) if last.value() == e.value() => *sum = sum.checked_add(c)?,
+				_ => collected.push((e, c)),
+			}
+		}
+
+		collected.retain(|(_, c)| c.abs() > NEGLIGIBLE);
+
+		Some(Series {
+			terms: collected,
+			order,
+		})
+	}
+
+	#[inline(always)]
+	fn constant(c: Decimal) -> Option<Series> {
+		Series::new(vec![(Ratio::ZERO, c)], EXACT)
+	}
+
+	#[inline(always)]
+	fn lead(&self) -> Option<(Ratio, Decimal)> {
+		self.terms.first().copied()
+	}
+
+	#[inline(always)]
+	fn add(&self, other: &Series) -> Option<Series> {
+		Series::new(
+			self.terms.iter().chain(&other.terms).copied().collect(),
+			self.order.min(other.order),
+		)
+	}
+
+	#[inline(always)]
+	fn neg(&self) -> Series {
+		Series {
+			terms: self.terms.iter().map(|(e, c)| (*e, -c)).collect(),
+			order: self.order,
+		}
+	}
+
+	fn mul(&self, other: &Series) -> Option<Series> {
+		let mut terms = vec![];
+
+		for (a, c) in &self.terms {
+			for (b, d) in &other.terms {
+				terms.push((a.add(*b), c.checked_mul(*d)?));
+			}
+		}
+
+		// The first unknown term of either, times the first term of the other
+		let order = match (self.lead(), other.lead()) {
+			(Some((a, _)), Some((b, _))) => (a.value() + other.order).min(b.value() + self.order),
+			(None, Some((b, _))) => self.order + b.value(),
+			(Some((a, _)), None) => a.value() + other.order,
+			(None, None) => self.order + other.order,
+		};
+
+		Series::new(terms, order.min(EXACT))
+	}
+
+	/// The series written as `c t^e (1 + u)`, where `u` only has positive powers
+	fn factor(&self) -> Option<(Ratio, Decimal, Series)> {
+		let (e, c) = self.lead()?;
+
+		let u = Series::new(
+			self.terms[1..]
+				.iter()
+				.map(|(power, coefficient)| Some((power.add(e.neg()), coefficient.checked_div(c)?)))
+				.collect::<Option<Vec<(Ratio, Decimal)>>>()?,
+			self.order - e.value(),
+		)?;
+
+		Some((e, c, u))
+	}
+
+	/// `a₀ + a₁ u + a₂ u² + ...` for a series `u` which approaches zero, with
+	/// the coefficients given by their index
+	fn compose(u: &Series, coefficient: impl Fn(usize) -> Option<Decimal>) -> Option<Series> {
+		let count = match u.lead() {
+			Some((m, _)) => (ORDER / m.value())
+				.ceil()
+				.to_usize()
+				.map_or(LARGEST_TERMS, |count| (count + 1).min(LARGEST_TERMS)),
+			None => 1,
+		};
+
+		let mut power = Series::constant(Decimal::ONE)?;
+		let mut terms = vec![];
+
+		for k in 0..count {
+			if k > 0 {
+				power = power.mul(u)?;
+			}
+
+			let a = coefficient(k)?;
+
+			for (e, c) in &power.terms {
+				terms.push((*e, c.checked_mul(a)?));
+			}
+		}
+
+		let order = match u.lead() {
+			Some((m, _)) => u.order.min(m.value() * Decimal::from(count)),
+			None => u.order,
+		};
+
+		Series::new(terms, order.min(ORDER))
+	}
+
+	/// The series times `c t^e`
+	#[inline(always)]
+	fn scale(&self, e: Ratio, c: Decimal) -> Option<Series> {
+		Series::new(
+			self
+				.terms
+				.iter()
+				.map(|(power, coefficient)| Some((power.add(e), coefficient.checked_mul(c)?)))
+				.collect::<Option<Vec<(Ratio, Decimal)>>>()?,
+			self.order + e.value(),
+		)
+	}
+
+	#[inline(always)]
+	fn recip(&self) -> Option<Series> {
+		let (e, c, u) = self.factor()?;
+
+		Series::compose(&u, |k| {
+			Some(if k % 2 == 0 {
+				Decimal::ONE
+			} else {
+				Decimal::NEGATIVE_ONE
+			})
+		})?
+		.scale(e.neg(), Decimal::ONE.checked_div(c)?)
+	}
+
+	/// The series raised to a constant power, by the binomial series
+	fn pow(&self, p: Ratio) -> Option<Series> {
+		let (e, c, u) = self.factor()?;
+
+		let scale = match p.integer() {
+			Some(n) => c.checked_powi(n)?,
+			None if c.is_sign_positive() => c.checked_powd(p.value())?,
+			None => return None,
+		};
+
+		Series::compose(&u, |k| {
+			let mut binomial = Decimal::ONE;
+
+			for j in 0..k {
+				binomial = binomial.checked_mul(p.value() - Decimal::from(j))? / Decimal::from(j + 1);
+			}
+
+			Some(binomial)
+		})?
+		.scale(e.mul(p), scale)
+	}
+
+	/// Series of a call to a function of one variable, by its Taylor series
+	/// about the value its argument approaches
+	fn call(name: &str, s: &Series, t: &str, ctx: &mut InterpreterContext) -> Option<Series> {
+		let a = match s.lead() {
+			Some((e, _)) if e.is_negative() => return None,
+			Some((e, c)) if e.is_zero() => c,
+			_ => Decimal::ZERO,
+		};
+
+		let smooth = match name {
+			"sin" | "cos" | "sinh" | "cosh" | "atan" | "erf" | "erfc" | "exp" => true,
+			"ln" | "log10" => a.is_sign_positive() && !a.is_zero(),
+			"tan" => a.checked_cos().is_some_and(|cos| cos.abs() > NEGLIGIBLE),
+			_ => false,
+		};
+
+		if !smooth {
+			return None;
+		}
+
+		let h = s.add(&Series::constant(-a)?)?;
+
+		let variable = Data::Ident(t.to_string());
+		let mut derivative = call(name, vec![Expression::Identifier(t.to_string())]);
+		let mut coefficients = vec![];
+		let mut factorial = Decimal::ONE;
+
+		for k in 0..LARGEST_TERMS {
+			if k > 0 {
+				derivative = derivative.differentiate(&variable, ctx).ok()?;
+				factorial = factorial.checked_mul(Decimal::from(k))?;
+			}
+
+			let Ok(Limit::Finite(value)) =
+				value(derivative.substitute(&[(t.to_string(), number(a))]), ctx)
+			else {
+				return None;
+			};
+
+			coefficients.push(value.checked_div(factorial)?);
+
+			if h
+				.lead()
+				.is_none_or(|(m, _)| m.value() * Decimal::from(k) >= ORDER)
+			{
+				break;
+			}
+		}
+
+		Series::compose(&h, |k| coefficients.get(k).copied().or(Some(Decimal::ZERO)))
+	}
+}
+
+/// Series of `f` in increasing powers of `t`, if it has one
+fn expand(f: &Expression, t: &str, ctx: &mut InterpreterContext) -> Option<Series> {
+	if let Some(a) = ratio(f) {
+		return Series::constant(a.value());
+	}
+
+	match f {
+		Expression::Identifier(name) if name == t => {
+			Series::new(vec![(Ratio::ONE, Decimal::ONE)], EXACT)
+		}
+		Expression::Identifier(name) => match ctx.0.get(name).map(|variable| &variable.value) {
+			Some(Data::Number(a, b)) if b.is_zero() => Series::constant(*a),
+			_ => None,
+		},
+		Expression::Abs(g) => {
+			let g = expand(g, t, ctx)?;
+
+			if g.lead()?.1.is_sign_negative() {
+				Some(g.neg())
+			} else {
+				Some(g)
+			}
+		}
+		Expression::Binary(g, op, h, _) => {
+			let g = expand(g, t, ctx)?;
+
+			match op {
+				Token::Pow => match ratio(h) {
+					Some(p) => g.pow(p),
+					// f^g = e^(g ln f)
+					None => {
+						let logarithm = Series::call("ln", &g, t, ctx)?;
+
+						Series::call("exp", &expand(h, t, ctx)?.mul(&logarithm)?, t, ctx)
+					}
+				},
+				Token::Add => g.add(&expand(h, t, ctx)?),
+				Token::Sub => g.add(&expand(h, t, ctx)?.neg()),
+				Token::Mul => g.mul(&expand(h, t, ctx)?),
+				Token::Div => g.mul(&expand(h, t, ctx)?.recip()?),
+				_ => None,
+			}
+		}
+		Expression::FunctionCall(name, args) if args.len() == 1 => {
+			let s = expand(&args[0].0, t, ctx)?;
+
+			match name.as_str() {
+				"sqrt" => s.pow(Ratio::new(Decimal::ONE, Decimal::TWO)?),
+				"cbrt" => s.pow(Ratio::new(Decimal::ONE, Decimal::from(3))?),
+				name => Series::call(name, &s, t, ctx),
+			}
+		}
+		_ => None,
+	}
+}
+
+/// A point a limit is taken at, or the value of a limit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Limit {
+	Finite(Decimal),
+	/// Positive or negative infinity
+	Infinite(bool),
+}
+
+impl Limit {
+	#[inline(always)]
+	fn is_zero(self) -> bool {
+		self == Limit::Finite(Decimal::ZERO)
+	}
+
+	/// The limit with its sign flipped when `negative`
+	#[inline(always)]
+	fn signed(self, negative: bool) -> Limit {
+		match self {
+			Limit::Finite(a) if negative => Limit::Finite(-a),
+			Limit::Infinite(positive) => Limit::Infinite(positive != negative),
+			limit => limit,
+		}
+	}
+
+	#[inline(always)]
+	pub fn data(self) -> Data {
+		match self {
+			Limit::Finite(a) => Data::new_real(a.round_dp(24).normalize()),
+			Limit::Infinite(true) => Data::Ident("inf".to_string()),
+			Limit::Infinite(false) => Data::Expression(negate(Expression::Identifier("inf".to_string()))),
+		}
+	}
+}
+
+#[inline(always)]
+fn call(name: &str, args: Vec<Expression>) -> Expression {
+	Expression::FunctionCall(
+		name.to_string(),
+		args.into_iter().map(|arg| (arg, 0..0)).collect(),
+	)
+}
+
+#[inline(always)]
+fn quotient(numerator: Expression, denominator: Expression) -> Expression {
+	product(vec![
+		numerator,
+		power(denominator, number(Decimal::NEGATIVE_ONE)),
+	])
+}
+
+#[inline(always)]
+fn not_found(f: &Expression) -> Error {
+	Error::LogicError(format!("could not find the limit of `{f}`"))
+}
+
+#[inline(always)]
+fn does_not_exist(f: &Expression) -> Error {
+	Error::LogicError(format!("the limit of `{f}` does not exist"))
+}
+
+/// Whether `f` stays between two bounds whatever its variable is, which
+/// decides sums and products with it even where it has no limit itself
+fn bounded(f: &Expression) -> bool {
+	match f {
+		Expression::FunctionCall(name, _) => {
+			["sin", "cos", "atan", "erf", "erfc", "sign"].contains(&name.as_str())
+		}
+		Expression::Binary(g, Token::Add | Token::Sub | Token::Mul, h, _) => bounded(g) && bounded(h),
+		Expression::Abs(g) => bounded(g),
+		f => ratio(f).is_some(),
+	}
+}
+
+/// Finite limit of a number the limit was computed from, if it has one
+#[inline(always)]
+fn finite(f: &Expression, a: Option<Decimal>) -> Result<Limit, Error> {
+	a.map(Limit::Finite).ok_or_else(|| not_found(f))
+}
+
+/// Real value of an expression of numbers
+#[inline(always)]
+fn value(f: Expression, ctx: &mut InterpreterContext) -> Result<Limit, Error> {
+	match f.clone().evaluate(ctx, 0..0)? {
+		Data::Number(a, b) if b.is_zero() => Ok(Limit::Finite(a)),
+		_ => Err(Error::LogicError(format!(
+			"the limit of `{f}` is not a real number"
+		))),
+	}
+}
+
+/// Writes logarithms to a base and roots as quotients and powers, and calls of
+/// functions declared in the script as their bodies
+fn rewrite(f: &Expression, ctx: &InterpreterContext) -> Expression {
+	let f = crate::simplify::map(f, |f| rewrite(f, ctx));
+
+	let Expression::FunctionCall(name, args) = &f else {
+		return f;
+	};
+
+	match (name.as_str(), &args[..]) {
+		("log", [(u, _), (b, _)]) => quotient(call("ln", vec![u.clone()]), call("ln", vec![b.clone()])),
+		("nrt", [(u, _), (n, _)]) => power(u.clone(), quotient(number(Decimal::ONE), n.clone())),
+		_ => match ctx.1.get(name) {
+			Some(Function::UserDefined(function)) => rewrite(
+				&function.code.substitute(
+					&function
+						.params
+						.iter()
+						.zip(args)
+						.map(|((param, _), (arg, _))| (param.clone(), arg.clone()))
+						.collect::<Vec<(String, Expression)>>(),
+				),
+				ctx,
+			),
+			_ => f,
+		},
+	}
+}
+
+/// Whether `f`, which approaches zero as `t` approaches zero from above, does
+/// so from above, which is the sign of its first derivative not to vanish
+fn from_above(
+	f: &Expression,
+	t: &str,
+	ctx: &mut InterpreterContext,
+	depth: usize,
+) -> Result<bool, Error> {
+	if let Some((_, c)) = expand(f, t, ctx).and_then(|series| series.lead()) {
+		return Ok(c.is_sign_positive());
+	}
+
+	let mut derivative = f.clone();
+
+	for _ in 0..LARGEST_DEPTH {
+		if depth >= LARGEST_DEPTH {
+			break;
+		}
+
+		derivative = derivative.differentiate(&Data::Ident(t.to_string()), ctx)?;
+
+		match limit(&derivative, t, ctx, depth + 1)? {
+			Limit::Finite(a) if a.is_zero() => continue,
+			Limit::Finite(a) => return Ok(a.is_sign_positive()),
+			Limit::Infinite(positive) => return Ok(positive),
+		}
+	}
+
+	Err(Error::LogicError(format!(
+		"could not find which side `{f}` approaches zero from"
+	)))
+}
+
+/// Limit of `numerator / denominator` as the limit of the quotient of their
+/// derivatives
+#[inline(always)]
+fn lhopital(
+	numerator: &Expression,
+	denominator: &Expression,
+	t: &str,
+	ctx: &mut InterpreterContext,
+	depth: usize,
+) -> Result<Limit, Error> {
+	let wrt = Data::Ident(t.to_string());
+	let derivative = quotient(
+		numerator.differentiate(&wrt, ctx)?,
+		denominator.differentiate(&wrt, ctx)?,
+	)
+	.simplify();
+
+	limit(&derivative, t, ctx, depth + 1)
+}
+
+/// Limit of `numerator / denominator`, by L'Hôpital's rule when both approach
+/// zero or both grow without bound
+fn fraction(
+	numerator: &Expression,
+	denominator: &Expression,
+	t: &str,
+	ctx: &mut InterpreterContext,
+	depth: usize,
+) -> Result<Limit, Error> {
+	let f = quotient(numerator.clone(), denominator.clone());
+
+	let (a, b) = (
+		limit(numerator, t, ctx, depth),
+		limit(denominator, t, ctx, depth)?,
+	);
+
+	// A bounded quantity over one which grows without bound vanishes
+	if let (Err(_), Limit::Infinite(_)) = (&a, b)
+		&& bounded(numerator)
+	{
+		return Ok(Limit::Finite(Decimal::ZERO));
+	}
+
+	Ok(match (a?, b) {
+		(a, b)
+			if a.is_zero() && b.is_zero()
+				|| matches!((a, b), (Limit::Infinite(_), Limit::Infinite(_))) =>
+		{
+			if depth >= LARGEST_DEPTH {
+				return Err(not_found(&f));
+			}
+
+			// A quantity which vanishes like e^(-1/t) only gets smaller relative to
+			// its derivatives, but its inverse can be divided instead
+			match lhopital(numerator, denominator, t, ctx, depth) {
+				Ok(limit) => limit,
+				Err(_) => {
+					let inverse = |f: &Expression| quotient(number(Decimal::ONE), f.clone());
+
+					lhopital(&inverse(denominator), &inverse(numerator), t, ctx, depth)?
+				}
+			}
+		}
+		(_, Limit::Infinite(_)) => Limit::Finite(Decimal::ZERO),
+		(Limit::Finite(a), Limit::Finite(b)) if !b.is_zero() => finite(&f, a.checked_div(b))?,
+		(Limit::Infinite(positive), Limit::Finite(b)) if !b.is_zero() => {
+			Limit::Infinite(positive == b.is_sign_positive())
+		}
+		// Dividing by a quantity which approaches zero
+		(a, _) => {
+			let above = from_above(denominator, t, ctx, depth)?;
+
+			match a {
+				Limit::Finite(a) => Limit::Infinite(a.is_sign_positive() == above),
+				Limit::Infinite(positive) => Limit::Infinite(positive == above),
+			}
+		}
+	})
+}
+
+/// Limit of `e^f` from the limit of `f`
+#[inline(always)]
+fn exponential(exponent: Limit, ctx: &mut InterpreterContext) -> Result<Limit, Error> {
+	Ok(match exponent {
+		Limit::Finite(a) => value(call("exp", vec![number(a)]), ctx)?,
+		Limit::Infinite(true) => Limit::Infinite(true),
+		Limit::Infinite(false) => Limit::Finite(Decimal::ZERO),
+	})
+}
+
+/// Limit of `f^g`, written as `e^(g ln f)` for the forms `0^0`, `1^∞` and
+/// `∞^0`
+fn exponentiation(
+	f: &Expression,
+	g: &Expression,
+	t: &str,
+	ctx: &mut InterpreterContext,
+	depth: usize,
+) -> Result<Limit, Error> {
+	let expr = Expression::Binary(
+		Box::new(f.clone()),
+		Token::Pow,
+		Box::new(g.clone()),
+		Operands::default(),
+	);
+
+	let (base, exponent) = (limit(f, t, ctx, depth)?, limit(g, t, ctx, depth)?);
+
+	// An integer power of a negative base, which does not change with `t`
+	let integer = match exponent {
+		Limit::Finite(n) if !g.involves(t) && n.is_integer() => Some(n),
+		_ => None,
+	};
+
+	let logarithm = |ctx: &mut InterpreterContext| {
+		let exponent = limit(
+			&product(vec![g.clone(), call("ln", vec![f.clone()])]),
+			t,
+			ctx,
+			depth + 1,
+		)?;
+
+		exponential(exponent, ctx)
+	};
+
+	Ok(match (base, exponent) {
+		(Limit::Finite(a), Limit::Finite(b)) if a.is_sign_positive() && !a.is_zero() => {
+			value(Expression::binary(number(a), Token::Pow, number(b)), ctx)?
+		}
+		(Limit::Finite(a), Limit::Finite(_)) if a.is_sign_negative() && !a.is_zero() => match integer {
+			Some(n) => value(Expression::binary(number(a), Token::Pow, number(n)), ctx)?,
+			None => return Err(does_not_exist(&expr)),
+		},
+		// The base approaches zero
+		(Limit::Finite(_), Limit::Finite(b)) if b.is_zero() => logarithm(ctx)?,
+		(Limit::Finite(_), Limit::Finite(b)) if b.is_sign_positive() => Limit::Finite(Decimal::ZERO),
+		(Limit::Finite(_), Limit::Finite(_)) => {
+			let above = from_above(f, t, ctx, depth)?;
+
+			match integer {
+				_ if above => Limit::Infinite(true),
+				Some(n) => Limit::Infinite(n % Decimal::TWO == Decimal::ZERO),
+				None => return Err(does_not_exist(&expr)),
+			}
+		}
+		(Limit::Finite(a), Limit::Infinite(_)) if a == Decimal::ONE => logarithm(ctx)?,
+		(Limit::Finite(a), Limit::Infinite(positive)) if a.is_zero() => {
+			if !from_above(f, t, ctx, depth)? {
+				return Err(does_not_exist(&expr));
+			}
+
+			if positive {
+				Limit::Finite(Decimal::ZERO)
+			} else {
+				Limit::Infinite(true)
+			}
+		}
+		(Limit::Finite(a), Limit::Infinite(positive)) if a.is_sign_positive() => {
+			if (a > Decimal::ONE) == positive {
+				Limit::Infinite(true)
+			} else {
+				Limit::Finite(Decimal::ZERO)
+			}
+		}
+		(Limit::Finite(_), Limit::Infinite(_)) => return Err(does_not_exist(&expr)),
+		(Limit::Infinite(_), Limit::Finite(b)) if b.is_zero() => logarithm(ctx)?,
+		(Limit::Infinite(_), Limit::Finite(b)) if b.is_sign_negative() => Limit::Finite(Decimal::ZERO),
+		(Limit::Infinite(true), Limit::Finite(_)) => Limit::Infinite(true),
+		(Limit::Infinite(false), Limit::Finite(_)) => match integer {
+			Some(n) => Limit::Infinite(n % Decimal::TWO == Decimal::ZERO),
+			None => return Err(does_not_exist(&expr)),
+		},
+		(Limit::Infinite(true), Limit::Infinite(true)) => Limit::Infinite(true),
+		(Limit::Infinite(true), Limit::Infinite(false)) => Limit::Finite(Decimal::ZERO),
+		(Limit::Infinite(false), Limit::Infinite(_)) => return Err(does_not_exist(&expr)),
+	})
+}
+
+/// Limit of a call to a function from the limits of its arguments
+fn function(
+	f: &Expression,
+	name: &str,
+	args: &[Expression],
+	t: &str,
+	ctx: &mut InterpreterContext,
+	depth: usize,
+) -> Result<Limit, Error> {
+	let limits = args
+		.iter()
+		.map(|arg| limit(arg, t, ctx, depth))
+		.collect::<Result<Vec<Limit>, Error>>()?;
+
+	Ok(match (name, &limits[..]) {
+		// Functions which jump or are not defined at the limit of their argument
+		// depend on the side it is approached from
+		("ln" | "log10", [Limit::Finite(a)]) if a.is_zero() => {
+			if !from_above(&args[0], t, ctx, depth)? {
+				return Err(does_not_exist(f));
+			}
+
+			Limit::Infinite(false)
+		}
+		("sign", [Limit::Finite(a)]) if a.is_zero() => {
+			Limit::Finite(Decimal::ONE).signed(!from_above(&args[0], t, ctx, depth)?)
+		}
+		("floor" | "ceil", [Limit::Finite(a)]) if a.is_integer() => {
+			let above = from_above(
+				&Expression::binary(args[0].clone(), Token::Sub, number(*a)),
+				t,
+				ctx,
+				depth,
+			);
+
+			match (name, above) {
+				(_, Err(_)) => Limit::Finite(*a),
+				("floor", Ok(above)) => Limit::Finite(if above { *a } else { a - Decimal::ONE }),
+				(_, Ok(above)) => Limit::Finite(if above { a + Decimal::ONE } else { *a }),
+			}
+		}
+		(_, limits) if limits.iter().all(|limit| matches!(limit, Limit::Finite(_))) => value(
+			call(
+				name,
+				limits
+					.iter()
+					.map(|limit| match limit {
+						Limit::Finite(a) => number(*a),
+						Limit::Infinite(_) => unreachable!(),
+					})
+					.collect(),
+			),
+			ctx,
+		)?,
+		("exp", [Limit::Infinite(positive)]) => exponential(Limit::Infinite(*positive), ctx)?,
+		("ln" | "log10" | "sqrt" | "lgamma" | "gamma", [Limit::Infinite(true)]) => {
+			Limit::Infinite(true)
+		}
+		("cbrt" | "sinh" | "floor" | "ceil" | "round", [infinite]) => *infinite,
+		("cosh", [_]) => Limit::Infinite(true),
+		("sign" | "erf", [Limit::Infinite(positive)]) => Limit::Finite(Decimal::ONE).signed(!positive),
+		("atan", [Limit::Infinite(positive)]) => Limit::Finite(Decimal::HALF_PI).signed(!positive),
+		("erfc", [Limit::Infinite(positive)]) => Limit::Finite(if *positive {
+			Decimal::ZERO
+		} else {
+			Decimal::TWO
+		}),
+		("sin" | "cos" | "tan", [Limit::Infinite(_)]) => return Err(does_not_exist(f)),
+		_ => return Err(not_found(f)),
+	})
+}
+
+/// Limit of `f` as `t` approaches zero from above
+fn limit(
+	f: &Expression,
+	t: &str,
+	ctx: &mut InterpreterContext,
+	depth: usize,
+) -> Result<Limit, Error> {
+	if let Some(a) = ratio(f) {
+		return Ok(Limit::Finite(a.value()));
+	}
+
+	// The first term of a series decides the limit, which only fails to have
+	// one where an exponential or logarithm grows without bound
+	if let Some((e, c)) = expand(f, t, ctx).and_then(|series| series.lead()) {
+		return Ok(match e.value() {
+			e if e.is_sign_negative() => Limit::Infinite(c.is_sign_positive()),
+			e if e.is_zero() => Limit::Finite(c),
+			_ => Limit::Finite(Decimal::ZERO),
+		});
+	}
+
+	Ok(match f {
+		Expression::Identifier(name) if name == t => Limit::Finite(Decimal::ZERO),
+		Expression::Identifier(name) => match ctx.0.get(name).map(|variable| &variable.value) {
+			Some(Data::Number(a, b)) if b.is_zero() => Limit::Finite(*a),
+			_ => {
+				return Err(Error::LogicError(format!(
+					"expected the limit to involve no unknowns other than its variable, found `{name}`"
+				)));
+			}
+		},
+		Expression::Abs(g) => match limit(g, t, ctx, depth)? {
+			Limit::Finite(a) => Limit::Finite(a.abs()),
+			Limit::Infinite(_) => Limit::Infinite(true),
+		},
+		Expression::Binary(g, op @ (Token::Add | Token::Sub), h, _) => {
+			let subtract = *op == Token::Sub;
+
+			let (a, b) = (
+				limit(g, t, ctx, depth),
+				limit(h, t, ctx, depth).map(|b| b.signed(subtract)),
+			);
+
+			// A bounded term does not stop the other from growing without bound
+			match (&a, &b) {
+				(Err(_), Ok(Limit::Infinite(positive))) if bounded(g) => {
+					return Ok(Limit::Infinite(*positive));
+				}
+				(Ok(Limit::Infinite(positive)), Err(_)) if bounded(h) => {
+					return Ok(Limit::Infinite(*positive));
+				}
+				_ => {}
+			}
+
+			match (a?, b?) {
+				(Limit::Finite(a), Limit::Finite(b)) => finite(f, a.checked_add(b))?,
+				(Limit::Infinite(positive), Limit::Finite(_))
+				| (Limit::Finite(_), Limit::Infinite(positive)) => Limit::Infinite(positive),
+				(Limit::Infinite(a), Limit::Infinite(b)) if a == b => Limit::Infinite(a),
+				// ∞ - ∞, as the quotient (1/h + 1/g) / (1/(g h)) which approaches 0/0
+				_ => {
+					let h = if subtract {
+						negate(h.as_ref().clone())
+					} else {
+						h.as_ref().clone()
+					};
+					let one = || number(Decimal::ONE);
+
+					fraction(
+						&sum(vec![
+							quotient(one(), g.as_ref().clone()),
+							quotient(one(), h.clone()),
+						]),
+						&quotient(one(), product(vec![g.as_ref().clone(), h])),
+						t,
+						ctx,
+						depth,
+					)?
+				}
+			}
+		}
+		Expression::Binary(g, Token::Mul, h, _) => {
+			let (a, b) = (limit(g, t, ctx, depth), limit(h, t, ctx, depth));
+
+			// A bounded factor times one which vanishes vanishes as well
+			match (&a, &b) {
+				(Err(_), Ok(b)) if b.is_zero() && bounded(g) => {
+					return Ok(Limit::Finite(Decimal::ZERO));
+				}
+				(Ok(a), Err(_)) if a.is_zero() && bounded(h) => {
+					return Ok(Limit::Finite(Decimal::ZERO));
+				}
+				_ => {}
+			}
+
+			match (a?, b?) {
+				(Limit::Finite(a), Limit::Finite(b)) => finite(f, a.checked_mul(b))?,
+				(Limit::Infinite(a), Limit::Infinite(b)) => Limit::Infinite(a == b),
+				// 0 ∞, as the quotient of the factor which grows by the inverse of the
+				// one which vanishes
+				(Limit::Finite(a), Limit::Infinite(_)) if a.is_zero() => fraction(
+					h,
+					&quotient(number(Decimal::ONE), g.as_ref().clone()),
+					t,
+					ctx,
+					depth,
+				)?,
+				(Limit::Infinite(_), Limit::Finite(b)) if b.is_zero() => fraction(
+					g,
+					&quotient(number(Decimal::ONE), h.as_ref().clone()),
+					t,
+					ctx,
+					depth,
+				)?,
+				(Limit::Finite(a), Limit::Infinite(positive))
+				| (Limit::Infinite(positive), Limit::Finite(a)) => {
+					Limit::Infinite(positive == a.is_sign_positive())
+				}
+			}
+		}
+		Expression::Binary(g, Token::Div, h, _) => fraction(g, h, t, ctx, depth)?,
+		Expression::Binary(g, Token::Pow, h, _) => exponentiation(g, h, t, ctx, depth)?,
+		Expression::Binary(g, Token::Rem, h, _) => {
+			match (limit(g, t, ctx, depth)?, limit(h, t, ctx, depth)?) {
+				(Limit::Finite(a), Limit::Finite(b)) if !b.is_zero() => finite(f, a.checked_rem(b))?,
+				_ => return Err(not_found(f)),
+			}
+		}
+		Expression::FunctionCall(name, args) => function(
+			f,
+			name,
+			&args
+				.iter()
+				.map(|(arg, _)| arg.clone())
+				.collect::<Vec<Expression>>(),
+			t,
+			ctx,
+			depth,
+		)?,
+		_ => return Err(not_found(f)),
+	})
+}
+
+impl Expression {
+	/// Limit as `x` approaches `a`, from above or below when `side` is given
+	/// and from both sides otherwise. Every limit is turned into one as a
+	/// variable approaches zero from above, by `x = a ± t` or `x = ±1/t`.
+	pub fn limit<'a, 'b>(
+		&self,
+		x: &str,
+		a: Limit,
+		side: Option<bool>,
+		ctx: &'a mut InterpreterContext<'b>,
+	) -> Result<Limit, Error>
+	where
+		'b: 'a,
+	{
+		let f = rewrite(self, ctx);
+		let t = Expression::Identifier(x.to_string());
+
+		let approach = |above: bool, ctx: &mut InterpreterContext| {
+			let point = match a {
+				Limit::Finite(a) => sum(vec![
+					number(a),
+					if above { t.clone() } else { negate(t.clone()) },
+				]),
+				Limit::Infinite(positive) => quotient(
+					number(if positive {
+						Decimal::ONE
+					} else {
+						Decimal::NEGATIVE_ONE
+					}),
+					t.clone(),
+				),
+			};
+
+			limit(
+				&f.substitute(&[(x.to_string(), point)]).simplify(),
+				x,
+				ctx,
+				0,
+			)
+		};
+
+		match (a, side) {
+			(Limit::Infinite(_), _) => approach(true, ctx),
+			(_, Some(above)) => approach(above, ctx),
+			(_, None) => match (approach(true, ctx)?, approach(false, ctx)?) {
+				(Limit::Finite(above), Limit::Finite(below))
+					if (above - below).abs() <= TOLERANCE * above.abs().max(Decimal::ONE) =>
+				{
+					Ok(Limit::Finite(above))
+				}
+				(above, below) if above == below => Ok(above),
+				_ => Err(Error::LogicError(format!(
+					"the limit of `{self}` does not exist, as it differs from either side"
+				))),
+			},
+		}
+	}
+
+	/// Taylor polynomial of degree `n` about `x = a`, the sum of
+	/// `f⁽ᵏ⁾(a) / k! (x - a)^k`. Coefficients which are not exact are the
+	/// limits of the derivatives at `a`, which fails where `f` is not smooth.
+	pub fn taylor<'a, 'b>(
+		&self,
+		x: &str,
+		a: &Expression,
+		n: usize,
+		ctx: &'a mut InterpreterContext<'b>,
+	) -> Result<Expression, Error>
+	where
+		'b: 'a,
+	{
+		let shift = sum(vec![
+			Expression::Identifier(x.to_string()),
+			negate(a.clone()),
+		]);
+
+		let mut derivative = self.simplify();
+		let mut factorial = Decimal::ONE;
+		let mut terms = vec![];
+
+		for k in 0..=n {
+			if k > 0 {
+				derivative = derivative.differentiate(&Data::Ident(x.to_string()), ctx)?;
+				factorial = factorial.checked_mul(Decimal::from(k)).ok_or_else(|| {
+					Error::LogicError(format!("degree {n} is too large for a Taylor polynomial"))
+				})?;
+			}
+
+			let at = derivative
+				.substitute(&[(x.to_string(), a.clone())])
+				.simplify();
+
+			let coefficient = match (ratio(&at), ratio(a)) {
+				(Some(value), _) => value.expression(),
+				(None, Some(point)) if at.identifiers().iter().all(|name| ctx.0.contains_key(name)) => {
+					match derivative.limit(x, Limit::Finite(point.value()), None, ctx)? {
+						Limit::Finite(value) => number(value.round_dp(24).normalize()),
+						Limit::Infinite(_) => {
+							return Err(Error::LogicError(format!(
+								"`{self}` has no Taylor series about {a}, as `{derivative}` grows without bound there"
+							)));
+						}
+					}
+				}
+				_ => at,
+			};
+
+			terms.push(product(vec![
+				coefficient,
+				Ratio::new(Decimal::ONE, factorial)
+					.map(Ratio::expression)
+					.unwrap_or(number(Decimal::ONE / factorial)),
+				power(shift.clone(), number(Decimal::from(k))),
+			]));
+		}
+
+		Ok(sum(terms))
+	}
+}
@@ -0,0 +1,912 @@
+use std::cmp::Ordering;
+
+use rust_decimal::{
+	Decimal, MathematicalOps,
+	prelude::{Signed, ToPrimitive},
+};
+
+use crate::{
+	expr::{Expression, Operands},
+	token::Token,
+};
+
+/// Largest integer power of a number which is worked out instead of being kept
+/// as a power
+pub(crate) const LARGEST_POWER: i64 = 64;
+
+/// Exact ratio of two numbers, kept for coefficients so that `x/3` is not
+/// rounded to `0.3333333333333333333333333333x`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Ratio {
+	pub(crate) numerator: Decimal,
+	pub(crate) denominator: Decimal,
+}
+
+impl Ratio {
+	pub(crate) const ZERO: Ratio = Ratio {
+		numerator: Decimal::ZERO,
+		denominator: Decimal::ONE,
+	};
+
+	pub(crate) const ONE: Ratio = Ratio {
+		numerator: Decimal::ONE,
+		denominator: Decimal::ONE,
+	};
+
+	/// Reduced to lowest terms with a positive denominator when both parts are
+	/// integers, otherwise divided out into a decimal
+	#[inline(always)]
+	pub(crate) fn new(numerator: Decimal, denominator: Decimal) -> Option<Ratio> {
+		if denominator.is_zero() {
+			return None;
+		}
+
+		if !numerator.fract().is_zero() || !denominator.fract().is_zero() {
+			return Some(Ratio {
+				numerator: numerator.checked_div(denominator)?.normalize(),
+				denominator: Decimal::ONE,
+			});
+		}
+
+		let divisor = gcd(numerator.abs(), denominator.abs());
+		let sign = if denominator.is_sign_negative() {
+			Decimal::NEGATIVE_ONE
+		} else {
+			Decimal::ONE
+		};
+
+		Some(Ratio {
+			numerator: (sign * numerator / divisor).normalize(),
+			denominator: (sign * denominator / divisor).normalize(),
+		})
+	}
+
+	#[inline(always)]
+	pub(crate) fn value(self) -> Decimal {
+		self.numerator / self.denominator
+	}
+
+	#[inline(always)]
+	pub(crate) fn is_zero(self) -> bool {
+		self.numerator.is_zero()
+	}
+
+	#[inline(always)]
+	pub(crate) fn is_one(self) -> bool {
+		self == Ratio::ONE
+	}
+
+	#[inline(always)]
+	pub(crate) fn is_negative(self) -> bool {
+		self.numerator.is_sign_negative() && !self.is_zero()
+	}
+
+	#[inline(always)]
+	pub(crate) fn integer(self) -> Option<i64> {
+		if self.denominator == Decimal::ONE && self.numerator.fract().is_zero() {
+			self.numerator.to_i64()
+		} else {
+			None
+		}
+	}
+
+	#[inline(always)]
+	pub(crate) fn neg(self) -> Ratio {
+		Ratio {
+			numerator: -self.numerator,
+			denominator: self.denominator,
+		}
+	}
+
+	/// Sum, or the sum of the decimals when the exact one is too large
+	#[inline(always)]
+	pub(crate) fn add(self, other: Ratio) -> Ratio {
+		self
+			.numerator
+			.checked_mul(other.denominator)
+			.zip(other.numerator.checked_mul(self.denominator))
+			.and_then(|(a, b)| a.checked_add(b))
+			.zip(self.denominator.checked_mul(other.denominator))
+			.and_then(|(numerator, denominator)| Ratio::new(numerator, denominator))
+			.unwrap_or_else(|| Ratio {
+				numerator: self.value() + other.value(),
+				denominator: Decimal::ONE,
+			})
+	}
+
+	#[inline(always)]
+	pub(crate) fn recip(self) -> Option<Ratio> {
+		Ratio::new(self.denominator, self.numerator)
+	}
+
+	/// Product, or the product of the decimals when the exact one is too large
+	#[inline(always)]
+	pub(crate) fn mul(self, other: Ratio) -> Ratio {
+		self
+			.numerator
+			.checked_mul(other.numerator)
+			.zip(self.denominator.checked_mul(other.denominator))
+			.and_then(|(numerator, denominator)| Ratio::new(numerator, denominator))
+			.unwrap_or_else(|| Ratio {
+				numerator: self.value() * other.value(),
+				denominator: Decimal::ONE,
+			})
+	}
+
+	#[inline(always)]
+	pub(crate) fn pow(self, exponent: i64) -> Option<Ratio> {
+		if exponent.abs() > LARGEST_POWER {
+			return None;
+		}
+
+		let (mut numerator, mut denominator) = (Decimal::ONE, Decimal::ONE);
+
+		for _ in 0..exponent.abs() {
+			numerator = numerator.checked_mul(self.numerator)?;
+			denominator = denominator.checked_mul(self.denominator)?;
+		}
+
+		if exponent < 0 {
+			Ratio::new(denominator, numerator)
+		} else {
+			Ratio::new(numerator, denominator)
+		}
+	}
+
+	#[inline(always)]
+	pub(crate) fn expression(self) -> Expression {
+		if self.denominator == Decimal::ONE {
+			Expression::Float(self.numerator)
+		} else {
+			Expression::Binary(
+				Box::new(Expression::Float(self.numerator)),
+				Token::Div,
+				Box::new(Expression::Float(self.denominator)),
+				Operands::default(),
+			)
+		}
+	}
+}
+
+#[inline(always)]
+pub(crate) fn gcd(mut a: Decimal, mut b: Decimal) -> Decimal {
+	while !b.is_zero() {
+		(a, b) = (b, a % b);
+	}
+
+	if a.is_zero() { Decimal::ONE } else { a }
+}
+
+/// The number an expression stands for, written as a decimal or a ratio of two
+#[inline(always)]
+pub(crate) fn ratio(expr: &Expression) -> Option<Ratio> {
+	match expr {
+		Expression::Float(n) => Ratio::new(*n, Decimal::ONE),
+		Expression::Binary(numerator, Token::Div, denominator, _) => {
+			match (numerator.as_ref(), denominator.as_ref()) {
+				(Expression::Float(numerator), Expression::Float(denominator)) => {
+					Ratio::new(*numerator, *denominator)
+				}
+				_ => None,
+			}
+		}
+		_ => None,
+	}
+}
+
+#[inline(always)]
+pub(crate) fn number(n: Decimal) -> Expression {
+	Expression::Float(n)
+}
+
+#[inline(always)]
+fn is_number(expr: &Expression, n: Decimal) -> bool {
+	ratio(expr).is_some_and(|ratio| ratio.value() == n && ratio.denominator == Decimal::ONE)
+}
+
+#[inline(always)]
+fn call(name: &str, args: Vec<Expression>) -> Expression {
+	Expression::FunctionCall(
+		name.to_string(),
+		args.into_iter().map(|arg| (arg, 0..0)).collect(),
+	)
+}
+
+/// Terms of a sum, with those which are subtracted negated
+#[inline(always)]
+pub(crate) fn terms(expr: Expression) -> Vec<Expression> {
+	match expr {
+		Expression::Binary(lhs, Token::Add, rhs, _) => {
+			let mut terms = terms(*lhs);
+			terms.extend(self::terms(*rhs));
+			terms
+		}
+		Expression::Binary(lhs, Token::Sub, rhs, _) => {
+			let mut terms = terms(*lhs);
+			terms.extend(self::terms(*rhs).into_iter().map(negate));
+			terms
+		}
+		expr => vec![expr],
+	}
+}
+
+/// Factors of a product, with those which divide it raised to the power of -1
+#[inline(always)]
+pub(crate) fn factors(expr: Expression) -> Vec<Expression> {
+	match expr {
+		Expression::Binary(lhs, Token::Mul, rhs, _) => {
+			let mut factors = factors(*lhs);
+			factors.extend(self::factors(*rhs));
+			factors
+		}
+		// A ratio of two numbers is a single factor
+		Expression::Binary(lhs, Token::Div, rhs, _)
+			if !matches!(
+				(lhs.as_ref(), rhs.as_ref()),
+				(Expression::Float(_), Expression::Float(_))
+			) =>
+		{
+			let mut factors = factors(*lhs);
+			factors.extend(
+				self::factors(*rhs)
+					.into_iter()
+					.map(|factor| power(factor, number(Decimal::NEGATIVE_ONE))),
+			);
+			factors
+		}
+		expr => vec![expr],
+	}
+}
+
+#[inline(always)]
+pub(crate) fn negate(expr: Expression) -> Expression {
+	product(vec![number(Decimal::NEGATIVE_ONE), expr])
+}
+
+#[inline(always)]
+fn is_matrix(expr: &Expression) -> bool {
+	matches!(expr, Expression::Matrix(..) | Expression::List(..))
+}
+
+/// Order of the factors of a product: names alphabetically, then powers of `e`
+/// and other numbers, then calls and everything else by how it is written
+#[inline(always)]
+fn factor_order((a, m): &(Expression, Expression), (b, n): &(Expression, Expression)) -> Ordering {
+	let rank = |base: &Expression| match base {
+		Expression::Identifier(name) if name != "e" => 0,
+		Expression::Identifier(_) | Expression::Float(_) => 1,
+		Expression::FunctionCall(..) => 2,
+		_ => 3,
+	};
+
+	rank(a)
+		.cmp(&rank(b))
+		.then_with(|| a.to_string().cmp(&b.to_string()))
+		.then_with(|| exponent_order(n, m))
+}
+
+#[inline(always)]
+pub(crate) fn exponent_order(a: &Expression, b: &Expression) -> Ordering {
+	match (ratio(a), ratio(b)) {
+		(Some(a), Some(b)) => a.value().cmp(&b.value()),
+		(Some(_), None) => Ordering::Less,
+		(None, Some(_)) => Ordering::Greater,
+		(None, None) => a.to_string().cmp(&b.to_string()),
+	}
+}
+
+/// Total power of the names in a term, which orders the terms of a sum from the
+/// highest power down
+#[inline(always)]
+fn degree(powers: &[(Expression, Expression)]) -> Decimal {
+	powers
+		.iter()
+		.filter(|(base, _)| matches!(base, Expression::Identifier(name) if name != "e"))
+		.filter_map(|(_, exponent)| ratio(exponent))
+		.map(Ratio::value)
+		.sum()
+}
+
+/// Order of the terms of a sum: highest degree first, then by their names
+/// alphabetically with higher powers first, as in `x^2+x*y+y^2`
+#[inline(always)]
+fn term_order(a: &[(Expression, Expression)], b: &[(Expression, Expression)]) -> Ordering {
+	degree(b).cmp(&degree(a)).then_with(|| {
+		a.iter()
+			.zip(b)
+			.map(|(a, b)| factor_order(a, b))
+			.find(|ordering| ordering.is_ne())
+			.unwrap_or_else(|| a.len().cmp(&b.len()))
+	})
+}
+
+/// Base and exponent of a factor, which is its own base when not a power
+#[inline(always)]
+pub(crate) fn base_exponent(factor: Expression) -> (Expression, Expression) {
+	match factor {
+		Expression::Binary(base, Token::Pow, exponent, _) => (*base, *exponent),
+		factor => (factor, number(Decimal::ONE)),
+	}
+}
+
+/// Numerical coefficient of a product and the power of each base in it, with
+/// the exponents of the same base added together
+pub(crate) fn powers(factors: Vec<Expression>) -> (Ratio, Vec<(Expression, Expression)>) {
+	let mut coefficient = Ratio::ONE;
+	let mut bases: Vec<(Expression, Vec<Expression>)> = vec![];
+
+	for factor in factors.into_iter().flat_map(self::factors) {
+		if let Some(n) = ratio(&factor) {
+			coefficient = coefficient.mul(n);
+			continue;
+		}
+
+		let (base, exponent) = base_exponent(factor);
+
+		match bases.iter_mut().find(|(other, _)| *other == base) {
+			Some((_, exponents)) => exponents.push(exponent),
+			None => bases.push((base, vec![exponent])),
+		}
+	}
+
+	let mut powers = vec![];
+	let mut expanded = vec![];
+
+	for (base, exponents) in bases {
+		let power = power(base, sum(exponents));
+
+		match ratio(&power) {
+			Some(n) => coefficient = coefficient.mul(n),
+			// A product raised to a power which adds up to an integer comes apart
+			None if matches!(power, Expression::Binary(_, Token::Mul | Token::Div, _, _)) => {
+				expanded.push(power)
+			}
+			None => powers.push(base_exponent(power)),
+		}
+	}
+
+	if !expanded.is_empty() {
+		expanded.push(coefficient.expression());
+		expanded.extend(
+			powers
+				.into_iter()
+				.map(|(base, exponent)| join(base, exponent)),
+		);
+
+		return self::powers(expanded);
+	}
+
+	if coefficient.is_zero() {
+		return (Ratio::ZERO, vec![]);
+	}
+
+	powers.sort_by(factor_order);
+
+	(coefficient, powers)
+}
+
+#[inline(always)]
+pub(crate) fn join(base: Expression, exponent: Expression) -> Expression {
+	if is_number(&exponent, Decimal::ONE) {
+		base
+	} else {
+		Expression::Binary(
+			Box::new(base),
+			Token::Pow,
+			Box::new(exponent),
+			Operands::default(),
+		)
+	}
+}
+
+/// Product of the coefficient and the powers, as a fraction when any of the
+/// powers or the coefficient divides
+pub(crate) fn build_product(
+	coefficient: Ratio,
+	powers: Vec<(Expression, Expression)>,
+) -> Expression {
+	if coefficient.is_zero() {
+		return number(Decimal::ZERO);
+	}
+
+	let mut numerator = vec![];
+	let mut denominator = vec![];
+
+	if coefficient.numerator != Decimal::ONE || powers.is_empty() {
+		numerator.push(number(coefficient.numerator));
+	}
+
+	if coefficient.denominator != Decimal::ONE {
+		denominator.push(number(coefficient.denominator));
+	}
+
+	for (base, exponent) in powers {
+		match ratio(&exponent) {
+			Some(n) if n.is_negative() => denominator.push(join(base, n.neg().expression())),
+			_ => numerator.push(join(base, exponent)),
+		}
+	}
+
+	let chain = |factors: Vec<Expression>| {
+		factors
+			.into_iter()
+			.reduce(|lhs, rhs| {
+				Expression::Binary(
+					Box::new(lhs),
+					Token::Mul,
+					Box::new(rhs),
+					Operands::default(),
+				)
+			})
+			.unwrap_or(number(Decimal::ONE))
+	};
+
+	if denominator.is_empty() {
+		chain(numerator)
+	} else {
+		Expression::Binary(
+			Box::new(chain(numerator)),
+			Token::Div,
+			Box::new(chain(denominator)),
+			Operands::default(),
+		)
+	}
+}
+
+#[inline(always)]
+pub(crate) fn product(factors: Vec<Expression>) -> Expression {
+	// Products of matrices depend on the order of their factors
+	if factors.iter().any(is_matrix) {
+		return factors
+			.into_iter()
+			.reduce(|lhs, rhs| Expression::binary(lhs, Token::Mul, rhs))
+			.unwrap_or(number(Decimal::ONE));
+	}
+
+	let (coefficient, powers) = powers(factors);
+
+	build_product(coefficient, powers)
+}
+
+/// Sum of the terms with like terms collected, from the highest power down and
+/// the constant last
+pub(crate) fn sum(terms: Vec<Expression>) -> Expression {
+	let mut constant = Ratio::ZERO;
+	let mut groups: Vec<(Vec<(Expression, Expression)>, Ratio)> = vec![];
+
+	let mut pending = terms
+		.into_iter()
+		.flat_map(self::terms)
+		.collect::<Vec<Expression>>();
+
+	pending.reverse();
+
+	while let Some(term) = pending.pop() {
+		let (coefficient, powers) = powers(vec![term]);
+
+		// A number multiplying a sum is shared among its terms
+		if let [(inner @ Expression::Binary(_, Token::Add | Token::Sub, _, _), exponent)] = &powers[..]
+			&& is_number(exponent, Decimal::ONE)
+		{
+			pending.extend(
+				self::terms(inner.clone())
+					.into_iter()
+					.rev()
+					.map(|term| product(vec![coefficient.expression(), term])),
+			);
+			continue;
+		}
+
+		if powers.is_empty() {
+			constant = constant.add(coefficient);
+			continue;
+		}
+
+		match groups.iter_mut().find(|(other, _)| *other == powers) {
+			Some((_, total)) => *total = total.add(coefficient),
+			None => groups.push((powers, coefficient)),
+		}
+	}
+
+	groups.retain(|(_, coefficient)| !coefficient.is_zero());
+	groups.sort_by(|(a, _), (b, _)| term_order(a, b));
+
+	if !constant.is_zero() || groups.is_empty() {
+		groups.push((vec![], constant));
+	}
+
+	let mut groups = groups.into_iter();
+	let (powers, coefficient) = groups.next().unwrap();
+
+	groups.fold(
+		build_product(coefficient, powers),
+		|sum, (powers, coefficient)| {
+			if coefficient.is_negative() {
+				Expression::Binary(
+					Box::new(sum),
+					Token::Sub,
+					Box::new(build_product(coefficient.neg(), powers)),
+					Operands::default(),
+				)
+			} else {
+				Expression::Binary(
+					Box::new(sum),
+					Token::Add,
+					Box::new(build_product(coefficient, powers)),
+					Operands::default(),
+				)
+			}
+		},
+	)
+}
+
+pub(crate) fn power(base: Expression, exponent: Expression) -> Expression {
+	let integer = ratio(&exponent).and_then(Ratio::integer);
+
+	if is_number(&exponent, Decimal::ZERO) || is_number(&base, Decimal::ONE) {
+		return number(Decimal::ONE);
+	}
+
+	if is_number(&exponent, Decimal::ONE) {
+		return base;
+	}
+
+	if is_number(&base, Decimal::ZERO) && ratio(&exponent).is_some_and(|n| !n.is_negative()) {
+		return number(Decimal::ZERO);
+	}
+
+	if let (Some(base), Some(exponent)) = (ratio(&base), integer)
+		&& let Some(power) = base.pow(exponent)
+	{
+		return power.expression();
+	}
+
+	match base {
+		// e^ln(x) = x
+		Expression::Identifier(name) if name == "e" => match exponent {
+			Expression::FunctionCall(function, args) if function == "ln" && args.len() == 1 => {
+				args[0].0.clone()
+			}
+			exponent => join(Expression::Identifier(name), exponent),
+		},
+		// (x^a)^n = x^(an) and (xy)^n = x^n y^n only hold for integer n
+		Expression::Binary(base, Token::Pow, inner, _) if integer.is_some() => {
+			power(*base, product(vec![*inner, exponent]))
+		}
+		base @ Expression::Binary(_, Token::Mul | Token::Div, _, _) if integer.is_some() => product(
+			factors(base)
+				.into_iter()
+				.map(|factor| power(factor, exponent.clone()))
+				.collect(),
+		),
+		base => Expression::Binary(
+			Box::new(base),
+			Token::Pow,
+			Box::new(exponent),
+			Operands::default(),
+		),
+	}
+}
+
+/// Value of a call when it has an exact one
+#[inline(always)]
+fn special(name: &str, args: &[Expression]) -> Option<Expression> {
+	let is = |i: usize, n: Decimal| args.get(i).is_some_and(|arg| is_number(arg, n));
+	let zero = Decimal::ZERO;
+	let one = Decimal::ONE;
+
+	Some(match name {
+		"ln" | "log10" if is(0, one) => number(zero),
+		"ln" if args.first() == Some(&Expression::Identifier("e".to_string())) => number(one),
+		"log" if is(0, one) => number(zero),
+		"log" if args.len() == 2 && args[0] == args[1] => number(one),
+		"exp" if is(0, zero) => number(one),
+		"exp" => match args.first()? {
+			Expression::FunctionCall(function, inner) if function == "ln" && inner.len() == 1 => {
+				inner[0].0.clone()
+			}
+			_ => return None,
+		},
+		"sin" | "tan" | "sinh" | "atan" | "erf" | "sqrt" | "cbrt" if is(0, zero) => number(zero),
+		"cos" | "cosh" if is(0, zero) => number(one),
+		"sqrt" | "cbrt" if is(0, one) => number(one),
+		"sqrt" => {
+			let n = ratio(args.first()?)?;
+			let root = Ratio::new(n.numerator.sqrt()?.round(), n.denominator.sqrt()?.round())?;
+
+			if root.mul(root) != n {
+				return None;
+			}
+
+			root.expression()
+		}
+		"abs" => {
+			let n = ratio(args.first()?)?;
+
+			Ratio::new(n.numerator.abs(), n.denominator)?.expression()
+		}
+		"sign" => number(ratio(args.first()?)?.value().signum()),
+		"round" => number(ratio(args.first()?)?.value().round()),
+		"ceil" => number(ratio(args.first()?)?.value().ceil()),
+		"floor" => number(ratio(args.first()?)?.value().floor()),
+		_ => return None,
+	})
+}
+
+#[inline(always)]
+fn fold(lhs: Expression, op: Token, rhs: Expression) -> Expression {
+	let truth = |condition: bool| {
+		number(if condition {
+			Decimal::ONE
+		} else {
+			Decimal::ZERO
+		})
+	};
+
+	match (ratio(&lhs), op, ratio(&rhs)) {
+		(Some(a), Token::Rem, Some(b)) if !b.is_zero() => number((a.value() % b.value()).normalize()),
+		(Some(a), Token::IsEq, Some(b)) => truth(a == b),
+		(Some(a), Token::NEq, Some(b)) => truth(a != b),
+		(Some(a), Token::Gt, Some(b)) => truth(a.value() > b.value()),
+		(Some(a), Token::GtEq, Some(b)) => truth(a.value() >= b.value()),
+		(Some(a), Token::Lt, Some(b)) => truth(a.value() < b.value()),
+		(Some(a), Token::LtEq, Some(b)) => truth(a.value() <= b.value()),
+		(_, op, _) => Expression::binary(lhs, op, rhs),
+	}
+}
+
+/// Rewrites every child of an expression
+#[inline(always)]
+pub(crate) fn map(expr: &Expression, f: impl Fn(&Expression) -> Expression) -> Expression {
+	match expr {
+		Expression::Abs(inner) => Expression::Abs(Box::new(f(inner))),
+		Expression::Binary(lhs, op, rhs, _) => Expression::Binary(
+			Box::new(f(lhs)),
+			op.clone(),
+			Box::new(f(rhs)),
+			Operands::default(),
+		),
+		Expression::Branched(condition, then, otherwise) => Expression::Branched(
+			Box::new(f(condition)),
+			Box::new(f(then)),
+			Box::new(f(otherwise)),
+		),
+		Expression::Matrix(rows) => Expression::Matrix(
+			rows
+				.iter()
+				.map(|row| row.iter().map(&f).collect())
+				.collect(),
+		),
+		Expression::List(elements) => Expression::List(elements.iter().map(&f).collect()),
+		Expression::FunctionCall(name, args) => {
+			call(name, args.iter().map(|(arg, _)| f(arg)).collect())
+		}
+		expr => expr.clone(),
+	}
+}
+
+/// The argument of a call when the call is to the function
+#[inline(always)]
+fn argument<'a>(expr: &'a Expression, function: &str) -> Option<&'a Expression> {
+	match expr {
+		Expression::FunctionCall(name, args) if name == function && args.len() == 1 => Some(&args[0].0),
+		_ => None,
+	}
+}
+
+/// Rewrites products of sines, cosines and tangents of the same argument:
+/// `sin/cos = tan`, `tan cos = sin` and `2 sin cos = sin(2u)`
+fn trig_product(expr: Expression) -> Expression {
+	let (mut coefficient, mut powers) = powers(factors(expr));
+
+	let find = |powers: &[(Expression, Expression)], function: &str, u: &Expression| {
+		powers
+			.iter()
+			.position(|(base, _)| argument(base, function) == Some(u))
+	};
+
+	let mut i = 0;
+
+	while i < powers.len() {
+		let (base, exponent) = powers[i].clone();
+
+		let rewritten = if let Some(u) = argument(&base, "sin")
+			&& let Some(j) = find(&powers, "cos", u)
+		{
+			let other = ratio(&powers[j].1);
+
+			match (ratio(&exponent), other) {
+				(Some(a), Some(b)) if a.add(b).is_zero() => {
+					Some((j, vec![join(call("tan", vec![u.clone()]), exponent)]))
+				}
+				(Some(a), Some(b)) if a.is_one() && b.is_one() => {
+					coefficient = coefficient.mul(Ratio::new(Decimal::ONE, Decimal::TWO).unwrap());
+
+					Some((
+						j,
+						vec![call(
+							"sin",
+							vec![product(vec![number(Decimal::TWO), u.clone()])],
+						)],
+					))
+				}
+				_ => None,
+			}
+		} else if let Some(u) = argument(&base, "tan")
+			&& let Some(j) = find(&powers, "cos", u)
+			&& is_number(&exponent, Decimal::ONE)
+			&& is_number(&powers[j].1, Decimal::ONE)
+		{
+			Some((j, vec![call("sin", vec![u.clone()])]))
+		} else {
+			None
+		};
+
+		match rewritten {
+			Some((j, replacement)) => {
+				let mut factors = powers
+					.iter()
+					.enumerate()
+					.filter(|(k, _)| *k != i && *k != j)
+					.map(|(_, (base, exponent))| join(base.clone(), exponent.clone()))
+					.collect::<Vec<Expression>>();
+
+				factors.extend(replacement);
+				factors.push(coefficient.expression());
+
+				(coefficient, powers) = self::powers(factors);
+				i = 0;
+			}
+			None => i += 1,
+		}
+	}
+
+	build_product(coefficient, powers)
+}
+
+/// Rewrites sums of squares of sines and cosines of the same argument:
+/// `sin²+cos² = 1` and `cos²-sin² = cos(2u)`
+fn trig_sum(expr: Expression) -> Expression {
+	let mut terms = terms(expr)
+		.into_iter()
+		.map(|term| powers(vec![term]))
+		.collect::<Vec<(Ratio, Vec<(Expression, Expression)>)>>();
+
+	// The other factors of a term holding the square of a call to the function
+	let square = |powers: &[(Expression, Expression)], function: &str| {
+		powers.iter().enumerate().find_map(|(i, (base, exponent))| {
+			let u = argument(base, function)?;
+
+			is_number(exponent, Decimal::TWO).then(|| {
+				let mut rest = powers.to_vec();
+				rest.remove(i);
+				(u.clone(), rest)
+			})
+		})
+	};
+
+	let mut i = 0;
+
+	while i < terms.len() {
+		let matched = square(&terms[i].1, "sin").and_then(|(u, rest)| {
+			terms
+				.iter()
+				.enumerate()
+				.find_map(|(j, (coefficient, powers))| {
+					let (v, other) = square(powers, "cos")?;
+
+					(j != i && u == v && rest == other).then_some((j, *coefficient, u.clone(), rest.clone()))
+				})
+		});
+
+		match matched {
+			Some((j, cosine, _, rest)) if cosine == terms[i].0 => {
+				terms[i] = (cosine, rest);
+				terms.remove(j);
+				i = 0;
+			}
+			Some((j, cosine, u, mut rest)) if cosine == terms[i].0.neg() => {
+				rest.push((
+					call("cos", vec![product(vec![number(Decimal::TWO), u])]),
+					number(Decimal::ONE),
+				));
+				terms[i] = (cosine, rest);
+				terms.remove(j);
+				i = 0;
+			}
+			_ => i += 1,
+		}
+	}
+
+	sum(
+		terms
+			.into_iter()
+			.map(|(coefficient, powers)| build_product(coefficient, powers))
+			.collect(),
+	)
+}
+
+#[inline(always)]
+fn trig(expr: &Expression) -> Expression {
+	let expr = map(expr, trig);
+
+	match &expr {
+		Expression::Binary(_, Token::Add | Token::Sub, _, _) => trig_sum(expr).simplify(),
+		Expression::Binary(_, Token::Mul | Token::Div | Token::Pow, _, _) => {
+			trig_product(expr).simplify()
+		}
+		// Sine and tangent are odd and cosine is even
+		Expression::FunctionCall(name, args) if args.len() == 1 => {
+			match (name.as_str(), powers(vec![args[0].0.clone()])) {
+				("sin" | "tan", (coefficient, powers)) if coefficient.is_negative() => {
+					negate(call(name, vec![build_product(coefficient.neg(), powers)]))
+				}
+				("cos", (coefficient, powers)) if coefficient.is_negative() => {
+					call(name, vec![build_product(coefficient.neg(), powers)])
+				}
+				_ => expr,
+			}
+		}
+		_ => expr,
+	}
+}
+
+impl Expression {
+	/// Canonical form of the expression: numbers are folded, like terms and the
+	/// powers of the same base collected, zeros and ones dropped and the terms of
+	/// a sum ordered from the highest power down, with every factor which
+	/// divides a product gathered into a single fraction.
+	pub fn simplify(&self) -> Expression {
+		match self {
+			Expression::Binary(lhs, op, rhs, _) => {
+				let (lhs, rhs) = (lhs.simplify(), rhs.simplify());
+
+				match op {
+					Token::Add => sum(vec![lhs, rhs]),
+					Token::Sub => sum(vec![lhs, negate(rhs)]),
+					Token::Mul if is_matrix(&lhs) || is_matrix(&rhs) => {
+						Expression::binary(lhs, Token::Mul, rhs)
+					}
+					Token::Mul => product(vec![lhs, rhs]),
+					Token::Div => product(vec![lhs, power(rhs, number(Decimal::NEGATIVE_ONE))]),
+					Token::Pow => power(lhs, rhs),
+					op => fold(lhs, op.clone(), rhs),
+				}
+			}
+			Expression::Abs(inner) => match inner.simplify() {
+				inner @ Expression::Abs(..) => inner,
+				inner => match ratio(&inner) {
+					Some(n) if n.is_negative() => n.neg().expression(),
+					Some(n) => n.expression(),
+					None => Expression::Abs(Box::new(inner)),
+				},
+			},
+			Expression::Branched(condition, then, otherwise) => match condition.simplify() {
+				condition if ratio(&condition).is_some_and(Ratio::is_zero) => otherwise.simplify(),
+				condition if ratio(&condition).is_some() => then.simplify(),
+				condition => Expression::Branched(
+					Box::new(condition),
+					Box::new(then.simplify()),
+					Box::new(otherwise.simplify()),
+				),
+			},
+			Expression::FunctionCall(name, args) => {
+				let args = args
+					.iter()
+					.map(|(arg, _)| arg.simplify())
+					.collect::<Vec<Expression>>();
+
+				special(name, &args).unwrap_or_else(|| call(name, args))
+			}
+			Expression::Float(n) => number(n.normalize()),
+			expr @ (Expression::Matrix(..) | Expression::List(..)) => map(expr, Expression::simplify),
+			expr => expr.clone(),
+		}
+	}
+
+	/// Simplifies the expression using the identities between trigonometric
+	/// functions as well, which can make it longer in other places.
+	pub fn simplify_trig(&self) -> Expression {
+		trig(&self.simplify()).simplify()
+	}
+}
@@ -0,0 +1,474 @@
+use rust_decimal::{Decimal, MathematicalOps};
+
+use crate::{
+	algebra::{LARGEST_DIVIDED, irreducible, primitive},
+	errors::Error,
+	expr::Expression,
+	matrix::Complex,
+	simplify::{
+		Ratio, base_exponent, factors, negate, number, power, powers, product, ratio, sum, terms,
+	},
+	standardlibrary::math,
+	token::Token,
+	types::Data,
+};
+
+/// A root of a polynomial, written exactly along with its value when it is
+/// real
+enum Root {
+	Real(Expression, Decimal),
+	Complex(Complex),
+}
+
+impl Root {
+	/// Real and imaginary parts, by which roots are ordered
+	#[inline(always)]
+	fn value(&self) -> (Decimal, Decimal) {
+		match self {
+			Root::Real(_, value) => (*value, Decimal::ZERO),
+			Root::Complex(root) => (root.re, root.im),
+		}
+	}
+
+	#[inline(always)]
+	fn data(self) -> Data {
+		match self {
+			Root::Real(root, _) => solution(root),
+			Root::Complex(root) => Data::from(root.clean()),
+		}
+	}
+}
+
+#[inline(always)]
+fn call(name: &str, args: Vec<Expression>) -> Expression {
+	Expression::FunctionCall(
+		name.to_string(),
+		args.into_iter().map(|arg| (arg, 0..0)).collect(),
+	)
+}
+
+#[inline(always)]
+fn quotient(numerator: Expression, denominator: Expression) -> Expression {
+	product(vec![
+		numerator,
+		power(denominator, number(Decimal::NEGATIVE_ONE)),
+	])
+}
+
+#[inline(always)]
+fn is_zero(expr: &Expression) -> bool {
+	ratio(expr).is_some_and(Ratio::is_zero)
+}
+
+/// The difference of the sides of an equation, which is zero where it holds,
+/// or the expression itself when it is not an equation
+#[inline(always)]
+fn difference(equation: &Expression) -> Expression {
+	match equation {
+		Expression::Binary(lhs, Token::IsEq, rhs, _) => {
+			sum(vec![lhs.as_ref().clone(), negate(rhs.as_ref().clone())])
+		}
+		equation => equation.clone(),
+	}
+	.expand()
+}
+
+/// A solution as a number when it is one which a decimal holds exactly,
+/// otherwise as an expression
+#[inline(always)]
+fn solution(expr: Expression) -> Data {
+	let expr = expr.simplify();
+
+	match ratio(&expr) {
+		Some(n) if n.value() * n.denominator == n.numerator => Data::new_real(n.value().normalize()),
+		_ => Data::Expression(expr),
+	}
+}
+
+/// Takes the numbers multiplying every term out of each sum dividing a
+/// product, so that `-2/(-2a-4)` becomes `1/(a+2)`
+fn content(expr: Expression) -> Expression {
+	if matches!(expr, Expression::Binary(_, Token::Add | Token::Sub, _, _)) {
+		return expr;
+	}
+
+	product(
+		factors(expr)
+			.into_iter()
+			.map(|factor| {
+				let (base, exponent) = base_exponent(factor.clone());
+
+				let Some(n) = ratio(&exponent).filter(|n| n.is_negative()) else {
+					return factor;
+				};
+
+				let terms = terms(base);
+
+				let Some((constant, _)) = primitive(
+					&terms
+						.iter()
+						.map(|term| powers(vec![term.clone()]).0)
+						.collect::<Vec<Ratio>>(),
+				) else {
+					return factor;
+				};
+
+				let Some(scale) = constant.recip().filter(|_| terms.len() > 1) else {
+					return factor;
+				};
+
+				product(vec![
+					power(constant.expression(), n.expression()),
+					power(
+						sum(
+							terms
+								.into_iter()
+								.map(|term| product(vec![scale.expression(), term]))
+								.collect(),
+						),
+						n.expression(),
+					),
+				])
+			})
+			.collect(),
+	)
+}
+
+/// Square root of a positive integer with its square factors taken out, when
+/// it is small enough to search for them
+#[inline(always)]
+fn surd(n: Decimal) -> Expression {
+	let (mut outside, mut inside) = (Decimal::ONE, n);
+	let mut d = Decimal::TWO;
+
+	while n <= LARGEST_DIVIDED && d * d <= inside {
+		if (inside % (d * d)).is_zero() {
+			inside /= d * d;
+			outside *= d;
+		} else {
+			d += Decimal::ONE;
+		}
+	}
+
+	product(vec![number(outside), call("sqrt", vec![number(inside)])])
+}
+
+/// Roots of `ax² + bx + c` with integer coefficients, exact when they are
+/// real
+fn quadratic(a: Ratio, b: Ratio, c: Ratio) -> Option<Vec<Root>> {
+	let discriminant = b
+		.mul(b)
+		.add(a.mul(c).mul(Ratio::new(Decimal::from(-4), Decimal::ONE)?));
+	let two_a = a.mul(Ratio::new(Decimal::TWO, Decimal::ONE)?).recip()?;
+	let centre = b.neg().mul(two_a);
+
+	let width = discriminant.value().abs().sqrt()? * two_a.value();
+
+	if discriminant.is_negative() {
+		return Some(
+			[width, -width]
+				.into_iter()
+				.map(|im| Root::Complex(Complex::new(centre.value(), im)))
+				.collect(),
+		);
+	}
+
+	let root = product(vec![two_a.expression(), surd(discriminant.value())]);
+
+	Some(vec![
+		Root::Real(
+			sum(vec![centre.expression(), root.clone()]),
+			centre.value() + width,
+		),
+		Root::Real(
+			sum(vec![centre.expression(), negate(root)]),
+			centre.value() - width,
+		),
+	])
+}
+
+/// Roots of a polynomial with integer coefficients which does not factor over
+/// the rationals, exact for polynomials of degree two and for quadratics in
+/// `x²` with real roots, and otherwise found numerically
+fn irreducible_roots(f: &[Ratio]) -> Result<Vec<Root>, Error> {
+	let too_large = || Error::LogicError("coefficients are too large to solve exactly".to_string());
+
+	Ok(match f {
+		[a, b] => {
+			let root = b.neg().mul(a.recip().ok_or_else(too_large)?);
+
+			vec![Root::Real(root.expression(), root.value())]
+		}
+		&[a, b, c] => quadratic(a, b, c).ok_or_else(too_large)?,
+		&[a, b, c, d, e] if b.is_zero() && d.is_zero() => {
+			let mut roots = vec![];
+
+			for y in quadratic(a, c, e).ok_or_else(too_large)? {
+				match y {
+					Root::Real(y, value) if value.is_sign_positive() => {
+						let root = call("sqrt", vec![y]);
+						let value = value.sqrt().ok_or_else(too_large)?;
+
+						roots.push(Root::Real(root.clone(), value));
+						roots.push(Root::Real(negate(root), -value));
+					}
+					Root::Real(_, value) => {
+						let root = Complex::new(Decimal::ZERO, (-value).sqrt().ok_or_else(too_large)?);
+
+						roots.push(Root::Complex(root));
+						roots.push(Root::Complex(-root));
+					}
+					Root::Complex(y) => {
+						roots.push(Root::Complex(y.sqrt()));
+						roots.push(Root::Complex(-y.sqrt()));
+					}
+				}
+			}
+
+			roots
+		}
+		f => {
+			let Data::List(roots) = math::roots(&Data::List(
+				f.iter().map(|c| Data::new_real(c.value())).collect(),
+			))?
+			else {
+				unreachable!()
+			};
+
+			roots
+				.iter()
+				.map(|root| Complex::from_data(root).map(Root::Complex))
+				.collect::<Result<Vec<Root>, Error>>()?
+		}
+	})
+}
+
+/// Roots of a polynomial of degree one or two whose coefficients are not all
+/// numbers
+fn symbolic_roots(coefficients: &[Expression], x: &str) -> Result<Vec<Data>, Error> {
+	Ok(match coefficients {
+		[a, b] => vec![solution(negate(quotient(b.clone(), a.clone())))],
+		[a, b, c] => {
+			let root = call(
+				"sqrt",
+				vec![sum(vec![
+					power(b.clone(), number(Decimal::TWO)),
+					product(vec![number(Decimal::from(-4)), a.clone(), c.clone()]),
+				])],
+			);
+
+			[root.clone(), negate(root)]
+				.into_iter()
+				.map(|root| {
+					solution(quotient(
+						sum(vec![negate(b.clone()), root]),
+						product(vec![number(Decimal::TWO), a.clone()]),
+					))
+				})
+				.collect()
+		}
+		_ => {
+			return Err(Error::LogicError(format!(
+				"can not solve an equation of degree {} in `{x}` whose coefficients are not numbers",
+				coefficients.len() - 1
+			)));
+		}
+	})
+}
+
+impl Expression {
+	/// Distinct solutions for `x` of a polynomial equation `lhs == rhs`, or of
+	/// the expression being zero. Polynomials with rational coefficients are
+	/// factored and solved exactly up to quadratics, with complex solutions and
+	/// those of higher degree factors found as numbers.
+	pub fn solve(&self, x: &str) -> Result<Vec<Data>, Error> {
+		let f = difference(self);
+
+		if !f.involves(x) {
+			return if is_zero(&f) {
+				Err(Error::LogicError(format!(
+					"`{self}` holds for every value of `{x}`"
+				)))
+			} else {
+				Ok(vec![])
+			};
+		}
+
+		let mut coefficients = f.coefficients(x).map_err(|_| {
+			Error::LogicError(format!(
+				"expected a polynomial equation in `{x}`, found `{self}`"
+			))
+		})?;
+
+		let mut roots = vec![];
+
+		// Without a constant term zero is a solution
+		if coefficients.last().is_some_and(is_zero) {
+			while coefficients.last().is_some_and(is_zero) {
+				coefficients.pop();
+			}
+
+			roots.push(Root::Real(number(Decimal::ZERO), Decimal::ZERO));
+		}
+
+		let Some(f) = coefficients
+			.iter()
+			.map(ratio)
+			.collect::<Option<Vec<Ratio>>>()
+		else {
+			let mut solutions = roots.into_iter().map(Root::data).collect::<Vec<Data>>();
+
+			solutions.extend(symbolic_roots(&coefficients, x)?);
+
+			return Ok(solutions);
+		};
+
+		let Some((_, f)) = primitive(&f) else {
+			return Err(Error::LogicError(format!(
+				"coefficients of `{self}` are too large to solve exactly"
+			)));
+		};
+
+		let mut factors = irreducible(f);
+
+		// Repeated factors have the same roots
+		factors.sort_by_key(|factor| factor.iter().map(|c| c.value()).collect::<Vec<Decimal>>());
+		factors.dedup();
+
+		for factor in factors {
+			roots.extend(irreducible_roots(&factor)?);
+		}
+
+		roots.sort_by_key(Root::value);
+
+		Ok(roots.into_iter().map(Root::data).collect())
+	}
+}
+
+/// Values of the variables solving a system of linear equations, found by
+/// Gauss-Jordan elimination. Variables which the equations leave free are
+/// their own values, and the others are written in terms of them.
+pub(crate) fn solve_linear(
+	equations: &[Expression],
+	variables: &[String],
+) -> Result<Vec<Data>, Error> {
+	let not_linear = |equation: &Expression| {
+		Error::LogicError(format!(
+			"expected equations linear in {}, found `{equation}`",
+			variables.join(", ")
+		))
+	};
+
+	let zeros = variables
+		.iter()
+		.map(|variable| (variable.clone(), number(Decimal::ZERO)))
+		.collect::<Vec<(String, Expression)>>();
+
+	// Each row holds the coefficients of the variables and then the constant
+	// on the other side of the equation
+	let mut rows = vec![];
+
+	for equation in equations {
+		let f = difference(equation);
+		let mut row = vec![];
+
+		for variable in variables {
+			let coefficient = match &f.coefficients(variable).map_err(|_| not_linear(equation))?[..] {
+				[_] => number(Decimal::ZERO),
+				[coefficient, _] => coefficient.clone(),
+				_ => return Err(not_linear(equation)),
+			};
+
+			if variables
+				.iter()
+				.any(|variable| coefficient.involves(variable))
+			{
+				return Err(not_linear(equation));
+			}
+
+			row.push(coefficient);
+		}
+
+		row.push(negate(f.substitute(&zeros)).simplify());
+		rows.push(row);
+	}
+
+	let n = variables.len();
+	let mut pivots = vec![];
+
+	for column in 0..n {
+		let rank = pivots.len();
+
+		let Some(i) = (rank..rows.len()).find(|&i| !is_zero(&rows[i][column])) else {
+			continue;
+		};
+
+		rows.swap(rank, i);
+
+		// A number divides its row, while rows with other pivots are combined by
+		// multiplying across, which keeps their coefficients polynomials
+		if let Some(pivot) = ratio(&rows[rank][column]).and_then(Ratio::recip) {
+			rows[rank] = rows[rank]
+				.iter()
+				.map(|element| product(vec![pivot.expression(), element.clone()]))
+				.collect();
+		}
+
+		let pivot = rows[rank][column].clone();
+
+		for i in 0..rows.len() {
+			if i == rank || is_zero(&rows[i][column]) {
+				continue;
+			}
+
+			let multiple = rows[i][column].clone();
+
+			rows[i] = rows[i]
+				.iter()
+				.zip(&rows[rank])
+				.map(|(element, other)| {
+					sum(vec![
+						product(vec![pivot.clone(), element.clone()]),
+						negate(product(vec![multiple.clone(), other.clone()])),
+					])
+					.expand()
+				})
+				.collect();
+		}
+
+		pivots.push(column);
+	}
+
+	if rows[pivots.len()..].iter().any(|row| !is_zero(&row[n])) {
+		return Err(Error::LogicError(
+			"the system of equations has no solution".to_string(),
+		));
+	}
+
+	let mut values = variables
+		.iter()
+		.map(|variable| Expression::Identifier(variable.clone()))
+		.collect::<Vec<Expression>>();
+
+	for (row, &column) in rows.iter().zip(&pivots) {
+		let mut value = vec![row[n].clone()];
+
+		for (j, variable) in variables.iter().enumerate() {
+			if j != column && !pivots.contains(&j) {
+				value.push(negate(product(vec![
+					row[j].clone(),
+					Expression::Identifier(variable.clone()),
+				])));
+			}
+		}
+
+		values[column] = quotient(sum(value), row[column].clone());
+	}
+
+	Ok(
+		values
+			.into_iter()
+			.map(|value| solution(content(value.simplify())))
+			.collect(),
+	)
+}
@@ -0,0 +1,316 @@
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::{
+	errors::{Error, TypeError, ValueError},
+	expr::Expression,
+	interpreter::{Function, InterpreterContext},
+	series::Limit,
+	simplify::negate,
+	types::{Data, DataType},
+};
+
+/// The expression a function computes along with the variables it is a
+/// function of, which are the parameters of a function given by name and the
+/// unbound names of an expression
+#[inline(always)]
+pub(super) fn symbolic(
+	f: &Data,
+	ctx: &mut InterpreterContext,
+) -> Result<(Expression, Vec<String>), Error> {
+	match f {
+		Data::Ident(name) => Ok(match ctx.1.get(name) {
+			Some(Function::UserDefined(function)) => (
+				function.code.clone(),
+				function
+					.params
+					.iter()
+					.map(|(param, _)| param.clone())
+					.collect(),
+			),
+			Some(Function::STD(function)) => (
+				Expression::FunctionCall(
+					function.name.clone(),
+					vec![(Expression::Identifier("x".to_string()), 0..0)],
+				),
+				vec!["x".to_string()],
+			),
+			// A name which is not a function is a variable
+			None => (Expression::Identifier(name.clone()), vec![name.clone()]),
+		}),
+		Data::Expression(expression) => Ok((
+			expression.clone(),
+			expression
+				.identifiers()
+				.into_iter()
+				.filter(|name| !ctx.0.contains_key(name) && !ctx.1.contains_key(name))
+				.collect(),
+		)),
+		Data::Polynomial(polynomial) => Ok((
+			polynomial.expression(),
+			vec![polynomial.variable().to_string()],
+		)),
+		Data::Number(..) => Ok((Expression::from_data(f).unwrap(), vec![])),
+		_ => Err(TypeError::new(DataType::Ident, f.ty(), 0..0).to_error()),
+	}
+}
+
+/// Values held by a list, a matrix of names or expressions, or a single value
+#[inline(always)]
+pub(super) fn elements(data: &Data) -> Vec<Data> {
+	match data {
+		Data::List(list) => list.clone(),
+		Data::Expression(Expression::Matrix(rows)) => rows
+			.iter()
+			.flatten()
+			.map(|element| match element {
+				Expression::Identifier(name) => Data::Ident(name.clone()),
+				element => Data::Expression(element.clone()),
+			})
+			.collect(),
+		data => vec![data.clone()],
+	}
+}
+
+#[inline(always)]
+pub(super) fn variables(data: &Data) -> Result<Vec<String>, Error> {
+	elements(data)
+		.iter()
+		.map(|variable| match variable {
+			Data::Ident(name) => Ok(name.clone()),
+			_ => Err(TypeError::new(DataType::Ident, variable.ty(), 0..0).to_error()),
+		})
+		.collect()
+}
+
+#[inline(always)]
+fn partials(
+	expression: &Expression,
+	variables: &[String],
+	ctx: &mut InterpreterContext,
+) -> Result<Vec<Expression>, Error> {
+	variables
+		.iter()
+		.map(|variable| expression.differentiate(&Data::Ident(variable.clone()), ctx))
+		.collect()
+}
+
+/// Highest order of derivative `diff` takes, as each one can grow the
+/// expression
+const LARGEST_ORDER: usize = 100;
+
+/// The `n`-th derivative of a function with respect to one of its variables,
+/// the first when no order is given.
+pub fn diff<'a, 'b>(args: &[Data], ctx: &'a mut InterpreterContext<'b>) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let (mut expression, _) = symbolic(&args[0], ctx)?;
+	let [variable] = &variables(&args[1])?[..] else {
+		return Err(Error::LogicError(
+			"expected a single variable to differentiate with respect to".to_string(),
+		));
+	};
+
+	let order = match args.get(2) {
+		Some(Data::Number(n, im)) => match n.to_usize() {
+			Some(order) if im.is_zero() && n.is_integer() && order <= LARGEST_ORDER => order,
+			_ => {
+				return Err(
+					ValueError::new(
+						format!("cannot take a derivative of order `{}`", args[2]),
+						"the order of a derivative must be an integer from 0 to 100",
+						0..0,
+					)
+					.to_error(),
+				);
+			}
+		},
+		Some(n) => return Err(TypeError::new(DataType::Number, n.ty(), 0..0).to_error()),
+		None => 1,
+	};
+
+	for _ in 0..order {
+		expression = expression.differentiate(&Data::Ident(variable.clone()), ctx)?;
+	}
+
+	Ok(Data::Expression(expression))
+}
+
+/// Column of the partial derivatives of a function with respect to each of its
+/// variables, or to the given ones.
+pub fn grad<'a, 'b>(args: &[Data], ctx: &'a mut InterpreterContext<'b>) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let (expression, mut wrt) = symbolic(&args[0], ctx)?;
+
+	if let Some(given) = args.get(1) {
+		wrt = variables(given)?;
+	}
+
+	Ok(Data::Expression(Expression::Matrix(
+		partials(&expression, &wrt, ctx)?
+			.into_iter()
+			.map(|partial| vec![partial])
+			.collect(),
+	)))
+}
+
+/// Matrix with a row of partial derivatives for each function, with respect to
+/// the given variables or every variable of the functions in the order they
+/// first appear.
+pub fn jacobian<'a, 'b>(args: &[Data], ctx: &'a mut InterpreterContext<'b>) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let mut functions = vec![];
+	let mut wrt: Vec<String> = vec![];
+
+	for f in elements(&args[0]) {
+		let (expression, variables) = symbolic(&f, ctx)?;
+
+		for variable in variables {
+			if !wrt.contains(&variable) {
+				wrt.push(variable);
+			}
+		}
+
+		functions.push(expression);
+	}
+
+	if let Some(given) = args.get(1) {
+		wrt = variables(given)?;
+	}
+
+	Ok(Data::Expression(Expression::Matrix(
+		functions
+			.iter()
+			.map(|f| partials(f, &wrt, ctx))
+			.collect::<Result<Vec<Vec<Expression>>, Error>>()?,
+	)))
+}
+
+/// Symmetric matrix of the second partial derivatives of a function with
+/// respect to each pair of its variables, or of the given ones.
+pub fn hessian<'a, 'b>(args: &[Data], ctx: &'a mut InterpreterContext<'b>) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let (expression, mut wrt) = symbolic(&args[0], ctx)?;
+
+	if let Some(given) = args.get(1) {
+		wrt = variables(given)?;
+	}
+
+	let mut rows: Vec<Vec<Expression>> = vec![];
+
+	for (i, partial) in partials(&expression, &wrt, ctx)?.iter().enumerate() {
+		let mut row = vec![];
+
+		for (j, variable) in wrt.iter().enumerate() {
+			row.push(if j < i {
+				rows[j][i].clone()
+			} else {
+				partial.differentiate(&Data::Ident(variable.clone()), ctx)?
+			});
+		}
+
+		rows.push(row);
+	}
+
+	Ok(Data::Expression(Expression::Matrix(rows)))
+}
+
+/// Point a limit is taken at, where the identifier `inf` stands for infinity
+#[inline(always)]
+pub(super) fn point(a: &Data) -> Result<Limit, Error> {
+	let infinity = Expression::Identifier("inf".to_string());
+
+	match a {
+		Data::Number(a, b) if b.is_zero() => Ok(Limit::Finite(*a)),
+		Data::Ident(name) if name == "inf" => Ok(Limit::Infinite(true)),
+		Data::Expression(a) if a.simplify() == negate(infinity) => Ok(Limit::Infinite(false)),
+		_ => Err(TypeError::new(DataType::Number, a.ty(), 0..0).to_error()),
+	}
+}
+
+/// Limit of a function as one of its variables approaches a point or infinity,
+/// from the side given by the sign of the fourth argument or from both sides.
+pub fn limit<'a, 'b>(args: &[Data], ctx: &'a mut InterpreterContext<'b>) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let (expression, _) = symbolic(&args[0], ctx)?;
+	let [variable] = &variables(&args[1])?[..] else {
+		return Err(Error::LogicError(
+			"expected a single variable to take the limit in".to_string(),
+		));
+	};
+
+	let side = match args.get(3) {
+		Some(Data::Number(n, im)) if im.is_zero() && !n.is_zero() => Some(n.is_sign_positive()),
+		Some(_) => {
+			return Err(Error::LogicError(
+				"side of a limit must be a positive or a negative number".to_string(),
+			));
+		}
+		None => None,
+	};
+
+	Ok(
+		expression
+			.limit(variable, point(&args[2])?, side, ctx)?
+			.data(),
+	)
+}
+
+/// Taylor polynomial of a function of degree `n` about a point.
+pub fn taylor<'a, 'b>(args: &[Data], ctx: &'a mut InterpreterContext<'b>) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let (expression, _) = symbolic(&args[0], ctx)?;
+	let [variable] = &variables(&args[1])?[..] else {
+		return Err(Error::LogicError(
+			"expected a single variable to expand in".to_string(),
+		));
+	};
+
+	let Some(a) = Expression::from_data(&args[2]) else {
+		return Err(TypeError::new(DataType::Number, args[2].ty(), 0..0).to_error());
+	};
+
+	let degree = match &args[3] {
+		Data::Number(n, im) if im.is_zero() && n.is_integer() && !n.is_sign_negative() => {
+			n.to_usize().unwrap_or(usize::MAX)
+		}
+		_ => {
+			return Err(Error::LogicError(
+				"degree of a Taylor polynomial must be a non-negative integer".to_string(),
+			));
+		}
+	};
+
+	Ok(Data::Expression(
+		expression.taylor(variable, &a, degree, ctx)?,
+	))
+}
+
+/// Antiderivative of a function with respect to one of its variables.
+pub fn antiderivative<'a, 'b>(
+	f: &Data,
+	x: &Data,
+	ctx: &'a mut InterpreterContext<'b>,
+) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let (expression, _) = symbolic(f, ctx)?;
+	let [variable] = &variables(x)?[..] else {
+		return Err(Error::LogicError(
+			"expected a single variable to integrate with respect to".to_string(),
+		));
+	};
+
+	Ok(Data::Expression(expression.integrate(variable, ctx)?))
+}
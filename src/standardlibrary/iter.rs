@@ -1,26 +1,47 @@
-use crate::{errors::Error, interpreter::InterpreterContext, types::Data};
+use crate::{
+	errors::{Error, TypeError},
+	interpreter::InterpreterContext,
+	matrix::{Complex, Matrix},
+	types::{Data, DataType},
+};
 
 #[inline(always)]
 pub fn map(f: &Data, a: &Data, ctx: &mut InterpreterContext) -> Result<Data, Error> {
-	let Data::Matrix(matrix) = a else {
-		unreachable!()
+	let Data::Ident(g) = f else {
+		return Err(TypeError::new(DataType::Ident, f.ty(), 0..0).to_error());
 	};
 
-	let Data::Ident(g) = f else { unreachable!() };
+	let Some(func) = ctx.1.get(g).cloned() else {
+		return Err(Error::LogicError(format!("undefined function: `{g}`")));
+	};
+
+	if let Data::List(list) = a {
+		let mut list_data = vec![];
 
-	let func = ctx.1.get(g).unwrap().clone();
+		for element in list {
+			list_data.push(func.execute(ctx, vec![element.clone()])?);
+		}
+
+		return Ok(Data::List(list_data));
+	}
+
+	// The function is applied to the zeros of a sparse matrix as well, as it
+	// need not keep them zero
+	let matrix = match a {
+		Data::Matrix(matrix) => matrix,
+		Data::Sparse(matrix) => &matrix.to_dense(),
+		_ => return Err(TypeError::new(DataType::Matrix, a.ty(), 0..0).to_error()),
+	};
 
 	let mut matrix_data = vec![];
 
-	for row in matrix {
-		let mut row_data = vec![];
-		for element in row {
-			let data = func.execute(ctx, vec![element.clone()])?;
+	for &element in matrix.elements() {
+		let data = func.execute(ctx, vec![Data::from(element)])?;
 
-			row_data.push(data);
-		}
-		matrix_data.push(row_data);
+		matrix_data.push(Complex::from_data(&data)?);
 	}
 
-	Ok(Data::Matrix(matrix_data))
+	let (rows, cols) = matrix.shape();
+
+	Ok(Data::Matrix(Matrix::new(rows, cols, matrix_data)))
 }
@@ -0,0 +1,1152 @@
+use std::cmp::Ordering;
+
+use rust_decimal::{Decimal, MathematicalOps, prelude::ToPrimitive};
+
+use crate::{
+	errors::{Error, ShapeError, TypeError, ValueError},
+	matrix::{Complex, Matrix, length},
+	standardlibrary::math::square_matrix,
+	types::{Data, DataType},
+};
+
+/// Size relative to the largest element of a matrix below which values are
+/// treated as zero, a few digits short of the 28 that decimals carry.
+const EPSILON: Decimal = Decimal::from_parts(1, 0, 0, false, 24);
+
+/// Last digit a decimal can hold, values no larger are always treated as zero.
+const SMALLEST: Decimal = Decimal::from_parts(1, 0, 0, false, 28);
+
+/// Cosine of the angle between two columns below which the singular value
+/// decomposition treats them as orthogonal.
+const ORTHOGONAL: Decimal = Decimal::from_parts(1, 0, 0, false, 20);
+
+/// Distance relative to the size of a matrix within which eigenvalues are
+/// treated as repeated, since a repeated eigenvalue of a defective matrix is
+/// only found to about the square root of the precision.
+const REPEATED: Decimal = Decimal::from_parts(1, 0, 0, false, 8);
+
+impl Complex {
+	#[inline(always)]
+	fn is_negligible(self, tolerance: Decimal) -> bool {
+		self.magnitude() <= tolerance
+	}
+
+	/// Rounds away the noise left behind by elimination before handing the value
+	/// back to the interpreter.
+	#[inline(always)]
+	pub(crate) fn clean(self) -> Self {
+		let clean = |x: Decimal| x.round_sf(20).unwrap_or(x).round_dp(20).normalize();
+
+		Complex::new(clean(self.re), clean(self.im))
+	}
+
+	/// Rounds away the noise left behind by elimination, treating parts no larger
+	/// than `tolerance` as zero.
+	#[inline(always)]
+	fn round(self, tolerance: Decimal) -> Self {
+		let round = |x: Decimal| {
+			if x.abs() <= tolerance {
+				Decimal::ZERO
+			} else {
+				x.round_sf(20).unwrap_or(x).normalize()
+			}
+		};
+
+		Complex::new(round(self.re), round(self.im))
+	}
+
+	#[inline(always)]
+	fn to_data(self) -> Data {
+		Data::from(self.round(SMALLEST))
+	}
+}
+
+/// Size below which values are treated as zero next to values as large as
+/// `scale` in a matrix with `size` rows or columns, as the rounding error grows
+/// with both.
+#[inline(always)]
+fn negligible(scale: Decimal, size: usize) -> Decimal {
+	(EPSILON * Decimal::from(size) * scale).max(SMALLEST)
+}
+
+#[inline(always)]
+fn magnitude(matrix: &Matrix) -> Decimal {
+	matrix
+		.elements()
+		.iter()
+		.map(|z| z.magnitude())
+		.max()
+		.unwrap_or_default()
+}
+
+#[inline(always)]
+fn tolerance(matrix: &Matrix) -> Decimal {
+	negligible(magnitude(matrix), matrix.rows().max(matrix.cols()))
+}
+
+/// Divides `matrix` by its largest element, so that the digits of a decimal are
+/// not lost to leading zeros when its elements are small. Returns the scaled
+/// matrix and the factor it was divided by.
+#[inline(always)]
+fn normalized(matrix: &Matrix) -> (Matrix, Decimal) {
+	let scale = magnitude(matrix);
+
+	if scale.is_zero() {
+		return (matrix.clone(), Decimal::ONE);
+	}
+
+	(
+		matrix.map(|z| Complex::new(z.re / scale, z.im / scale)),
+		scale,
+	)
+}
+
+#[inline(always)]
+fn matrix(v: &Data) -> Result<&Matrix, Error> {
+	match v {
+		Data::Matrix(matrix) => Ok(matrix),
+		_ => Err(TypeError::new(DataType::Matrix, v.ty(), 0..0).to_error()),
+	}
+}
+
+#[inline(always)]
+fn to_data(matrix: Matrix) -> Data {
+	let tolerance = tolerance(&matrix);
+
+	Data::Matrix(matrix.map(|z| z.round(tolerance)))
+}
+
+#[inline(always)]
+fn conjugate_transpose(a: &Matrix) -> Matrix {
+	a.transpose().map(Complex::conj)
+}
+
+/// Reads a matrix dimension given as an argument
+#[inline(always)]
+pub(super) fn dimension(d: &Data) -> Result<usize, Error> {
+	match d {
+		Data::Number(a, b) if b.is_zero() && a.fract().is_zero() && *a >= Decimal::ONE => a
+			.to_usize()
+			.ok_or_else(|| too_large(&format!("a matrix dimension of {a}"))),
+		Data::Number(..) => Err(
+			ValueError::new(
+				format!("`{d}` is not a matrix dimension"),
+				"matrix dimensions must be positive integers",
+				0..0,
+			)
+			.to_error(),
+		),
+		_ => Err(TypeError::new(DataType::Number, d.ty(), 0..0).to_error()),
+	}
+}
+
+/// Reads the dimensions of a dense matrix, which must have few enough elements
+/// to be stored.
+#[inline(always)]
+pub(super) fn shape(m: &Data, n: &Data) -> Result<(usize, usize), Error> {
+	let (m, n) = (dimension(m)?, dimension(n)?);
+
+	match m.checked_mul(n) {
+		Some(size) if size <= isize::MAX as usize / size_of::<Complex>() => Ok((m, n)),
+		_ => Err(too_large(&format!("a {m}x{n} matrix"))),
+	}
+}
+
+#[inline(always)]
+fn too_large(what: &str) -> Error {
+	ValueError::new(
+		format!("{what} is too large to store"),
+		"every element of a dense matrix is stored, use a sparse matrix for large ones",
+		0..0,
+	)
+	.to_error()
+}
+
+/// `PA = LU` with the multipliers of `L` stored below the diagonal of `U`.
+struct Decomposition {
+	lu: Matrix,
+	permutation: Vec<usize>,
+	odd: bool,
+	tolerance: Decimal,
+}
+
+impl Decomposition {
+	#[inline(always)]
+	fn new(mut lu: Matrix) -> Self {
+		let n = lu.rows();
+		let tolerance = tolerance(&lu);
+		let mut permutation = (0..n).collect::<Vec<usize>>();
+		let mut odd = false;
+
+		for k in 0..n {
+			let pivot = (k..n)
+				.max_by(|&a, &b| lu[(a, k)].magnitude().cmp(&lu[(b, k)].magnitude()))
+				.unwrap();
+
+			if lu[(pivot, k)].is_negligible(tolerance) {
+				continue;
+			}
+
+			if pivot != k {
+				lu.swap_rows(pivot, k);
+				permutation.swap(pivot, k);
+				odd = !odd;
+			}
+
+			for i in k + 1..n {
+				let multiplier = lu[(i, k)] / lu[(k, k)];
+
+				lu[(i, k)] = multiplier;
+
+				for j in k + 1..n {
+					let delta = multiplier * lu[(k, j)];
+
+					lu[(i, j)] = lu[(i, j)] - delta;
+				}
+			}
+		}
+
+		Self {
+			lu,
+			permutation,
+			odd,
+			tolerance,
+		}
+	}
+
+	#[inline(always)]
+	fn is_singular(&self) -> bool {
+		(0..self.lu.rows()).any(|i| self.lu[(i, i)].is_negligible(self.tolerance))
+	}
+
+	/// Solves `Ax = b` for every column of `b` by forward and back substitution.
+	#[inline(always)]
+	fn solve(&self, b: &Matrix) -> Result<Matrix, Error> {
+		if self.is_singular() {
+			return Err(Error::LogicError(
+				"matrix is singular so the system has no unique solution".to_string(),
+			));
+		}
+
+		let n = self.lu.rows();
+
+		let mut x = Matrix::from_fn(n, b.cols(), |i, j| b[(self.permutation[i], j)]);
+
+		for col in 0..x.cols() {
+			for i in 0..n {
+				for k in 0..i {
+					let delta = self.lu[(i, k)] * x[(k, col)];
+
+					x[(i, col)] = x[(i, col)] - delta;
+				}
+			}
+
+			for i in (0..n).rev() {
+				for k in i + 1..n {
+					let delta = self.lu[(i, k)] * x[(k, col)];
+
+					x[(i, col)] = x[(i, col)] - delta;
+				}
+
+				x[(i, col)] = x[(i, col)] / self.lu[(i, i)];
+			}
+		}
+
+		Ok(x)
+	}
+}
+
+/// Householder reflection `I - 2 * v * v^H` for a unit vector `v` acting on the
+/// entries from `offset` onwards.
+struct Reflector {
+	v: Vec<Complex>,
+	offset: usize,
+}
+
+impl Reflector {
+	/// Reflector which maps `x` onto a multiple of the first basis vector, `None`
+	/// when `x` is negligible.
+	#[inline(always)]
+	fn new(mut x: Vec<Complex>, offset: usize, tolerance: Decimal) -> Option<Self> {
+		let norm = length(&x);
+
+		if norm <= tolerance {
+			return None;
+		}
+
+		let phase = if x[0].magnitude().is_zero() {
+			Complex::ONE
+		} else {
+			x[0] / Complex::real(x[0].modulus())
+		};
+
+		x[0] = x[0] + phase * Complex::real(norm);
+
+		let norm = Complex::real(length(&x));
+
+		Some(Self {
+			v: x.into_iter().map(|z| z / norm).collect(),
+			offset,
+		})
+	}
+
+	/// Replaces `matrix` with `H * matrix`
+	#[inline(always)]
+	fn apply_left(&self, matrix: &mut Matrix) {
+		let mut s = vec![Complex::ZERO; matrix.cols()];
+
+		for (k, v) in self.v.iter().enumerate() {
+			for (s, &x) in s.iter_mut().zip(matrix.row(self.offset + k)) {
+				*s = *s + v.conj() * x;
+			}
+		}
+
+		for (k, &v) in self.v.iter().enumerate() {
+			for (x, &s) in matrix.row_mut(self.offset + k).iter_mut().zip(&s) {
+				*x = *x - Complex::TWO * v * s;
+			}
+		}
+	}
+
+	/// Replaces `matrix` with `matrix * H`
+	#[inline(always)]
+	fn apply_right(&self, matrix: &mut Matrix) {
+		for i in 0..matrix.rows() {
+			let row = &mut matrix.row_mut(i)[self.offset..];
+
+			let s = row
+				.iter()
+				.zip(&self.v)
+				.fold(Complex::ZERO, |s, (&x, &v)| s + x * v)
+				* Complex::TWO;
+
+			for (x, v) in row.iter_mut().zip(&self.v) {
+				*x = *x - s * v.conj();
+			}
+		}
+	}
+}
+
+/// Full QR decomposition of `r` with the diagonal of `R` made real and
+/// non-negative.
+#[inline(always)]
+fn householder(mut r: Matrix) -> (Matrix, Matrix) {
+	let (m, n) = r.shape();
+	let tolerance = tolerance(&r);
+
+	let mut q = Matrix::identity(m);
+
+	for k in 0..n.min(m.saturating_sub(1)) {
+		if let Some(reflector) = Reflector::new((k..m).map(|i| r[(i, k)]).collect(), k, tolerance) {
+			reflector.apply_left(&mut r);
+			reflector.apply_right(&mut q);
+		}
+	}
+
+	for k in 0..n.min(m) {
+		if r[(k, k)].is_negligible(tolerance) {
+			continue;
+		}
+
+		let phase = r[(k, k)] / Complex::real(r[(k, k)].modulus());
+
+		for element in r.row_mut(k) {
+			*element = phase.conj() * *element;
+		}
+
+		for i in 0..m {
+			q[(i, k)] = q[(i, k)] * phase;
+		}
+	}
+
+	(q, r)
+}
+
+/// Orders eigenvalues and singular values from the largest modulus down,
+/// ignoring the noise in the digits past those which are displayed.
+#[inline(always)]
+fn descending(a: &Complex, b: &Complex) -> Ordering {
+	let key = |z: &Complex| {
+		(
+			z.modulus().round_dp(15),
+			z.re.round_dp(15),
+			z.im.round_dp(15),
+		)
+	};
+
+	key(b).cmp(&key(a))
+}
+
+/// Eigenvalues of the trailing 2x2 block of `a`
+#[inline(always)]
+fn trailing_eigenvalues(a: &Matrix) -> (Complex, Complex) {
+	let k = a.rows();
+
+	let (p, b, c, d) = (
+		a[(k - 2, k - 2)],
+		a[(k - 2, k - 1)],
+		a[(k - 1, k - 2)],
+		a[(k - 1, k - 1)],
+	);
+
+	let half = (p + d) / Complex::real(Decimal::TWO);
+	let discriminant = (half * half - (p * d - b * c)).sqrt();
+
+	(half + discriminant, half - discriminant)
+}
+
+/// Eigenvalues by the shifted QR algorithm on the Hessenberg form of `a`,
+/// deflating one eigenvalue at a time off the bottom of the matrix.
+#[inline(always)]
+fn eigenvalues(a: &Matrix) -> Result<Vec<Complex>, Error> {
+	let (mut a, scale) = normalized(a);
+
+	let n = a.rows();
+	let tolerance = tolerance(&a);
+
+	for k in 0..n.saturating_sub(2) {
+		if let Some(reflector) =
+			Reflector::new((k + 1..n).map(|i| a[(i, k)]).collect(), k + 1, tolerance)
+		{
+			reflector.apply_left(&mut a);
+			reflector.apply_right(&mut a);
+		}
+	}
+
+	let mut values = vec![];
+	let mut iterations = 0;
+
+	while a.rows() > 2 {
+		let k = a.rows();
+
+		let (p, d) = (a[(k - 2, k - 2)], a[(k - 1, k - 1)]);
+
+		if a[(k - 1, k - 2)].modulus() <= (EPSILON * (p.modulus() + d.modulus())).max(tolerance) {
+			values.push(d);
+
+			a = Matrix::from_fn(k - 1, k - 1, |i, j| a[(i, j)]);
+
+			iterations = 0;
+
+			continue;
+		}
+
+		iterations += 1;
+
+		if iterations > 100 {
+			return Err(Error::LogicError(
+				"eigenvalues of the matrix did not converge".to_string(),
+			));
+		}
+
+		let (l1, l2) = trailing_eigenvalues(&a);
+
+		let mut shift = if (l1 - d).modulus() < (l2 - d).modulus() {
+			l1
+		} else {
+			l2
+		};
+
+		// An exceptional shift breaks the cycles the Wilkinson shift can get stuck in.
+		if iterations % 11 == 0 {
+			shift = shift + Complex::real(a[(k - 1, k - 2)].modulus());
+		}
+
+		for i in 0..k {
+			a[(i, i)] = a[(i, i)] - shift;
+		}
+
+		let (q, r) = householder(a);
+
+		a = r.matmul(&q);
+
+		for i in 0..k {
+			a[(i, i)] = a[(i, i)] + shift;
+		}
+	}
+
+	match a.rows() {
+		2 => {
+			let (l1, l2) = trailing_eigenvalues(&a);
+
+			values.extend([l1, l2]);
+		}
+		1 => values.push(a[(0, 0)]),
+		_ => {}
+	}
+
+	let mut values = values
+		.into_iter()
+		.map(|z| z * Complex::real(scale))
+		.collect::<Vec<Complex>>();
+
+	values.sort_by(descending);
+
+	Ok(values)
+}
+
+/// Unit eigenvectors for an eigenvalue repeated `multiplicity` times, which
+/// are the right singular vectors of `a - value * I` whose singular values
+/// vanish. A defective matrix has fewer of these than the multiplicity.
+#[inline(always)]
+fn eigenvectors(
+	a: &Matrix,
+	value: Complex,
+	multiplicity: usize,
+) -> Result<Vec<Vec<Complex>>, Error> {
+	let n = a.rows();
+
+	let mut shifted = a.clone();
+
+	for i in 0..n {
+		shifted[(i, i)] = shifted[(i, i)] - value;
+	}
+
+	let (_, sigmas, v) = singular_values(shifted)?;
+
+	let vanishing = sigmas
+		.iter()
+		.filter(|&&sigma| sigma <= REPEATED * Decimal::from(n))
+		.count()
+		.clamp(1, multiplicity);
+
+	Ok(
+		(n - vanishing..n)
+			.map(|k| {
+				let x = (0..n).map(|i| v[(i, k)]).collect::<Vec<Complex>>();
+
+				// Rotate the vector so that its largest component is real and positive.
+				let largest = *x
+					.iter()
+					.max_by(|a, b| a.magnitude().cmp(&b.magnitude()))
+					.unwrap();
+
+				let phase = largest.conj() / Complex::real(largest.modulus());
+
+				x.into_iter().map(|x| x * phase).collect()
+			})
+			.collect(),
+	)
+}
+
+/// Thin singular value decomposition `A = U * S * V^H` by one sided Jacobi
+/// rotations, with the singular values in descending order.
+#[inline(always)]
+fn singular_values(a: Matrix) -> Result<(Matrix, Vec<Decimal>, Matrix), Error> {
+	let (m, n) = a.shape();
+
+	if m < n {
+		let (u, s, v) = singular_values(conjugate_transpose(&a))?;
+
+		return Ok((v, s, u));
+	}
+
+	let (a, scale) = normalized(&a);
+	let tolerance = tolerance(&a);
+
+	// Both are stored with a row for each of their columns while rotating.
+	let mut u = a.transpose();
+	let mut v = Matrix::identity(n);
+
+	for sweep in 0.. {
+		if sweep == 100 {
+			return Err(Error::LogicError(
+				"singular value decomposition did not converge".to_string(),
+			));
+		}
+
+		let mut rotated = false;
+
+		for p in 0..n {
+			for q in p + 1..n {
+				let (alpha, beta) = (length(u.row(p)), length(u.row(q)));
+				let gamma = u
+					.row(p)
+					.iter()
+					.zip(u.row(q))
+					.fold(Complex::ZERO, |s, (&x, &y)| s + x.conj() * y);
+
+				let g = gamma.modulus();
+
+				// Past the last digit of a decimal rotating further changes nothing.
+				if alpha.min(beta) <= tolerance
+					|| g / alpha / beta <= ORTHOGONAL
+					|| g <= SMALLEST * Decimal::from(m)
+				{
+					continue;
+				}
+
+				rotated = true;
+
+				let phase = gamma.conj() / Complex::real(g);
+
+				let zeta = (beta - alpha) / g * ((beta + alpha) / Decimal::TWO);
+
+				let t = if zeta.abs() > Decimal::from(1_000_000_000_000u64) {
+					Decimal::ONE / (Decimal::TWO * zeta)
+				} else {
+					let sign = if zeta.is_sign_negative() {
+						Decimal::NEGATIVE_ONE
+					} else {
+						Decimal::ONE
+					};
+
+					sign / (zeta.abs() + (Decimal::ONE + zeta * zeta).sqrt().unwrap())
+				};
+
+				let c = Complex::real(Decimal::ONE / (Decimal::ONE + t * t).sqrt().unwrap());
+				let s = c * Complex::real(t);
+
+				for columns in [&mut u, &mut v] {
+					for i in 0..columns.cols() {
+						let (x, y) = (columns[(p, i)], phase * columns[(q, i)]);
+
+						columns[(p, i)] = c * x - s * y;
+						columns[(q, i)] = s * x + c * y;
+					}
+				}
+			}
+		}
+
+		if !rotated {
+			break;
+		}
+	}
+
+	let mut order = (0..n)
+		.map(|j| (j, length(u.row(j)) * scale))
+		.collect::<Vec<(usize, Decimal)>>();
+
+	order.sort_by(|a, b| descending(&Complex::real(a.1), &Complex::real(b.1)));
+
+	let cutoff = negligible(
+		order.first().map(|(_, sigma)| *sigma).unwrap_or_default(),
+		m,
+	);
+
+	let mut columns = Matrix::zeros(n, m);
+	let mut sigmas = vec![];
+
+	for (k, &(j, sigma)) in order.iter().enumerate() {
+		let column = if sigma > cutoff {
+			let norm = Complex::real(length(u.row(j)));
+
+			u.row(j).iter().map(|&z| z / norm).collect()
+		} else {
+			orthogonal_complement(&columns, k)
+		};
+
+		columns.row_mut(k).copy_from_slice(&column);
+
+		sigmas.push(sigma);
+	}
+
+	let v = Matrix::from_fn(n, n, |i, k| v[(order[k].0, i)]);
+
+	Ok((columns.transpose(), sigmas, v))
+}
+
+/// Unit vector orthogonal to the first `count` rows of `rows`
+#[inline(always)]
+fn orthogonal_complement(rows: &Matrix, count: usize) -> Vec<Complex> {
+	let m = rows.cols();
+
+	for i in 0..m {
+		let mut x = vec![Complex::ZERO; m];
+		x[i] = Complex::ONE;
+
+		for u in rows.iter_rows().take(count) {
+			let projection = u
+				.iter()
+				.zip(&x)
+				.fold(Complex::ZERO, |s, (&u, &x)| s + u.conj() * x);
+
+			for (x, &u) in x.iter_mut().zip(u) {
+				*x = *x - projection * u;
+			}
+		}
+
+		let norm = length(&x);
+
+		if norm > Decimal::new(5, 1) {
+			return x.into_iter().map(|x| x / Complex::real(norm)).collect();
+		}
+	}
+
+	vec![Complex::ZERO; m]
+}
+
+/// Least squares solution of `Ax = b` for an `A` with at least as many rows as
+/// columns, found from its QR decomposition.
+#[inline(always)]
+pub(super) fn least_squares(a: Matrix, b: &Matrix) -> Result<Matrix, Error> {
+	let n = a.cols();
+	let tolerance = tolerance(&a);
+
+	let (q, r) = householder(a);
+
+	if (0..n).any(|k| r[(k, k)].is_negligible(tolerance)) {
+		return Err(Error::LogicError(
+			"columns are linearly dependent so the least squares solution is not unique".to_string(),
+		));
+	}
+
+	let y = conjugate_transpose(&q).matmul(b);
+	let mut x = Matrix::from_fn(n, b.cols(), |i, j| y[(i, j)]);
+
+	for col in 0..x.cols() {
+		for i in (0..n).rev() {
+			for k in i + 1..n {
+				let delta = r[(i, k)] * x[(k, col)];
+
+				x[(i, col)] = x[(i, col)] - delta;
+			}
+
+			x[(i, col)] = x[(i, col)] / r[(i, i)];
+		}
+	}
+
+	Ok(x)
+}
+
+/// Reduces `matrix` to reduced row echelon form, returning the pivot columns.
+#[inline(always)]
+fn row_reduce(matrix: &mut Matrix) -> Vec<usize> {
+	let (rows, cols) = matrix.shape();
+	let tolerance = tolerance(matrix);
+
+	let mut pivots = vec![];
+
+	for col in 0..cols {
+		let row = pivots.len();
+
+		if row == rows {
+			break;
+		}
+
+		let pivot = (row..rows)
+			.max_by(|&a, &b| {
+				matrix[(a, col)]
+					.magnitude()
+					.cmp(&matrix[(b, col)].magnitude())
+			})
+			.unwrap();
+
+		if matrix[(pivot, col)].is_negligible(tolerance) {
+			for i in row..rows {
+				matrix[(i, col)] = Complex::ZERO;
+			}
+
+			continue;
+		}
+
+		matrix.swap_rows(pivot, row);
+
+		let scale = matrix[(row, col)];
+
+		for element in matrix.row_mut(row) {
+			*element = *element / scale;
+		}
+
+		for i in 0..rows {
+			let factor = matrix[(i, col)];
+
+			if i == row || factor == Complex::ZERO {
+				continue;
+			}
+
+			for j in 0..cols {
+				let delta = factor * matrix[(row, j)];
+
+				matrix[(i, j)] = matrix[(i, j)] - delta;
+			}
+		}
+
+		pivots.push(col);
+	}
+
+	pivots
+}
+
+#[inline(always)]
+pub fn determinant(v: &Data) -> Result<Data, Error> {
+	square_matrix(v, "matrix must be square to have a determinant")?;
+
+	let decomposition = Decomposition::new(matrix(v)?.clone());
+
+	if decomposition.is_singular() {
+		return Ok(Data::new_real(Decimal::ZERO));
+	}
+
+	let mut delta = if decomposition.odd {
+		-Complex::ONE
+	} else {
+		Complex::ONE
+	};
+
+	for i in 0..decomposition.lu.rows() {
+		delta = delta * decomposition.lu[(i, i)];
+	}
+
+	Ok(delta.to_data())
+}
+
+#[inline(always)]
+pub fn inverse(v: &Data) -> Result<Data, Error> {
+	square_matrix(v, "matrix must be square to have an inverse")?;
+
+	let a = matrix(v)?;
+
+	Decomposition::new(a.clone())
+		.solve(&Matrix::identity(a.rows()))
+		.map(to_data)
+		.map_err(|_| Error::LogicError("matrix is singular so it has no inverse".to_string()))
+}
+
+#[inline(always)]
+pub fn solve(a: &Data, b: &Data) -> Result<Data, Error> {
+	square_matrix(a, "matrix of coefficients must be square to solve a system")?;
+
+	let (n, _) = a.shape();
+	let (rows, cols) = b.shape();
+
+	if rows != n {
+		return Err(
+			ShapeError::new(
+				(n, cols),
+				(rows, cols),
+				"right hand side must have a row for every equation",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	Decomposition::new(matrix(a)?.clone())
+		.solve(matrix(b)?)
+		.map(to_data)
+}
+
+/// Returns `{L, U, P}` such that `P * A = L * U`.
+#[inline(always)]
+pub fn lu(v: &Data) -> Result<Data, Error> {
+	square_matrix(v, "matrix must be square to have an LU decomposition")?;
+
+	let Decomposition {
+		lu, permutation, ..
+	} = Decomposition::new(matrix(v)?.clone());
+
+	let n = lu.rows();
+
+	let l = Matrix::from_fn(n, n, |i, j| match j.cmp(&i) {
+		Ordering::Less => lu[(i, j)],
+		Ordering::Equal => Complex::ONE,
+		Ordering::Greater => Complex::ZERO,
+	});
+	let u = Matrix::from_fn(n, n, |i, j| if j < i { Complex::ZERO } else { lu[(i, j)] });
+	let p = Matrix::from_fn(n, n, |i, j| {
+		if permutation[i] == j {
+			Complex::ONE
+		} else {
+			Complex::ZERO
+		}
+	});
+
+	Ok(Data::List(vec![to_data(l), to_data(u), to_data(p)]))
+}
+
+/// Returns `{Q, R}` such that `A = Q * R`, computed with Householder reflections.
+#[inline(always)]
+pub fn qr(v: &Data) -> Result<Data, Error> {
+	let (q, r) = householder(matrix(v)?.clone());
+
+	Ok(Data::List(vec![to_data(q), to_data(r)]))
+}
+
+#[inline(always)]
+pub fn rref(v: &Data) -> Result<Data, Error> {
+	let mut matrix = matrix(v)?.clone();
+
+	row_reduce(&mut matrix);
+
+	Ok(to_data(matrix))
+}
+
+#[inline(always)]
+pub fn rank(v: &Data) -> Result<Data, Error> {
+	let mut matrix = matrix(v)?.clone();
+
+	Ok(Data::new_real(Decimal::from(row_reduce(&mut matrix).len())))
+}
+
+/// Returns a matrix whose columns form a basis of the null space.
+#[inline(always)]
+pub fn nullspace(v: &Data) -> Result<Data, Error> {
+	let mut matrix = matrix(v)?.clone();
+
+	let cols = matrix.cols();
+
+	let pivots = row_reduce(&mut matrix);
+
+	let free = (0..cols)
+		.filter(|col| !pivots.contains(col))
+		.collect::<Vec<usize>>();
+
+	if free.is_empty() {
+		return Ok(Data::Matrix(Matrix::zeros(0, 0)));
+	}
+
+	let mut basis = Matrix::zeros(cols, free.len());
+
+	for (k, &f) in free.iter().enumerate() {
+		basis[(f, k)] = Complex::ONE;
+
+		for (row, &p) in pivots.iter().enumerate() {
+			basis[(p, k)] = -matrix[(row, f)];
+		}
+	}
+
+	Ok(to_data(basis))
+}
+
+#[inline(always)]
+pub fn trace(v: &Data) -> Result<Data, Error> {
+	square_matrix(v, "matrix must be square to have a trace")?;
+
+	let matrix = matrix(v)?;
+
+	Ok(
+		(0..matrix.rows())
+			.fold(Complex::ZERO, |sum, i| sum + matrix[(i, i)])
+			.to_data(),
+	)
+}
+
+#[inline(always)]
+pub fn identity(n: &Data) -> Result<Data, Error> {
+	Ok(Data::Matrix(Matrix::identity(shape(n, n)?.0)))
+}
+
+#[inline(always)]
+pub fn zeros(m: &Data, n: &Data) -> Result<Data, Error> {
+	let (m, n) = shape(m, n)?;
+
+	Ok(Data::Matrix(Matrix::zeros(m, n)))
+}
+
+#[inline(always)]
+pub fn ones(m: &Data, n: &Data) -> Result<Data, Error> {
+	let (m, n) = shape(m, n)?;
+
+	Ok(Data::Matrix(Matrix::new(m, n, vec![Complex::ONE; m * n])))
+}
+
+#[inline(always)]
+pub fn eigvals(v: &Data) -> Result<Data, Error> {
+	square_matrix(v, "matrix must be square to have eigenvalues")?;
+
+	let a = matrix(v)?;
+	let tolerance = tolerance(a);
+
+	Ok(Data::List(
+		eigenvalues(a)?
+			.into_iter()
+			.map(|z| Data::from(z.round(tolerance)))
+			.collect(),
+	))
+}
+
+/// Returns `{values, vectors}` where the columns of `vectors` are the unit
+/// eigenvectors for each value in `values`. Values are repeated as often as
+/// `eigvals` repeats them, and a defective matrix which has fewer independent
+/// eigenvectors than that repeats the last of them.
+#[inline(always)]
+pub fn eig(v: &Data) -> Result<Data, Error> {
+	square_matrix(v, "matrix must be square to have eigenvectors")?;
+
+	let a = matrix(v)?;
+	let n = a.rows();
+	let tolerance = tolerance(a);
+
+	let values = eigenvalues(a)?;
+
+	let (a, scale) = normalized(a);
+
+	let mut vectors = Matrix::zeros(n, n);
+	let mut found = vec![false; n];
+
+	for i in 0..n {
+		if found[i] {
+			continue;
+		}
+
+		let repeated = (i..n)
+			.filter(|&j| !found[j] && (values[j] - values[i]).modulus() <= REPEATED * scale)
+			.collect::<Vec<usize>>();
+
+		let value = repeated
+			.iter()
+			.fold(Complex::ZERO, |sum, &j| sum + values[j])
+			/ Complex::real(Decimal::from(repeated.len()) * scale);
+
+		let basis = eigenvectors(&a, value, repeated.len())?;
+
+		for (k, &j) in repeated.iter().enumerate() {
+			for (row, &x) in basis[k.min(basis.len() - 1)].iter().enumerate() {
+				vectors[(row, j)] = x;
+			}
+
+			found[j] = true;
+		}
+	}
+
+	Ok(Data::List(vec![
+		Data::List(
+			values
+				.into_iter()
+				.map(|z| Data::from(z.round(tolerance)))
+				.collect(),
+		),
+		to_data(vectors),
+	]))
+}
+
+/// Returns `{U, S, V}` such that `A = U * S * V^H` with the singular values on
+/// the diagonal of `S` in descending order.
+#[inline(always)]
+pub fn svd(v: &Data) -> Result<Data, Error> {
+	let (u, sigmas, v) = singular_values(matrix(v)?.clone())?;
+
+	let s = Matrix::from_fn(sigmas.len(), sigmas.len(), |i, j| {
+		if i == j {
+			Complex::real(sigmas[i])
+		} else {
+			Complex::ZERO
+		}
+	});
+
+	Ok(Data::List(vec![to_data(u), to_data(s), to_data(v)]))
+}
+
+/// Frobenius norm of a matrix, which is the euclidean norm for vectors.
+#[inline(always)]
+pub fn frobenius(v: &Data) -> Result<Data, Error> {
+	let v = vector(v)?;
+
+	Ok(Complex::real(length(matrix(&v)?.elements())).to_data())
+}
+
+/// The `p` norm of a vector or the norm of a matrix induced by the `1`, `2` and
+/// maximum vector norms.
+#[inline(always)]
+pub fn norm(v: &Data, p: &Data) -> Result<Data, Error> {
+	let v = vector(v)?;
+
+	let a = matrix(&v)?;
+
+	// The identifier `inf` stands for the largest modulus, or the largest row sum
+	// of a matrix.
+	let p = match p {
+		Data::Number(p, im) if im.is_zero() && *p >= Decimal::ONE => Some(*p),
+		Data::Ident(name) if name == "inf" => None,
+		Data::Number(..) => {
+			return Err(Error::LogicError(
+				"norms are only defined for real p of at least 1 or inf".to_string(),
+			));
+		}
+		_ => return Err(TypeError::new(DataType::Number, p.ty(), 0..0).to_error()),
+	};
+
+	let (rows, cols) = v.shape();
+
+	let value = if rows == 1 || cols == 1 {
+		let moduli = a.elements().iter().map(|z| z.modulus());
+
+		match p {
+			None => moduli.max().unwrap_or_default(),
+			Some(p) if p == Decimal::ONE => moduli.sum(),
+			Some(p) if p == Decimal::TWO => length(a.elements()),
+			Some(p) => moduli
+				.map(|x| x.powd(p))
+				.sum::<Decimal>()
+				.powd(Decimal::ONE / p),
+		}
+	} else {
+		match p {
+			None => a
+				.iter_rows()
+				.map(|row| row.iter().map(|z| z.modulus()).sum::<Decimal>())
+				.max()
+				.unwrap_or_default(),
+			Some(p) if p == Decimal::ONE => (0..cols)
+				.map(|j| (0..rows).map(|i| a[(i, j)].modulus()).sum::<Decimal>())
+				.max()
+				.unwrap_or_default(),
+			Some(p) if p == Decimal::TWO => singular_values(a.clone())?
+				.1
+				.first()
+				.copied()
+				.unwrap_or_default(),
+			Some(_) => {
+				return Err(Error::LogicError(
+					"matrix norms are only defined for p equal to 1, 2 or inf".to_string(),
+				));
+			}
+		}
+	};
+
+	Ok(Complex::real(value).to_data())
+}
+
+/// Lists are treated as row vectors by the norms.
+#[inline(always)]
+fn vector(v: &Data) -> Result<Data, Error> {
+	Ok(match v {
+		Data::List(list) => Data::Matrix(Matrix::new(
+			1,
+			list.len(),
+			list
+				.iter()
+				.map(Complex::from_data)
+				.collect::<Result<_, _>>()?,
+		)),
+		_ => v.clone(),
+	})
+}
+
+/// Ratio of the largest to the smallest singular value.
+#[inline(always)]
+pub fn cond(v: &Data) -> Result<Data, Error> {
+	let a = matrix(v)?;
+
+	let sigmas = singular_values(a.clone())?.1;
+
+	match (sigmas.first(), sigmas.last()) {
+		(Some(&largest), Some(&smallest)) if smallest > negligible(largest, a.rows().max(a.cols())) => {
+			Ok(Complex::real(largest / smallest).to_data())
+		}
+		_ => Err(Error::LogicError(
+			"matrix is singular so its condition number is infinite".to_string(),
+		)),
+	}
+}
+
+/// Moore-Penrose pseudo inverse, `V * S^+ * U^H`.
+#[inline(always)]
+pub fn pinv(v: &Data) -> Result<Data, Error> {
+	let (m, n) = v.shape();
+
+	let (u, sigmas, v) = singular_values(matrix(v)?.clone())?;
+
+	let tolerance = negligible(sigmas.first().copied().unwrap_or_default(), m.max(n));
+
+	let s = Matrix::from_fn(sigmas.len(), sigmas.len(), |i, j| {
+		if i == j && sigmas[i] > tolerance {
+			Complex::real(Decimal::ONE / sigmas[i])
+		} else {
+			Complex::ZERO
+		}
+	});
+
+	Ok(to_data(v.matmul(&s).matmul(&conjugate_transpose(&u))))
+}
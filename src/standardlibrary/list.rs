@@ -0,0 +1,178 @@
+use std::cmp::Ordering;
+
+use rust_decimal::{Decimal, prelude::FromPrimitive};
+
+use crate::{
+	errors::{Error, TypeError, ValueError},
+	standardlibrary::operators::{add, mul},
+	types::{Data, DataType},
+};
+
+#[inline(always)]
+pub fn len(a: &Data) -> Result<Data, Error> {
+	let Data::List(list) = a else {
+		return Err(TypeError::new(DataType::List, a.ty(), 0..0).to_error());
+	};
+
+	Ok(Data::new_real(Decimal::from_usize(list.len()).unwrap()))
+}
+
+#[inline(always)]
+pub fn push(a: &Data, b: &Data) -> Result<Data, Error> {
+	let Data::List(list) = a else {
+		return Err(TypeError::new(DataType::List, a.ty(), 0..0).to_error());
+	};
+
+	let mut list = list.clone();
+
+	list.push(b.clone());
+
+	Ok(Data::List(list))
+}
+
+#[inline(always)]
+pub fn concat(a: &Data, b: &Data) -> Result<Data, Error> {
+	let (Data::List(a), Data::List(b)) = (a, b) else {
+		let other = if matches!(a, Data::List(..)) { b } else { a };
+
+		return Err(TypeError::new(DataType::List, other.ty(), 0..0).to_error());
+	};
+
+	Ok(Data::List([a.clone(), b.clone()].concat()))
+}
+
+/// Numbers from `a` to `b` (inclusive when reached exactly) separated by `step`.
+#[inline(always)]
+pub fn range(a: &Data, b: &Data, step: &Data) -> Result<Data, Error> {
+	let (a, b, step) = (a.to_real(), b.to_real(), step.to_real());
+
+	if step == Decimal::ZERO {
+		return Err(
+			ValueError::new(
+				"step of range can not be zero".to_string(),
+				"give a positive step to count up or a negative one to count down",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	let mut list = vec![];
+	let mut n = a;
+
+	while (step > Decimal::ZERO && n <= b) || (step < Decimal::ZERO && n >= b) {
+		list.push(Data::new_real(n));
+		n += step;
+	}
+
+	Ok(Data::List(list))
+}
+
+/// `n` evenly spaced numbers from `a` to `b`, both inclusive.
+#[inline(always)]
+pub fn linspace(a: &Data, b: &Data, n: &Data) -> Result<Data, Error> {
+	let (a, b, n) = (a.to_real(), b.to_real(), n.to_real());
+
+	if n < Decimal::ONE || !n.fract().is_zero() {
+		return Err(
+			ValueError::new(
+				format!("`{n}` is not a number of points"),
+				"the number of points in linspace must be a positive integer",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	if n == Decimal::ONE {
+		return Ok(Data::List(vec![Data::new_real(a)]));
+	}
+
+	let step = (b - a) / (n - Decimal::ONE);
+
+	Ok(Data::List(
+		(0..n.to_string().parse::<i64>().unwrap())
+			.map(|i| Data::new_real(a + step * Decimal::from_i64(i).unwrap()))
+			.collect(),
+	))
+}
+
+#[inline(always)]
+pub fn reverse(a: &Data) -> Result<Data, Error> {
+	let Data::List(list) = a else {
+		return Err(TypeError::new(DataType::List, a.ty(), 0..0).to_error());
+	};
+
+	Ok(Data::List(list.iter().rev().cloned().collect()))
+}
+
+#[inline(always)]
+pub fn sort(a: &Data) -> Result<Data, Error> {
+	let Data::List(list) = a else {
+		return Err(TypeError::new(DataType::List, a.ty(), 0..0).to_error());
+	};
+
+	if list
+		.iter()
+		.any(|n| !matches!(n, Data::Number(_, b) if b.is_zero()))
+	{
+		return Err(
+			ValueError::new(
+				"list can not be sorted".to_string(),
+				"only lists of real numbers can be sorted",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	let mut list = list.clone();
+
+	list.sort_by(|a, b| {
+		a.to_real()
+			.partial_cmp(&b.to_real())
+			.unwrap_or(Ordering::Equal)
+	});
+
+	Ok(Data::List(list))
+}
+
+/// Removes repeated elements keeping the first occurrence of each.
+#[inline(always)]
+pub fn unique(a: &Data) -> Result<Data, Error> {
+	let Data::List(list) = a else {
+		return Err(TypeError::new(DataType::List, a.ty(), 0..0).to_error());
+	};
+
+	let mut unique: Vec<Data> = vec![];
+
+	for element in list {
+		if !unique.contains(element) {
+			unique.push(element.clone());
+		}
+	}
+
+	Ok(Data::List(unique))
+}
+
+#[inline(always)]
+pub fn sum(a: &Data) -> Result<Data, Error> {
+	let Data::List(list) = a else {
+		return Err(TypeError::new(DataType::List, a.ty(), 0..0).to_error());
+	};
+
+	list
+		.iter()
+		.try_fold(Data::new_zero(), |acc, n| add(&acc, n))
+}
+
+#[inline(always)]
+pub fn prod(a: &Data) -> Result<Data, Error> {
+	let Data::List(list) = a else {
+		return Err(TypeError::new(DataType::List, a.ty(), 0..0).to_error());
+	};
+
+	list
+		.iter()
+		.try_fold(Data::new_real(Decimal::ONE), |acc, n| mul(&acc, n))
+}
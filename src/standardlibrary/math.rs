@@ -3,58 +3,74 @@ use plotters::chart::ChartBuilder;
 use plotters::drawing::IntoDrawingArea;
 use plotters::element::PathElement;
 use plotters::series::LineSeries;
-use plotters::style::{Color, IntoFont, full_palette::*};
+use plotters::style::{Color, IntoFont, RGBColor, full_palette::*};
 use rust_decimal::prelude::FromPrimitive;
 use rust_decimal::{Decimal, MathematicalOps, dec, prelude::*};
 use std::f32;
+use std::ops::Range;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::errors::Error;
+use crate::errors::{Error, ShapeError, TypeError, ValueError};
+use crate::expr::Expression;
 use crate::interpreter::{Function, InterpreterContext, Variable};
+use crate::matrix::{Complex, Matrix};
+use crate::series::Limit;
+use crate::simplify::ratio;
+use crate::standardlibrary::calculus::{point, symbolic};
+use crate::standardlibrary::linalg::determinant;
 use crate::standardlibrary::operators::{add, div, mul, sub};
-use crate::types::Data;
+use crate::types::{Data, DataType};
 
 #[inline(always)]
-pub fn abs(a: &Data) -> Data {
-	match a {
+pub fn abs(a: &Data) -> Result<Data, Error> {
+	Ok(match a {
 		Data::Number(a, b) => Data::new_real((a * a + b * b).sqrt().unwrap()),
-		Data::Matrix(..) => determinant(a),
-		_ => unimplemented!(),
-	}
+		Data::Measured(value, sigma) => Data::new_measured(value.abs(), *sigma),
+		Data::Matrix(..) => determinant(a)?,
+		Data::Sparse(matrix) => determinant(&Data::Matrix(matrix.to_dense()))?,
+		_ => return Err(TypeError::new(DataType::Number, a.ty(), 0..0).to_error()),
+	})
+}
+
+/// Sign of a real number, or the number scaled to a modulus of one when complex
+#[inline(always)]
+pub fn sign(a: &Data) -> Result<Data, Error> {
+	let z = Complex::from_data(a)?;
+
+	Ok(if z.is_real() {
+		Data::new_real(z.re.signum())
+	} else {
+		Data::from(z / Complex::real(z.modulus()))
+	})
 }
 
 #[inline(always)]
-pub fn round(a: &Data) -> Data {
+pub fn round(a: &Data) -> Result<Data, Error> {
 	match a {
-		Data::Number(x, y) => Data::Number(x.round(), y.round()),
-		_ => unimplemented!(),
+		Data::Number(x, y) => Ok(Data::Number(x.round(), y.round())),
+		_ => Err(TypeError::new(DataType::Number, a.ty(), 0..0).to_error()),
 	}
 }
 
 #[inline(always)]
-pub fn ceil(a: &Data) -> Data {
+pub fn ceil(a: &Data) -> Result<Data, Error> {
 	match a {
-		Data::Number(x, y) => Data::Number(x.ceil(), y.ceil()),
-		_ => unimplemented!(),
+		Data::Number(x, y) => Ok(Data::Number(x.ceil(), y.ceil())),
+		_ => Err(TypeError::new(DataType::Number, a.ty(), 0..0).to_error()),
 	}
 }
 
 #[inline(always)]
-pub fn floor(a: &Data) -> Data {
+pub fn floor(a: &Data) -> Result<Data, Error> {
 	match a {
-		Data::Number(x, y) => Data::Number(x.floor(), y.floor()),
-		_ => unimplemented!(),
+		Data::Number(x, y) => Ok(Data::Number(x.floor(), y.floor())),
+		_ => Err(TypeError::new(DataType::Number, a.ty(), 0..0).to_error()),
 	}
 }
 
 #[inline(always)]
-pub fn exp(a: &Data) -> Data {
-	let x = a.to_real();
-
-	Data::Number(
-		x.exp_with_tolerance(Decimal::from_parts(2, 0, 0, false, 28)),
-		Decimal::ZERO,
-	)
+pub fn exp(a: &Data) -> Result<Data, Error> {
+	Ok(Data::from(Complex::from_data(a)?.exp()?))
 }
 
 #[inline(always)]
@@ -68,76 +84,57 @@ pub fn ln(a: &Data) -> Data {
 }
 
 #[inline(always)]
-pub fn log10(a: &Data) -> Data {
+pub fn log10(a: &Data) -> Result<Data, Error> {
 	match a {
 		Data::Number(..) => div(&ln(a), &ln(&Data::new_real(Decimal::TEN))),
-		_ => unimplemented!(),
+		_ => Err(TypeError::new(DataType::Number, a.ty(), 0..0).to_error()),
 	}
 }
 
 #[inline(always)]
-pub fn log(a: &Data, b: &Data) -> Data {
-	match a {
-		Data::Number(..) => div(&ln(a), &ln(b)),
-		_ => unimplemented!(),
+pub fn log(a: &Data, b: &Data) -> Result<Data, Error> {
+	match (a, b) {
+		(Data::Number(..), Data::Number(..)) => div(&ln(a), &ln(b)),
+		(Data::Number(..), _) => Err(TypeError::new(DataType::Number, b.ty(), 0..0).to_error()),
+		_ => Err(TypeError::new(DataType::Number, a.ty(), 0..0).to_error()),
 	}
 }
 
 #[inline(always)]
-pub fn sin(a: &Data) -> Data {
-	let x = a.to_real();
-	let y = a.to_img();
-
-	if y == Decimal::ZERO {
-		return Data::new_real((dec!(3.0) * Decimal::PI).sin());
-	}
-
-	let p = &mul(&Data::new_real(x.sin()), &cosh(&Data::new_real(y)));
-	let q = &mul(&Data::new_real(x.cos()), &sinh(&Data::new_real(y)));
-
-	Data::Number(p.to_real(), q.to_real())
+pub fn sin(a: &Data) -> Result<Data, Error> {
+	Ok(Data::from(Complex::from_data(a)?.sin()?))
 }
 
 #[inline(always)]
-pub fn sinh(a: &Data) -> Data {
+pub fn sinh(a: &Data) -> Result<Data, Error> {
 	div(
 		&sub(
-			&exp(a),
-			&exp(&mul(a, &Data::new_real(Decimal::NEGATIVE_ONE))),
-		),
+			&exp(a)?,
+			&exp(&mul(a, &Data::new_real(Decimal::NEGATIVE_ONE))?)?,
+		)?,
 		&Data::new_real(Decimal::TWO),
 	)
 }
 
 #[inline(always)]
-pub fn cos(a: &Data) -> Data {
-	let x = a.to_real();
-	let y = a.to_img();
-
-	if y == Decimal::ZERO {
-		return Data::new_real(x.cos());
-	}
-
-	let p = &mul(&Data::new_real(x.cos()), &cosh(&Data::new_real(y)));
-	let q = &mul(&Data::new_real(-x.sin()), &sinh(&Data::new_real(y)));
-
-	Data::Number(p.to_real(), q.to_real())
+pub fn cos(a: &Data) -> Result<Data, Error> {
+	Ok(Data::from(Complex::from_data(a)?.cos()?))
 }
 
 #[inline(always)]
-pub fn cosh(a: &Data) -> Data {
+pub fn cosh(a: &Data) -> Result<Data, Error> {
 	div(
 		&add(
-			&exp(a),
-			&exp(&mul(a, &Data::new_real(Decimal::NEGATIVE_ONE))),
-		),
+			&exp(a)?,
+			&exp(&mul(a, &Data::new_real(Decimal::NEGATIVE_ONE))?)?,
+		)?,
 		&Data::new_real(Decimal::TWO),
 	)
 }
 
 #[inline(always)]
-pub fn tan(a: &Data) -> Data {
-	div(&sin(a), &cos(a))
+pub fn tan(a: &Data) -> Result<Data, Error> {
+	div(&sin(a)?, &cos(a)?)
 }
 
 /* atan, atan2 and i macro implementations from libm (rust) */
@@ -424,22 +421,18 @@ pub fn atan(x: &Data) -> Data {
 
 #[inline(always)]
 pub fn sqrt(a: &Data) -> Data {
-	let b = a.to_img();
-	let a = a.to_real();
+	let z = Complex::new(a.to_real(), a.to_img()).sqrt();
 
-	let r = (a * a + b * b).sqrt().unwrap();
-
-	let zr = ((a + r) * (a + r) + b * b).sqrt().unwrap();
-
-	Data::Number(r.sqrt().unwrap() * (a + r) / zr, r.sqrt().unwrap() * b / zr)
+	Data::Number(z.re.normalize(), z.im.normalize())
 }
 
 #[inline(always)]
-pub fn nrt(a: &Data, b: &Data) -> Data {
+pub fn nrt(a: &Data, b: &Data) -> Result<Data, Error> {
 	if let Data::Number(x, y) = a
-		&& let Data::Number(r, _) = abs(a)
 		&& let Data::Number(b, _) = b
 	{
+		let r = (x * x + y * y).sqrt().unwrap();
+
 		let z = r.powd(Decimal::ONE / b);
 
 		let Data::Number(theta, _) = atan2(
@@ -451,318 +444,1718 @@ pub fn nrt(a: &Data, b: &Data) -> Data {
 
 		let theta = theta / b;
 
-		return Data::Number(z * theta.cos(), z * theta.sin());
+		return Ok(Data::Number(z * theta.cos(), z * theta.sin()));
 	}
 
-	unimplemented!()
+	let wrong = if matches!(a, Data::Number(..)) { b } else { a };
+
+	Err(TypeError::new(DataType::Number, wrong.ty(), 0..0).to_error())
 }
 
-#[inline(always)]
-pub fn determinant(v: &Data) -> Data {
-	match v {
-		Data::Matrix(matrix) => {
-			let cols = matrix.len();
+/// Relative size below which the terms of a series no longer change its sum
+const EPSILON: Decimal = Decimal::from_parts(1, 0, 0, false, 27);
 
-			for row in matrix {
-				if row.len() != cols {
-					panic!("matrix should be square for determinant");
-				}
-			}
+const ITERATIONS: usize = 10_000;
 
-			if cols == 1 {
-				matrix[0][0].clone()
-			} else if cols == 2 && matrix[0].len() == 2 && matrix[1].len() == 2 {
-				sub(
-					&mul(&matrix[0][0], &matrix[1][1]),
-					&mul(&matrix[0][1], &matrix[1][0]),
-				)
-			} else {
-				let mut delta = Data::new_zero();
+const EULER_GAMMA: Decimal = dec!(0.5772156649015328606065120901);
 
-				for (i, n) in matrix[0].iter().enumerate() {
-					let mut minor_matrix = matrix.clone();
+pub(super) const LN_SQRT_TAU: Decimal = dec!(0.9189385332046727417803297365);
 
-					minor_matrix.remove(0);
+const FRAC_2_SQRT_PI: Decimal = dec!(1.1283791670955125738961589031);
 
-					for row in &mut minor_matrix {
-						row.remove(i);
-					}
+/// `e^x` to full precision, which is simply zero once it is too small to
+/// represent. The series is only summed for `|x| <= 1` and squared back up, as
+/// its terms overflow for larger arguments.
+#[inline(always)]
+pub(super) fn exponential(x: Decimal) -> Result<Decimal, Error> {
+	if x < Decimal::from(-64) {
+		return Ok(Decimal::ZERO);
+	}
 
-					delta = add(
-						&delta,
-						&mul(
-							&mul(
-								n,
-								&Data::new_real([Decimal::ONE, Decimal::NEGATIVE_ONE][i % 2]),
-							),
-							&determinant(&Data::Matrix(minor_matrix)),
-						),
-					);
-				}
+	let (mut reduced, mut halvings) = (x, 0);
 
-				delta
-			}
-		}
-		_ => panic!("expected matrix for determinant"),
+	while reduced.abs() > Decimal::ONE {
+		reduced /= Decimal::TWO;
+		halvings += 1;
 	}
+
+	(0..halvings).try_fold(
+		reduced.exp_with_tolerance(Decimal::from_parts(1, 0, 0, false, 28)),
+		|y, _| {
+			y.checked_mul(y).ok_or(Error::LogicError(
+				"result is too large to represent".to_string(),
+			))
+		},
+	)
 }
 
+/// Sine and cosine to full precision, from series on the argument reduced to
+/// within an eighth of a turn of a multiple of a quarter turn.
 #[inline(always)]
-pub fn transpose(v: &Data) -> Data {
-	match v {
-		Data::Matrix(matrix) => {
-			let cols = matrix.len();
+pub(super) fn sin_cos(x: Decimal) -> (Decimal, Decimal) {
+	let quarter = (x / Decimal::HALF_PI).round();
+	let r = x - quarter * Decimal::HALF_PI;
 
-			for row in matrix {
-				if row.len() != cols {
-					panic!("matrix should be square for determinant");
-				}
-			}
+	let (mut sin, mut cos) = (r, Decimal::ONE);
+	let (mut odd, mut even) = (r, Decimal::ONE);
 
-			let mut iters: Vec<_> = matrix.iter().map(|n| n.iter()).collect();
+	for n in 1..ITERATIONS {
+		let n = Decimal::from(2 * n);
 
-			Data::Matrix(
-				(0..cols)
-					.map(|_| {
-						iters
-							.iter_mut()
-							.map(|n| n.next().unwrap().clone())
-							.collect::<Vec<Data>>()
-					})
-					.collect(),
-			)
+		even *= -r * r / (n * (n - Decimal::ONE));
+		odd *= -r * r / (n * (n + Decimal::ONE));
+
+		cos += even;
+		sin += odd;
+
+		if even.is_zero() && odd.is_zero() {
+			break;
 		}
-		_ => panic!("expected matrix for transposing"),
+	}
+
+	match (quarter % Decimal::from(4) + Decimal::from(4)) % Decimal::from(4) {
+		q if q.is_zero() => (sin, cos),
+		q if q == Decimal::ONE => (cos, -sin),
+		q if q == Decimal::TWO => (-sin, -cos),
+		_ => (-cos, sin),
 	}
 }
 
-#[inline(always)]
-pub fn adj(v: &Data) -> Data {
-	match v {
-		Data::Matrix(matrix) => {
-			let cols = matrix.len();
-			let mut adj_matrix: Vec<Vec<Data>> = vec![];
+impl Complex {
+	/// Principal argument, from an arctangent of at most one in size which is
+	/// halved three times so its series converges quickly.
+	#[inline(always)]
+	pub(super) fn arg(self) -> Decimal {
+		if self.im.is_zero() {
+			return if self.re.is_sign_negative() {
+				Decimal::PI
+			} else {
+				Decimal::ZERO
+			};
+		}
 
-			for row in matrix {
-				if row.len() != cols {
-					panic!("matrix should be square for adj");
-				}
-				adj_matrix.push((0..cols).map(|_| Data::new_zero()).collect());
+		let atan = |mut t: Decimal| {
+			for _ in 0..3 {
+				t /= Decimal::ONE + (Decimal::ONE + t * t).sqrt().unwrap();
 			}
 
-			for i in 0..matrix.len() {
-				for j in 0..matrix[i].len() {
-					let mut minor_matrix = matrix.clone();
+			let (mut term, mut sum) = (t, t);
 
-					minor_matrix.remove(i);
-
-					for row in &mut minor_matrix {
-						row.remove(j);
-					}
+			for k in 1..ITERATIONS {
+				term *= -t * t;
 
-					let delta = determinant(&Data::Matrix(minor_matrix));
+				let contribution = term / Decimal::from(2 * k + 1);
+				sum += contribution;
 
-					adj_matrix[j][i] = mul(
-						&Data::new_real([Decimal::ONE, Decimal::NEGATIVE_ONE][(i + j) % 2]),
-						&delta,
-					);
+				if contribution.abs() <= EPSILON * sum.abs() {
+					break;
 				}
 			}
 
-			Data::Matrix(adj_matrix)
+			sum * Decimal::from(8)
+		};
+
+		// Half angle formulas, picking whichever avoids cancellation
+		if self.re.is_sign_positive() {
+			Decimal::TWO * atan(self.im / (self.modulus() + self.re))
+		} else if self.im.is_sign_negative() {
+			-Decimal::PI - Decimal::TWO * atan(self.im / (self.modulus() - self.re))
+		} else {
+			Decimal::PI - Decimal::TWO * atan(self.im / (self.modulus() - self.re))
 		}
-		_ => panic!("expected matrix for adj"),
 	}
-}
 
-#[inline(always)]
-pub fn inverse(v: &Data) -> Data {
-	match v {
-		t @ Data::Matrix(matrix) => {
-			let cols = matrix.len();
+	/// Principal natural logarithm
+	#[inline(always)]
+	pub(super) fn ln(self) -> Self {
+		if self.is_real() && self.re.is_sign_positive() {
+			return Self::real(self.re.ln());
+		}
 
-			for row in matrix {
-				if row.len() != cols {
-					panic!("matrix should be square for inverse");
-				}
-			}
-			let det = &determinant(t);
-			div(&adj(&t), det)
+		Self::new(self.modulus().ln(), self.arg())
+	}
+
+	#[inline(always)]
+	pub(super) fn exp(self) -> Result<Self, Error> {
+		let magnitude = exponential(self.re)?;
+
+		if self.is_real() {
+			return Ok(Self::real(magnitude));
 		}
-		_ => panic!("expected matrix for inverse"),
+
+		let (sin, cos) = sin_cos(self.im);
+
+		Ok(Self::new(magnitude * cos, magnitude * sin))
+	}
+
+	/// Hyperbolic cosine and sine of the imaginary part
+	#[inline(always)]
+	fn hyperbolic(self) -> Result<(Decimal, Decimal), Error> {
+		let (up, down) = (exponential(self.im)?, exponential(-self.im)?);
+
+		Ok(((up + down) / Decimal::TWO, (up - down) / Decimal::TWO))
+	}
+
+	#[inline(always)]
+	pub(super) fn sin(self) -> Result<Self, Error> {
+		let (cosh, sinh) = self.hyperbolic()?;
+		let (sin, cos) = sin_cos(self.re);
+
+		Ok(Self::new(sin * cosh, cos * sinh))
+	}
+
+	#[inline(always)]
+	pub(super) fn cos(self) -> Result<Self, Error> {
+		let (cosh, sinh) = self.hyperbolic()?;
+		let (sin, cos) = sin_cos(self.re);
+
+		Ok(Self::new(cos * cosh, -sin * sinh))
+	}
+
+	/// Whether the number is zero or a negative integer, where the gamma function
+	/// and those built on it have poles
+	#[inline(always)]
+	fn is_pole(self) -> bool {
+		self.is_real() && self.re <= Decimal::ZERO && self.re.fract().is_zero()
 	}
 }
 
+/// Reads a number given to a special function, refusing the poles of the
+/// function named by `f`
 #[inline(always)]
-pub fn graph<'a, 'b>(f: &Data, ctx: &'a mut InterpreterContext<'b>) -> Result<Data, Error>
-where
-	'b: 'a,
-{
-	if let Data::Ident(f) = f
-		&& let Function::UserDefined(g) = ctx.1.get(f).unwrap().clone()
-	{
-		let start = SystemTime::now();
-		let duration = start.duration_since(UNIX_EPOCH).unwrap().as_millis();
-		let name = format!("graph-output-{duration}.png");
+fn special_argument(a: &Data, f: &str) -> Result<Complex, Error> {
+	let z = Complex::from_data(a)?;
+
+	if z.is_pole() {
+		return Err(
+			ValueError::new(
+				format!("{f} has a pole at {}", z.re),
+				"the gamma function and those built on it have poles at zero and the negative integers",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
 
-		let root = BitMapBackend::new(&name, (640, 480)).into_drawing_area();
+	Ok(z)
+}
 
-		root.fill(&WHITE).unwrap();
+/// Principal branch of the logarithm of the gamma function, from Stirling's
+/// series once the argument has been shifted up far enough for it to converge.
+/// The logarithms of the shifts are summed one at a time, which keeps the
+/// branch continuous.
+#[inline(always)]
+pub(super) fn ln_gamma(mut z: Complex) -> Complex {
+	let mut shift = Complex::ZERO;
 
-		let mut chart = ChartBuilder::on(&root)
-			.caption("Graph output", ("sans-serif", 20).into_font())
-			.margin(5)
-			.x_label_area_size(30)
-			.y_label_area_size(30)
-			.build_cartesian_2d(-10f32..10f32, -10f32..10f32)
-			.unwrap();
+	while z.re < Decimal::from(20) {
+		shift = shift + z.ln();
+		z = z + Complex::ONE;
+	}
 
-		chart.configure_mesh().draw().unwrap();
+	let inverse = Complex::ONE / z;
+	let square = inverse * inverse;
+
+	// Coefficients B₂ₖ / (2k (2k - 1)) of the series
+	let series = [
+		(dec!(1), dec!(12)),
+		(dec!(-1), dec!(360)),
+		(dec!(1), dec!(1260)),
+		(dec!(-1), dec!(1680)),
+		(dec!(1), dec!(1188)),
+		(dec!(-691), dec!(360360)),
+		(dec!(1), dec!(156)),
+		(dec!(-3617), dec!(122400)),
+	]
+	.iter()
+	.rev()
+	.fold(Complex::ZERO, |acc, (numerator, denominator)| {
+		acc * square + Complex::real(numerator / denominator)
+	}) * inverse;
+
+	(z - Complex::real(Decimal::new(5, 1))) * z.ln() - z + Complex::real(LN_SQRT_TAU) + series - shift
+}
 
-		let style = &GREY_A700;
-		let code = &g.code;
+/// Whether the gamma function is negative at a real number which is not a pole
+#[inline(always)]
+fn gamma_negative(x: Decimal) -> bool {
+	x.is_sign_negative() && x.floor() % Decimal::TWO != Decimal::ZERO
+}
 
-		let mut values = vec![];
+pub fn gamma(a: &Data) -> Result<Data, Error> {
+	let z = special_argument(a, "gamma")?;
 
-		for x in -500..=500 {
-			let x = x as f64 / 50.0;
+	if !z.is_real() {
+		return Ok(Data::from(ln_gamma(z).exp()?.clean()));
+	}
 
-			ctx.0.insert(
-				"x".to_string(),
-				Variable::new(Data::new_real(Decimal::from_f64(x).unwrap()), false),
-			);
+	// Factorials are found exactly while they fit
+	if z.re.fract().is_zero() && z.re <= Decimal::from(28) {
+		let factorial = (1..z.re.to_u64().unwrap()).map(Decimal::from).product();
 
-			let data = match code.clone().evaluate(ctx, g.range.clone()) {
-				Ok(data) => match data {
-					Data::Number(a, _) => a.to_f32().unwrap(),
-					_ => {
-						return Err(Error::LogicError(
-							"expected number for plotting".to_string(),
-						));
-					}
-				},
-				Err(..) => f32::NAN,
-			};
+		return Ok(Data::new_real(factorial));
+	}
 
-			values.push((x as f32, data));
+	let magnitude = exponential(ln_gamma(z).re)?;
+
+	Ok(Data::from(
+		Complex::real(if gamma_negative(z.re) {
+			-magnitude
+		} else {
+			magnitude
+		})
+		.clean(),
+	))
+}
+
+/// Logarithm of the absolute value of the gamma function for real numbers, and
+/// the principal branch of its logarithm for complex ones.
+pub fn lgamma(a: &Data) -> Result<Data, Error> {
+	let z = special_argument(a, "lgamma")?;
+	let result = ln_gamma(z);
+
+	Ok(Data::from(
+		if z.is_real() {
+			Complex::real(result.re)
+		} else {
+			result
 		}
+		.clean(),
+	))
+}
 
-		chart
-			.draw_series(LineSeries::new(values, &style))
-			.unwrap()
-			.label("Function")
-			.legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], *style));
+/// `Γ(a) Γ(b) / Γ(a + b)`
+pub fn beta(a: &Data, b: &Data) -> Result<Data, Error> {
+	let (a, b) = (special_argument(a, "beta")?, special_argument(b, "beta")?);
 
-		chart
-			.draw_series(LineSeries::new((-10..=10).map(|x| (x as f32, 0.0)), &BLACK))
-			.unwrap();
+	// The denominator having a pole makes the whole fraction vanish
+	if (a + b).is_pole() {
+		return Ok(Data::new_zero());
+	}
 
-		chart
-			.draw_series(LineSeries::new((-10..=10).map(|x| (0.0, x as f32)), &BLACK))
-			.unwrap();
+	let exponent = ln_gamma(a) + ln_gamma(b) - ln_gamma(a + b);
 
-		chart
-			.configure_series_labels()
-			.background_style(WHITE.mix(0.8))
-			.border_style(BLACK)
-			.draw()
-			.unwrap();
+	if !(a.is_real() && b.is_real()) {
+		return Ok(Data::from(exponent.exp()?.clean()));
+	}
 
-		root.present().unwrap();
+	let magnitude = exponential(exponent.re)?;
 
-		return Ok(Data::new_zero());
+	let negative = [a.re, b.re, a.re + b.re]
+		.into_iter()
+		.filter(|&x| gamma_negative(x))
+		.count()
+		% 2
+		== 1;
+
+	Ok(Data::from(
+		Complex::real(if negative { -magnitude } else { magnitude }).clean(),
+	))
+}
+
+/// Logarithmic derivative of the gamma function, from its asymptotic series
+/// once the argument has been shifted up far enough.
+pub fn digamma(a: &Data) -> Result<Data, Error> {
+	let mut z = special_argument(a, "digamma")?;
+	let mut shift = Complex::ZERO;
+
+	while z.re < Decimal::from(20) {
+		shift = shift + Complex::ONE / z;
+		z = z + Complex::ONE;
 	}
-	// TODO: error handle this
-	panic!("expected indentifier")
+
+	let inverse = Complex::ONE / z;
+	let square = inverse * inverse;
+
+	// Coefficients B₂ₖ / 2k of the series
+	let series = [
+		(dec!(1), dec!(12)),
+		(dec!(-1), dec!(120)),
+		(dec!(1), dec!(252)),
+		(dec!(-1), dec!(240)),
+		(dec!(1), dec!(132)),
+		(dec!(-691), dec!(32760)),
+		(dec!(1), dec!(12)),
+		(dec!(-3617), dec!(8160)),
+	]
+	.iter()
+	.rev()
+	.fold(Complex::ZERO, |acc, (numerator, denominator)| {
+		acc * square + Complex::real(numerator / denominator)
+	}) * square;
+
+	Ok(Data::from(
+		(z.ln() - inverse / Complex::TWO - series - shift).clean(),
+	))
 }
 
+/// Maclaurin series of the error function, which only loses digits to
+/// cancellation once the real part of the argument grows
 #[inline(always)]
-pub fn sum<'a, 'b>(
-	f: &Data,
-	a: &Data,
-	b: &Data,
-	ctx: &'a mut InterpreterContext<'b>,
-) -> Result<Data, Error>
-where
-	'b: 'a,
-{
-	let Data::Ident(g) = f else { unreachable!() };
+fn erf_series(z: Complex) -> Result<Complex, Error> {
+	if z.modulus() > Decimal::from(7) {
+		return Err(
+			ValueError::new(
+				"result is too large to represent".to_string(),
+				"the error function grows too quickly off the real line to evaluate this far out",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
 
-	let func = ctx.1.get(g).unwrap().clone();
+	let (mut term, mut sum) = (z, z);
+	let square = -(z * z);
 
-	let mut sum = Data::new_zero();
+	for n in 1..ITERATIONS {
+		term = term * square / Complex::real(Decimal::from(n));
 
-	let a = a.to_real();
-	let b = b.to_real();
+		let contribution = term / Complex::real(Decimal::from(2 * n + 1));
+		sum = sum + contribution;
 
-	for i in a.to_i64().unwrap()..=b.to_i64().unwrap() {
-		sum = add(
-			&sum,
-			&func.execute(ctx, vec![Data::new_real(Decimal::from_i64(i).unwrap())])?,
-		)
+		if contribution.magnitude() <= EPSILON * sum.magnitude() {
+			break;
+		}
 	}
 
-	Ok(sum)
+	Ok(sum * Complex::real(FRAC_2_SQRT_PI))
 }
 
+/// Continued fraction of the complementary error function, which converges
+/// quickly once the real part of the argument is large enough
 #[inline(always)]
-pub fn prod<'a, 'b>(
-	f: &Data,
-	a: &Data,
-	b: &Data,
-	ctx: &'a mut InterpreterContext<'b>,
-) -> Result<Data, Error>
-where
-	'b: 'a,
-{
-	let Data::Ident(g) = f else { unreachable!() };
+fn erfc_fraction(z: Complex) -> Result<Complex, Error> {
+	let tiny = Complex::real(Decimal::from_parts(1, 0, 0, false, 25));
+	let guard = |v: Complex| if v.magnitude().is_zero() { tiny } else { v };
 
-	let func = ctx.1.get(g).unwrap().clone();
+	// Lentz's method on z + (1/2) / (z + 1 / (z + (3/2) / (z + ...)))
+	let mut fraction = z;
+	let (mut c, mut d) = (z, Complex::ZERO);
 
-	let mut prod = Data::new_real(Decimal::ONE);
+	for n in 1..ITERATIONS {
+		let a = Complex::real(Decimal::from(n) / Decimal::TWO);
 
-	let a = a.to_real();
-	let b = b.to_real();
+		d = Complex::ONE / guard(z + a * d);
+		c = guard(z + a / c);
 
-	for i in a.to_i64().unwrap()..=b.to_i64().unwrap() {
-		prod = mul(
-			&prod,
-			&func.execute(ctx, vec![Data::new_real(Decimal::from_i64(i).unwrap())])?,
-		)
+		let delta = c * d;
+		fraction = fraction * delta;
+
+		if (delta - Complex::ONE).magnitude() <= EPSILON {
+			break;
+		}
 	}
 
-	Ok(prod)
+	Ok((-(z * z)).exp()? / (fraction * Complex::real(Decimal::PI.sqrt().unwrap())))
+}
+
+pub fn erf(a: &Data) -> Result<Data, Error> {
+	let z = Complex::from_data(a)?;
+
+	let odd = |z: Complex| {
+		if z.re >= Decimal::TWO {
+			Ok(Complex::ONE - erfc_fraction(z)?)
+		} else {
+			erf_series(z)
+		}
+	};
+
+	Ok(Data::from(
+		if z.re.is_sign_negative() {
+			-odd(-z)?
+		} else {
+			odd(z)?
+		}
+		.clean(),
+	))
+}
+
+pub fn erfc(a: &Data) -> Result<Data, Error> {
+	let z = Complex::from_data(a)?;
+
+	let upper = |z: Complex| {
+		if z.re >= Decimal::TWO {
+			erfc_fraction(z)
+		} else {
+			Ok(Complex::ONE - erf_series(z)?)
+		}
+	};
+
+	Ok(Data::from(
+		if z.re.is_sign_negative() {
+			Complex::TWO - upper(-z)?
+		} else {
+			upper(z)?
+		}
+		.clean(),
+	))
 }
 
+/// Reads the order of a Bessel function, which must be an integer
 #[inline(always)]
-pub fn differentiate<'a, 'b>(
-	f: &Data,
-	a: &Data,
-	ctx: &'a mut InterpreterContext<'b>,
-) -> Result<Data, Error>
-where
-	'b: 'a,
-{
-	let Data::Ident(g) = f else { unreachable!() };
+fn bessel_order(n: &Data) -> Result<i64, Error> {
+	match n {
+		Data::Number(n, b) if b.is_zero() && n.fract().is_zero() => Ok(n.to_i64().unwrap()),
+		Data::Number(..) => Err(
+			ValueError::new(
+				format!("Bessel function of order {n}"),
+				"order of a Bessel function must be an integer",
+				0..0,
+			)
+			.to_error(),
+		),
+		_ => Err(TypeError::new(DataType::Number, n.ty(), 0..0).to_error()),
+	}
+}
 
-	ctx.1.get(g).unwrap().clone().differentiate(a, &[], ctx)
+/// Modulus above which Bessel functions are found from their asymptotic
+/// expansions, where the series would lose too many digits to cancellation
+const BESSEL_ASYMPTOTIC: Decimal = Decimal::from_parts(21, 0, 0, false, 0);
+
+/// Terms `(-1)ᵏ (z/2)²ᵏ⁺ⁿ / (k! (k + n)!)` of the series of `Jₙ(z)`
+#[inline(always)]
+fn bessel_terms(n: u64, z: Complex) -> Result<Vec<Complex>, Error> {
+	let half = z / Complex::TWO;
+
+	if z.modulus() > Decimal::from(60) {
+		return Err(
+			ValueError::new(
+				"Bessel functions of such a high order can not be evaluated this far out".to_string(),
+				"within the square of the order the series is summed, which only reaches a modulus of 60",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	let mut term = (1..=n).fold(Complex::ONE, |term, k| {
+		term * half / Complex::real(Decimal::from(k))
+	});
+
+	let mut terms = vec![term];
+	let mut sum = term;
+
+	for k in 1..ITERATIONS as u64 {
+		term = term * -(half * half) / Complex::real(Decimal::from(k * (k + n)));
+		sum = sum + term;
+
+		terms.push(term);
+
+		if term.magnitude() <= EPSILON * sum.magnitude() {
+			break;
+		}
+	}
+
+	Ok(terms)
 }
 
+/// Hankel's asymptotic expansions of `Jₙ(z)` and `Yₙ(z)` for large `z` with a
+/// positive real part.
 #[inline(always)]
-pub fn quadroot<'a, 'b>(f: &Data, ctx: &'a mut InterpreterContext<'b>) -> Result<Data, Error>
-where
-	'b: 'a,
-{
-	let expr = match f {
-		Data::Ident(name) => match ctx.1.get(name).unwrap().clone() {
-			Function::UserDefined(user_defined_function) => user_defined_function.code,
-			_ => return Err(Error::LogicError("expected expression".to_string())),
-		},
-		Data::Expression(expression) => expression.clone(),
-		_ => return Err(Error::LogicError("expected expression".to_string())),
-	};
+fn bessel_asymptotic(n: u64, z: Complex) -> Result<(Complex, Complex), Error> {
+	let mu = Decimal::from(4 * n * n);
 
-	dbg!(expr);
+	let (mut p, mut q) = (Complex::ONE, Complex::ZERO);
+	let mut term = Complex::ONE;
+
+	for k in 1..ITERATIONS as u64 {
+		let odd = Decimal::from(2 * k - 1);
+		let next = term * Complex::real((mu - odd * odd) / Decimal::from(8 * k)) / z;
+
+		// The expansion diverges, so it is cut off at its smallest term
+		if next.magnitude() >= term.magnitude() || next.magnitude().is_zero() {
+			break;
+		}
+
+		term = next;
+
+		let signed = if k % 4 < 2 { term } else { -term };
+
+		if k % 2 == 0 {
+			p = p + signed;
+		} else {
+			q = q + signed;
+		}
+	}
+
+	let omega =
+		z - Complex::real((Decimal::from(n) / Decimal::TWO + Decimal::new(25, 2)) * Decimal::PI);
+
+	let scale = (Complex::TWO / (Complex::real(Decimal::PI) * z)).sqrt();
+	let (sin, cos) = (omega.sin()?, omega.cos()?);
+
+	Ok((scale * (p * cos - q * sin), scale * (p * sin + q * cos)))
+}
+
+/// `Jₙ(z)` for a non negative order
+#[inline(always)]
+fn bessel_j(n: u64, z: Complex) -> Result<Complex, Error> {
+	if z.modulus() > BESSEL_ASYMPTOTIC.max(Decimal::from(n * n)) {
+		// The function is even or odd just as its order is
+		return if z.re.is_sign_negative() {
+			let j = bessel_asymptotic(n, -z)?.0;
+
+			Ok(if n % 2 == 1 { -j } else { j })
+		} else {
+			Ok(bessel_asymptotic(n, z)?.0)
+		};
+	}
+
+	Ok(
+		bessel_terms(n, z)?
+			.into_iter()
+			.fold(Complex::ZERO, |sum, term| sum + term),
+	)
+}
+
+/// `Yₙ(z)` for a non negative order
+#[inline(always)]
+fn bessel_y(n: u64, z: Complex) -> Result<Complex, Error> {
+	if z.modulus() > BESSEL_ASYMPTOTIC.max(Decimal::from(n * n)) {
+		if !z.re.is_sign_negative() {
+			return Ok(bessel_asymptotic(n, z)?.1);
+		}
+
+		// Continuing across the branch cut picks up a multiple of Jₙ
+		let (j, y) = bessel_asymptotic(n, -z)?;
+		let i = Complex::new(Decimal::ZERO, Decimal::TWO);
+
+		let y = if z.im.is_sign_negative() {
+			y - i * j
+		} else {
+			y + i * j
+		};
+
+		return Ok(if n % 2 == 1 { -y } else { y });
+	}
+
+	let half = z / Complex::TWO;
+	let terms = bessel_terms(n, z)?;
+
+	let j = terms.iter().fold(Complex::ZERO, |sum, &term| sum + term);
+
+	// Harmonic numbers Hₖ and Hₙ₊ₖ weighting every term of the series of Jₙ
+	let harmonic = |k: u64| {
+		(1..=k).fold(Decimal::ZERO, |sum, i| {
+			sum + Decimal::ONE / Decimal::from(i)
+		})
+	};
+
+	let (mut h, mut hn) = (Decimal::ZERO, harmonic(n));
+	let mut weighted = Complex::ZERO;
+
+	for (k, &term) in terms.iter().enumerate() {
+		if k > 0 {
+			h += Decimal::ONE / Decimal::from(k);
+			hn += Decimal::ONE / Decimal::from(k as u64 + n);
+		}
+
+		weighted = weighted + term * Complex::real(h + hn);
+	}
+
+	// Terms (n - k - 1)! / k! (z/2)²ᵏ⁻ⁿ of the finite sum
+	let mut finite = Complex::ZERO;
+
+	if n > 0 {
+		let mut term = (1..n).fold(Complex::ONE, |term, k| {
+			term * Complex::real(Decimal::from(k))
+		});
+
+		for _ in 0..n {
+			term = term / half;
+		}
+
+		for k in 0..n {
+			if k > 0 {
+				term = term * half * half / Complex::real(Decimal::from((n - k) * k));
+			}
+
+			finite = finite + term;
+		}
+	}
+
+	let pi = Complex::real(Decimal::PI);
+
+	Ok((Complex::TWO * j * (half.ln() + Complex::real(EULER_GAMMA)) - finite - weighted) / pi)
+}
+
+/// Bessel function of the first kind `Jₙ(z)` of integer order
+pub fn besselj(n: &Data, z: &Data) -> Result<Data, Error> {
+	let (n, z) = (bessel_order(n)?, Complex::from_data(z)?);
+
+	let j = bessel_j(n.unsigned_abs(), z)?;
+
+	// J₋ₙ = (-1)ⁿ Jₙ
+	Ok(Data::from(if n < 0 && n % 2 != 0 { -j } else { j }.clean()))
+}
+
+/// Bessel function of the second kind `Yₙ(z)` of integer order
+pub fn bessely(n: &Data, z: &Data) -> Result<Data, Error> {
+	let (n, z) = (bessel_order(n)?, Complex::from_data(z)?);
+
+	if z.magnitude().is_zero() {
+		return Err(
+			ValueError::new(
+				"bessely has a singularity at zero".to_string(),
+				"Bessel functions of the second kind are only defined away from zero",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	let y = bessel_y(n.unsigned_abs(), z)?;
+
+	Ok(Data::from(if n < 0 && n % 2 != 0 { -y } else { y }.clean()))
+}
+
+/// `n⁻ˢ` for a positive integer `n`
+#[inline(always)]
+fn inverse_power(n: u64, s: Complex) -> Result<Complex, Error> {
+	(-s * Complex::real(Decimal::from(n).ln())).exp()
+}
+
+/// Riemann zeta function, from Borwein's acceleration of the alternating
+/// series of the Dirichlet eta function. Arguments left of the critical line
+/// are reflected across it with the functional equation.
+pub fn zeta(a: &Data) -> Result<Data, Error> {
+	let s = Complex::from_data(a)?;
+
+	if s == Complex::ONE {
+		return Err(
+			ValueError::new(
+				"zeta has a pole at 1".to_string(),
+				"the zeta function is defined everywhere except one",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	Ok(Data::from(riemann_zeta(s)?.clean()))
+}
+
+#[inline(always)]
+fn riemann_zeta(s: Complex) -> Result<Complex, Error> {
+	if s.is_real() && s.re.is_zero() {
+		return Ok(Complex::real(Decimal::new(-5, 1)));
+	}
+
+	// Trivial zeros at the negative even integers
+	if s.is_real() && s.re.is_sign_negative() && (s.re % Decimal::TWO).is_zero() {
+		return Ok(Complex::ZERO);
+	}
+
+	if s.re < Decimal::new(5, 1) {
+		let reflected = Complex::ONE - s;
+
+		// 2ˢ πˢ⁻¹ Γ(1 - s), combined as a logarithm since each part may overflow
+		let factor = (s * Complex::real(Decimal::TWO.ln())
+			+ (s - Complex::ONE) * Complex::real(Decimal::PI.ln())
+			+ ln_gamma(reflected))
+		.exp()?;
+
+		let sin = (s * Complex::real(Decimal::HALF_PI)).sin()?;
+
+		return Ok(factor * sin * riemann_zeta(reflected)?);
+	}
+
+	// Coefficients dₖ, which are largest at k = n and still fit a decimal
+	let n = 36;
+
+	let mut d = vec![Decimal::ONE];
+	let mut term = Decimal::ONE;
+
+	for i in 1..=n {
+		term =
+			term / Decimal::from((2 * i) * (2 * i - 1)) * Decimal::from(4 * (n + i - 1) * (n - i + 1));
+
+		d.push(d[i as usize - 1] + term);
+	}
+
+	let total = d[n as usize];
+
+	let mut eta = Complex::ZERO;
+
+	for k in 0..n {
+		let weight = Complex::real((total - d[k as usize]) / total);
+		let term = weight * inverse_power(k + 1, s)?;
+
+		eta = if k % 2 == 0 { eta + term } else { eta - term };
+	}
+
+	let two = (Complex::ONE - s) * Complex::real(Decimal::TWO.ln());
+
+	Ok(eta / (Complex::ONE - two.exp()?))
+}
+
+/// Checks that `v` is a square matrix for the operation described by `reason`
+#[inline(always)]
+pub fn square_matrix<'a>(v: &'a Data, reason: &'static str) -> Result<&'a Matrix, Error> {
+	let Data::Matrix(matrix) = v else {
+		return Err(TypeError::new(DataType::Matrix, v.ty(), 0..0).to_error());
+	};
+
+	let (rows, cols) = v.shape();
+
+	if rows != cols {
+		return Err(ShapeError::new((rows, rows), (rows, cols), reason, 0..0).to_error());
+	}
+
+	Ok(matrix)
+}
+
+#[inline(always)]
+pub fn transpose(v: &Data) -> Result<Data, Error> {
+	match v {
+		Data::Matrix(matrix) => Ok(Data::Matrix(matrix.transpose())),
+		Data::Sparse(matrix) => Ok(Data::Sparse(matrix.transpose())),
+		_ => Err(TypeError::new(DataType::Matrix, v.ty(), 0..0).to_error()),
+	}
+}
+
+#[inline(always)]
+pub fn adj(v: &Data) -> Result<Data, Error> {
+	let matrix = square_matrix(v, "matrix must be square to have an adjoint")?;
+
+	let n = matrix.rows();
+
+	let mut adj_matrix = Matrix::zeros(n, n);
+
+	for i in 0..n {
+		for j in 0..n {
+			let minor_matrix = Matrix::from_fn(n - 1, n - 1, |r, c| {
+				matrix[(r + (r >= i) as usize, c + (c >= j) as usize)]
+			});
+
+			let delta = Complex::from_data(&determinant(&Data::Matrix(minor_matrix))?)?;
+
+			adj_matrix[(j, i)] = if (i + j) % 2 == 0 { delta } else { -delta };
+		}
+	}
+
+	Ok(Data::Matrix(adj_matrix))
+}
+
+/// Colours of the lines on a graph, one for each series in turn
+const PALETTE: [RGBColor; 5] = [GREY_A700, BLUE_A700, RED_A700, GREEN_A700, ORANGE_A700];
+
+/// Draws a line through each series of points and writes the image to the file
+/// system as `graph-output-{TIME_SINCE_UNIX_EPOCH}.png`
+#[inline(always)]
+fn plot(series: Vec<(String, Vec<(f32, f32)>)>, x: Range<f32>, y: Range<f32>) {
+	let start = SystemTime::now();
+	let duration = start.duration_since(UNIX_EPOCH).unwrap().as_millis();
+	let name = format!("graph-output-{duration}.png");
+
+	let root = BitMapBackend::new(&name, (640, 480)).into_drawing_area();
+
+	root.fill(&WHITE).unwrap();
+
+	let mut chart = ChartBuilder::on(&root)
+		.caption("Graph output", ("sans-serif", 20).into_font())
+		.margin(5)
+		.x_label_area_size(30)
+		.y_label_area_size(30)
+		.build_cartesian_2d(x.clone(), y.clone())
+		.unwrap();
+
+	chart.configure_mesh().draw().unwrap();
+
+	for (i, (label, values)) in series.into_iter().enumerate() {
+		let style = PALETTE[i % PALETTE.len()];
+
+		chart
+			.draw_series(LineSeries::new(values, &style))
+			.unwrap()
+			.label(label)
+			.legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], style));
+	}
+
+	chart
+		.draw_series(LineSeries::new([(x.start, 0.0), (x.end, 0.0)], &BLACK))
+		.unwrap();
+
+	chart
+		.draw_series(LineSeries::new([(0.0, y.start), (0.0, y.end)], &BLACK))
+		.unwrap();
+
+	chart
+		.configure_series_labels()
+		.background_style(WHITE.mix(0.8))
+		.border_style(BLACK)
+		.draw()
+		.unwrap();
+
+	root.present().unwrap();
+}
+
+/// Range of the values with a little room on either side
+#[inline(always)]
+fn padded(values: impl Iterator<Item = f32>) -> Range<f32> {
+	let (low, high) = values
+		.filter(|value| value.is_finite())
+		.fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), value| {
+			(low.min(value), high.max(value))
+		});
+
+	let width = high - low;
+
+	if width > 0.0 {
+		low - width / 20.0..high + width / 20.0
+	} else if width == 0.0 {
+		low - 1.0..high + 1.0
+	} else {
+		-1.0..1.0
+	}
+}
+
+pub fn graph<'a, 'b>(f: &Data, ctx: &'a mut InterpreterContext<'b>) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	// Samples are drawn with the first column along the x axis and every other
+	// column as a line of its own
+	if let Data::Matrix(samples) = f {
+		if samples.cols() < 2 {
+			return Err(Error::LogicError(
+				"samples to graph need a column of x values followed by columns of y values".to_string(),
+			));
+		}
+
+		let value = |z: Complex| z.re.to_f32().unwrap_or(f32::NAN);
+
+		let x = padded(samples.iter_rows().map(|row| value(row[0])));
+		let y = padded(
+			samples
+				.iter_rows()
+				.flat_map(|row| row[1..].iter().map(|&z| value(z))),
+		);
+
+		let series = (1..samples.cols())
+			.map(|j| {
+				(
+					format!("y{j}"),
+					samples
+						.iter_rows()
+						.map(|row| (value(row[0]), value(row[j])))
+						.collect(),
+				)
+			})
+			.collect();
+
+		plot(series, x, y);
+
+		return Ok(Data::new_zero());
+	}
+
+	if let Data::Ident(f) = f
+		&& let Function::UserDefined(g) = ctx.1.get(f).unwrap().clone()
+	{
+		let code = &g.code;
+
+		let mut values = vec![];
+
+		for x in -500..=500 {
+			let x = x as f64 / 50.0;
+
+			ctx.0.insert(
+				"x".to_string(),
+				Variable::new(Data::new_real(Decimal::from_f64(x).unwrap()), false),
+			);
+
+			let data = match code.clone().evaluate(ctx, g.range.clone()) {
+				Ok(data) => match data {
+					Data::Number(a, _) => a.to_f32().unwrap(),
+					_ => {
+						return Err(Error::LogicError(
+							"expected number for plotting".to_string(),
+						));
+					}
+				},
+				Err(..) => f32::NAN,
+			};
+
+			values.push((x as f32, data));
+		}
+
+		plot(
+			vec![("Function".to_string(), values)],
+			-10f32..10f32,
+			-10f32..10f32,
+		);
+
+		return Ok(Data::new_zero());
+	}
+	// TODO: error handle this
+	panic!("expected indentifier")
+}
+
+#[inline(always)]
+pub fn sum<'a, 'b>(
+	f: &Data,
+	a: &Data,
+	b: &Data,
+	ctx: &'a mut InterpreterContext<'b>,
+) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let Data::Ident(g) = f else { unreachable!() };
+
+	let func = ctx.1.get(g).unwrap().clone();
+
+	let mut sum = Data::new_zero();
+
+	let a = a.to_real();
+	let b = b.to_real();
+
+	for i in a.to_i64().unwrap()..=b.to_i64().unwrap() {
+		sum = add(
+			&sum,
+			&func.execute(ctx, vec![Data::new_real(Decimal::from_i64(i).unwrap())])?,
+		)?
+	}
+
+	Ok(sum)
+}
+
+#[inline(always)]
+pub fn prod<'a, 'b>(
+	f: &Data,
+	a: &Data,
+	b: &Data,
+	ctx: &'a mut InterpreterContext<'b>,
+) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let Data::Ident(g) = f else { unreachable!() };
+
+	let func = ctx.1.get(g).unwrap().clone();
+
+	let mut prod = Data::new_real(Decimal::ONE);
+
+	let a = a.to_real();
+	let b = b.to_real();
+
+	for i in a.to_i64().unwrap()..=b.to_i64().unwrap() {
+		prod = mul(
+			&prod,
+			&func.execute(ctx, vec![Data::new_real(Decimal::from_i64(i).unwrap())])?,
+		)?
+	}
+
+	Ok(prod)
+}
+
+/// Function of one variable, either a function or an expression in its only
+/// free variable
+enum Univariate {
+	Function(Function),
+	Expression(Expression, String),
+}
+
+impl Univariate {
+	#[inline(always)]
+	fn new(f: &Data, ctx: &mut InterpreterContext) -> Result<Self, Error> {
+		match f {
+			Data::Ident(name) => match ctx.1.get(name) {
+				Some(Function::UserDefined(function)) if function.params.len() != 1 => {
+					Err(Error::LogicError(format!(
+						"expected a function of one variable, `{name}` takes {}",
+						function.params.len()
+					)))
+				}
+				Some(function) => Ok(Self::Function(function.clone())),
+				None => Err(Error::LogicError(format!("undefined function: `{name}`"))),
+			},
+			Data::Expression(expression) => {
+				let free = expression
+					.identifiers()
+					.into_iter()
+					.filter(|name| !ctx.0.contains_key(name) && !ctx.1.contains_key(name))
+					.collect::<Vec<String>>();
+
+				match free.as_slice() {
+					[] => Ok(Self::Expression(expression.clone(), String::new())),
+					[variable] => Ok(Self::Expression(expression.clone(), variable.clone())),
+					_ => Err(Error::LogicError(format!(
+						"expected an expression of one variable, found {}",
+						free.join(", ")
+					))),
+				}
+			}
+			_ => Err(TypeError::new(DataType::Ident, f.ty(), 0..0).to_error()),
+		}
+	}
+
+	#[inline(always)]
+	fn at<'a, 'b>(&self, x: Complex, ctx: &'a mut InterpreterContext<'b>) -> Result<Complex, Error>
+	where
+		'b: 'a,
+	{
+		let value = match self {
+			Self::Function(function) => function.execute(ctx, vec![Data::from(x)])?,
+			Self::Expression(expression, variable) => {
+				let previous = ctx
+					.0
+					.insert(variable.clone(), Variable::new(Data::from(x), false));
+
+				let value = expression.clone().evaluate(ctx, 0..0);
+
+				match previous {
+					Some(previous) => ctx.0.insert(variable.clone(), previous),
+					None => ctx.0.remove(variable),
+				};
+
+				value?
+			}
+		};
+
+		Complex::from_data(&value)
+	}
+
+	/// The derivative when `differentiate` knows how to find it
+	#[inline(always)]
+	fn derivative(&self, ctx: &mut InterpreterContext) -> Option<Self> {
+		let (expression, variable) = match self {
+			Self::Function(Function::UserDefined(function)) => {
+				(function.code.clone(), function.params[0].0.clone())
+			}
+			Self::Function(Function::STD(function)) => (
+				Expression::FunctionCall(
+					function.name.clone(),
+					vec![(Expression::Identifier("x".to_string()), 0..0)],
+				),
+				"x".to_string(),
+			),
+			Self::Expression(expression, variable) => (expression.clone(), variable.clone()),
+		};
+
+		let derivative = expression
+			.differentiate(&Data::Ident(variable.clone()), ctx)
+			.ok()?;
+
+		Some(Self::Expression(derivative, variable))
+	}
+}
+
+/// Nodes of the 15 point Kronrod rule on `[-1, 1]` from the centre outwards,
+/// every other one is also a node of the 7 point Gauss rule it extends.
+const KRONROD_NODES: [Decimal; 8] = [
+	Decimal::ZERO,
+	dec!(0.2077849550078984676006894038),
+	dec!(0.4058451513773971669066064121),
+	dec!(0.5860872354676911302941448457),
+	dec!(0.7415311855993944398638647733),
+	dec!(0.8648644233597690727897127886),
+	dec!(0.9491079123427585245261896840),
+	dec!(0.9914553711208126392068546975),
+];
+
+const KRONROD_WEIGHTS: [Decimal; 8] = [
+	dec!(0.2094821410847278280129991749),
+	dec!(0.2044329400752988924141619992),
+	dec!(0.1903505780647854099132564024),
+	dec!(0.1690047266392679028265834266),
+	dec!(0.1406532597155259187451895905),
+	dec!(0.1047900103222501838398763225),
+	dec!(0.0630920926299785532907006632),
+	dec!(0.0229353220105292249637320081),
+];
+
+const GAUSS_WEIGHTS: [Decimal; 4] = [
+	dec!(0.4179591836734693877551020408),
+	dec!(0.3818300505051189449503697755),
+	dec!(0.2797053914892766679014677714),
+	dec!(0.1294849661688696932706114327),
+];
+
+/// Error an integral is refined until, relative to its size once that exceeds
+/// one
+const INTEGRATION_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 10);
+
+const MAX_PANELS: usize = 500;
+
+/// Width relative to its position below which a panel is not split, as the
+/// nodes of the halves could no longer be told apart
+const NARROWEST_PANEL: Decimal = Decimal::from_parts(1, 0, 0, false, 18);
+
+/// Integral over `[a, b]` by the 15 point Kronrod rule, along with how far it
+/// is from the 7 point Gauss rule as an estimate of its error
+#[inline(always)]
+fn kronrod(
+	f: &mut impl FnMut(Decimal) -> Result<Complex, Error>,
+	a: Decimal,
+	b: Decimal,
+) -> Result<(Complex, Decimal), Error> {
+	let centre = (a + b) / Decimal::TWO;
+	let half = (b - a) / Decimal::TWO;
+
+	let middle = f(centre)?;
+
+	let mut kronrod = middle * Complex::real(KRONROD_WEIGHTS[0]);
+	let mut gauss = middle * Complex::real(GAUSS_WEIGHTS[0]);
+
+	for i in 1..8 {
+		let offset = half * KRONROD_NODES[i];
+		let pair = f(centre - offset)? + f(centre + offset)?;
+
+		kronrod = kronrod + pair * Complex::real(KRONROD_WEIGHTS[i]);
+
+		if i % 2 == 0 {
+			gauss = gauss + pair * Complex::real(GAUSS_WEIGHTS[i / 2]);
+		}
+	}
+
+	let scale = Complex::real(half);
+
+	Ok((kronrod * scale, ((kronrod - gauss) * scale).modulus()))
+}
+
+/// Adaptive Gauss-Kronrod quadrature which keeps splitting the panel with the
+/// largest estimated error until the errors add up to within the tolerance.
+/// A panel the function can not be evaluated on, eg at a pole, is split first
+/// and is left out of the estimate until its halves can be.
+#[inline(always)]
+fn adaptive(
+	f: &mut impl FnMut(Decimal) -> Result<Complex, Error>,
+	a: Decimal,
+	b: Decimal,
+) -> Result<Complex, Error> {
+	// Only a value the function is undefined or too large at is avoided by
+	// splitting the panel, any other error would happen on every panel
+	let mut panel = |a: Decimal, b: Decimal| match kronrod(f, a, b) {
+		Ok(estimate) => Ok((a, b, Some(estimate))),
+		Err(Error::ValueError(..)) => Ok((a, b, None)),
+		Err(error) => Err(error),
+	};
+
+	let mut panels = vec![panel(a, b)?];
+
+	loop {
+		let total = panels
+			.iter()
+			.filter_map(|panel| panel.2)
+			.fold(Complex::ZERO, |total, (estimate, _)| total + estimate);
+		let error: Decimal = panels
+			.iter()
+			.filter_map(|panel| panel.2)
+			.map(|(_, error)| error)
+			.sum();
+		let failed = panels.iter().find(|panel| panel.2.is_none());
+
+		if failed.is_none() && error <= INTEGRATION_TOLERANCE * total.modulus().max(Decimal::ONE) {
+			return Ok(total);
+		}
+
+		let worst = match panels.iter().position(|panel| panel.2.is_none()) {
+			Some(i) => i,
+			None => (0..panels.len())
+				.max_by_key(|&i| panels[i].2.map(|(_, error)| error))
+				.unwrap(),
+		};
+
+		let (a, b, _) = panels[worst];
+		let middle = (a + b) / Decimal::TWO;
+
+		let narrow = (b - a).abs() <= NARROWEST_PANEL * a.abs().max(b.abs()).max(Decimal::ONE);
+
+		if panels.len() >= MAX_PANELS || narrow {
+			let reason = if failed.is_some() {
+				", as the function could not be evaluated on part of the interval"
+			} else {
+				""
+			};
+
+			return Err(Error::LogicError(format!(
+				"integral did not converge, best estimate is {} with an estimated error of {}{reason}",
+				Data::from(total.clean()),
+				error.round_sf(3).unwrap_or(error).normalize()
+			)));
+		}
+
+		panels[worst] = panel(a, middle)?;
+		panels.push(panel(middle, b)?);
+	}
+}
+
+/// Integrand mapped from an infinite interval, which grows too large near the
+/// end of it
+#[inline(always)]
+fn too_large() -> Error {
+	ValueError::new(
+		"integrand is too large".to_string(),
+		"the integrand must fall off quickly enough towards infinity",
+		0..0,
+	)
+	.to_error()
+}
+
+/// Reads a bound of an integral, where the identifier `inf` stands for infinity
+#[inline(always)]
+fn bound(d: &Data) -> Result<Limit, Error> {
+	match d {
+		Data::Number(_, y) if !y.is_zero() => Err(Error::LogicError(
+			"bounds of an integral must be real numbers".to_string(),
+		)),
+		d => point(d),
+	}
+}
+
+/// Definite integral of `f` from `a` to `b`, either of which may be `inf` or
+/// `-inf`.
+pub fn integrate<'a, 'b>(
+	f: &Data,
+	a: &Data,
+	b: &Data,
+	ctx: &'a mut InterpreterContext<'b>,
+) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let integrand = Univariate::new(f, ctx)?;
+
+	// Integrals from a point out to infinity on either side of it are mapped
+	// onto [0, 1) by x = a ± t / (1 - t)
+	let infinite = |a: Decimal, positive: bool, ctx: &mut InterpreterContext| {
+		adaptive(
+			&mut |t| {
+				let complement = Decimal::ONE - t;
+				let jacobian = complement * complement;
+
+				let x = (t / complement)
+					.checked_mul(if positive {
+						Decimal::ONE
+					} else {
+						Decimal::NEGATIVE_ONE
+					})
+					.and_then(|x| x.checked_add(a))
+					.filter(|_| !jacobian.is_zero())
+					.ok_or_else(too_large)?;
+
+				let value = integrand.at(Complex::real(x), ctx)?;
+
+				value
+					.re
+					.checked_div(jacobian)
+					.zip(value.im.checked_div(jacobian))
+					.map(|(re, im)| Complex::new(re, im))
+					.ok_or_else(too_large)
+			},
+			Decimal::ZERO,
+			Decimal::ONE,
+		)
+	};
+
+	let integral = match (bound(a)?, bound(b)?) {
+		(Limit::Finite(a), Limit::Finite(b)) => {
+			adaptive(&mut |x| integrand.at(Complex::real(x), ctx), a, b)?
+		}
+		(Limit::Finite(a), Limit::Infinite(true)) => infinite(a, true, ctx)?,
+		(Limit::Finite(a), Limit::Infinite(false)) => -infinite(a, false, ctx)?,
+		(Limit::Infinite(false), Limit::Finite(b)) => infinite(b, false, ctx)?,
+		(Limit::Infinite(true), Limit::Finite(b)) => -infinite(b, true, ctx)?,
+		(Limit::Infinite(a), Limit::Infinite(b)) if a == b => Complex::ZERO,
+		(Limit::Infinite(a), Limit::Infinite(_)) => {
+			let whole = infinite(Decimal::ZERO, false, ctx)? + infinite(Decimal::ZERO, true, ctx)?;
+
+			if a { -whole } else { whole }
+		}
+	};
+
+	Ok(Data::from(integral.clean()))
+}
+
+#[inline(always)]
+pub fn differentiate<'a, 'b>(
+	f: &Data,
+	a: &Data,
+	ctx: &'a mut InterpreterContext<'b>,
+) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let Data::Ident(g) = f else { unreachable!() };
+
+	ctx.1.get(g).unwrap().clone().differentiate(a, &[], ctx)
+}
+
+/// Relative size of a Newton step, or of the bracket of a bisection, at which
+/// a root is considered found
+const ROOT_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 22);
+
+const ROOT_ITERATIONS: usize = 200;
+
+/// Relative distance within which estimates of the roots of a polynomial are
+/// taken to be the same repeated root
+const CLUSTER_RADIUS: Decimal = Decimal::from_parts(1, 0, 0, false, 6);
+
+/// Value of the polynomial with the coefficients from the highest power down
+#[inline(always)]
+fn evaluate(coefficients: &[Complex], x: Complex) -> Complex {
+	coefficients.iter().fold(Complex::ZERO, |p, &c| p * x + c)
+}
+
+/// Coefficients of the derivative of the polynomial
+#[inline(always)]
+fn derive(coefficients: &[Complex]) -> Vec<Complex> {
+	let degree = coefficients.len() - 1;
+
+	coefficients[..degree]
+		.iter()
+		.enumerate()
+		.map(|(i, &c)| c * Complex::real(Decimal::from(degree - i)))
+		.collect()
+}
+
+/// Roots of `ax^2 + bx + c`, the one with the larger magnitude found first so
+/// the other is not lost to cancellation
+#[inline(always)]
+fn quadratic_roots(a: Complex, b: Complex, c: Complex) -> Result<Vec<Complex>, Error> {
+	if a.magnitude().is_zero() {
+		if b.magnitude().is_zero() {
+			return Err(Error::LogicError(
+				"expected a polynomial of degree one or more".to_string(),
+			));
+		}
+
+		return Ok(vec![-c / b]);
+	}
+
+	let mut root = (b * b - Complex::real(Decimal::from(4)) * a * c).sqrt();
+
+	if (b.conj() * root).re < Decimal::ZERO {
+		root = -root;
+	}
+
+	let q = -(b + root) / Complex::real(Decimal::TWO);
+
+	if q.magnitude().is_zero() {
+		return Ok(vec![Complex::ZERO, Complex::ZERO]);
+	}
+
+	Ok(vec![q / a, c / q])
+}
+
+/// Returns the roots as a list ordered by their real and then imaginary parts
+#[inline(always)]
+fn root_list(mut roots: Vec<Complex>) -> Data {
+	roots = roots.into_iter().map(Complex::clean).collect();
+	roots.sort_by(|x, y| x.re.cmp(&y.re).then(x.im.cmp(&y.im)));
+
+	Data::List(roots.into_iter().map(Data::from).collect())
+}
+
+/// Roots of the quadratic `ax^2 + bx + c`.
+#[inline(always)]
+pub fn quadratic(a: &Data, b: &Data, c: &Data) -> Result<Data, Error> {
+	Ok(root_list(quadratic_roots(
+		Complex::from_data(a)?,
+		Complex::from_data(b)?,
+		Complex::from_data(c)?,
+	)?))
+}
+
+/// Roots of a quadratic function. One written as a polynomial is solved
+/// exactly, any other has its coefficients read from its values at `-1`, `0`
+/// and `1`.
+pub fn quadroot<'a, 'b>(f: &Data, ctx: &'a mut InterpreterContext<'b>) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	if let Ok((expression, variables)) = symbolic(f, ctx)
+		&& let [x] = &variables[..]
+		&& let Ok(coefficients) = expression.coefficients(x)
+		&& (2..=3).contains(&coefficients.len())
+		&& coefficients.iter().all(|c| ratio(c).is_some())
+	{
+		let mut roots = vec![];
+
+		for root in expression.solve(x)? {
+			let root = match root {
+				Data::Expression(root) => root.evaluate(ctx, 0..0)?,
+				root => root,
+			};
+
+			roots.push(Complex::from_data(&root)?);
+		}
+
+		// A double root is counted twice
+		if coefficients.len() == 3 && roots.len() == 1 {
+			roots.push(roots[0]);
+		}
+
+		return Ok(root_list(roots));
+	}
+
+	let f = Univariate::new(f, ctx)?;
+	let mut at = |x: i64| f.at(Complex::real(Decimal::from(x)), ctx);
+
+	let (below, c, above) = (at(-1)?, at(0)?, at(1)?);
+
+	let a = (above + below) / Complex::real(Decimal::TWO) - c;
+	let b = (above - below) / Complex::real(Decimal::TWO);
+
+	// Only a quadratic goes through the values it predicts everywhere else
+	for x in [-3, 2] {
+		let expected =
+			a * Complex::real(Decimal::from(x * x)) + b * Complex::real(Decimal::from(x)) + c;
+		let difference = (at(x)? - expected).magnitude();
+
+		if difference > ROOT_TOLERANCE * expected.magnitude().max(Decimal::ONE) {
+			return Err(Error::LogicError(
+				"expected a quadratic function".to_string(),
+			));
+		}
+	}
+
+	Ok(root_list(quadratic_roots(a, b, c)?))
+}
+
+/// Roots of a polynomial whose coefficients are given from the highest power
+/// down, found together by the Durand-Kerner method.
+pub fn roots(coefficients: &Data) -> Result<Data, Error> {
+	let coefficients = match coefficients {
+		Data::List(list) => list
+			.iter()
+			.map(Complex::from_data)
+			.collect::<Result<Vec<Complex>, Error>>()?,
+		Data::Matrix(matrix) => matrix.elements().to_vec(),
+		_ => {
+			return Err(TypeError::new(DataType::List, coefficients.ty(), 0..0).to_error());
+		}
+	};
+
+	let mut coefficients: Vec<Complex> = coefficients
+		.into_iter()
+		.skip_while(|c| c.magnitude().is_zero())
+		.collect();
+
+	// Roots at zero are exact, so only the rest have to be searched for
+	let mut roots = vec![];
+
+	while coefficients.len() > 1 && coefficients.last().unwrap().magnitude().is_zero() {
+		coefficients.pop();
+		roots.push(Complex::ZERO);
+	}
+
+	let degree = coefficients.len().saturating_sub(1);
+
+	if degree == 0 {
+		return Ok(root_list(roots));
+	}
+
+	if degree <= 2 {
+		let c = |i: usize| coefficients.get(i).copied().unwrap_or(Complex::ZERO);
+		let (a, b, c) = if degree == 2 {
+			(c(0), c(1), c(2))
+		} else {
+			(Complex::ZERO, c(0), c(1))
+		};
+
+		roots.extend(quadratic_roots(a, b, c)?);
+
+		return Ok(root_list(roots));
+	}
+
+	let leading = coefficients[0];
+	let monic: Vec<Complex> = coefficients.iter().map(|&c| c / leading).collect();
+
+	// Starting points spread around a circle containing every root
+	let radius = Decimal::ONE + monic.iter().skip(1).map(|c| c.modulus()).max().unwrap();
+	let seed = Complex::new(dec!(0.4), dec!(0.9));
+
+	let mut estimates = vec![Complex::real(radius)];
+
+	for i in 1..degree {
+		estimates.push(estimates[i - 1] * seed);
+	}
+
+	for _ in 0..ROOT_ITERATIONS {
+		let mut largest_step = Decimal::ZERO;
+
+		for i in 0..degree {
+			let x = estimates[i];
+
+			let denominator = (0..degree)
+				.filter(|&j| j != i)
+				.fold(Complex::real(Decimal::ONE), |d, j| d * (x - estimates[j]));
+
+			if denominator.magnitude().is_zero() {
+				continue;
+			}
+
+			let step = evaluate(&monic, x) / denominator;
+
+			estimates[i] = x - step;
+			largest_step = largest_step.max(step.modulus() / x.modulus().max(Decimal::ONE));
+		}
+
+		if largest_step <= ROOT_TOLERANCE {
+			break;
+		}
+	}
+
+	// Estimates of a root repeated m times only surround it within about the
+	// m-th root of the precision, so they are replaced by their mean refined as
+	// a simple root of the (m - 1)-th derivative
+	let mut remaining = estimates;
+
+	while let Some(x) = remaining.pop() {
+		let (cluster, rest): (Vec<Complex>, Vec<Complex>) = remaining
+			.into_iter()
+			.partition(|&y| (x - y).modulus() <= CLUSTER_RADIUS * x.modulus().max(Decimal::ONE));
+		remaining = rest;
+
+		let multiplicity = cluster.len() + 1;
+
+		if multiplicity == 1 {
+			roots.push(x);
+			continue;
+		}
+
+		let mean =
+			cluster.into_iter().fold(x, |sum, y| sum + y) / Complex::real(Decimal::from(multiplicity));
+
+		let derivative = (1..multiplicity).fold(monic.clone(), |p, _| derive(&p));
+		let slope = derive(&derivative);
+
+		let mut root = mean;
+
+		for _ in 0..ROOT_ITERATIONS {
+			let d = evaluate(&slope, root);
+
+			if d.magnitude().is_zero() {
+				break;
+			}
+
+			let step = evaluate(&derivative, root) / d;
+			root = root - step;
+
+			if step.modulus() <= ROOT_TOLERANCE * root.modulus().max(Decimal::ONE) {
+				break;
+			}
+		}
+
+		roots.extend(vec![root; multiplicity]);
+	}
+
+	Ok(root_list(roots))
+}
+
+/// Root of `f` found by Newton's method from `x0`, using the derivative from
+/// `differentiate` or else a central difference.
+pub fn findroot<'a, 'b>(
+	f: &Data,
+	x0: &Data,
+	ctx: &'a mut InterpreterContext<'b>,
+) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let f = Univariate::new(f, ctx)?;
+	let derivative = f.derivative(ctx);
+
+	let mut x = Complex::from_data(x0)?;
+
+	for _ in 0..ROOT_ITERATIONS {
+		let y = f.at(x, ctx)?;
+
+		if y.magnitude().is_zero() {
+			return Ok(Data::from(x.clean()));
+		}
+
+		let slope = match &derivative {
+			Some(derivative) => derivative.at(x, ctx)?,
+			None => {
+				let h = Complex::real(dec!(1e-10) * x.modulus().max(Decimal::ONE));
+
+				(f.at(x + h, ctx)? - f.at(x - h, ctx)?) / (h + h)
+			}
+		};
+
+		if slope.magnitude().is_zero() {
+			return Err(Error::LogicError(format!(
+				"derivative vanished at {}, try another starting point",
+				Data::from(x.clean())
+			)));
+		}
+
+		let step = y / slope;
+		x = x - step;
+
+		if step.modulus() <= ROOT_TOLERANCE * x.modulus().max(Decimal::ONE) {
+			return Ok(Data::from(x.clean()));
+		}
+	}
+
+	Err(Error::LogicError(format!(
+		"root finding did not converge, last estimate is {}",
+		Data::from(x.clean())
+	)))
+}
+
+/// Root of `f` between `a` and `b`, where it changes sign, found by bisection.
+pub fn bisect<'a, 'b>(
+	f: &Data,
+	a: &Data,
+	b: &Data,
+	ctx: &'a mut InterpreterContext<'b>,
+) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let f = Univariate::new(f, ctx)?;
+	let mut at = |x: Decimal| -> Result<Decimal, Error> {
+		let y = f.at(Complex::real(x), ctx)?;
+
+		if !y.is_real() {
+			return Err(Error::LogicError(
+				"bisection needs a function with real values".to_string(),
+			));
+		}
+
+		Ok(y.re)
+	};
+
+	let (mut a, mut b) = (a.to_real(), b.to_real());
+	let (mut fa, fb) = (at(a)?, at(b)?);
+
+	if fa.is_zero() {
+		return Ok(Data::new_real(a.normalize()));
+	}
+
+	if fb.is_zero() {
+		return Ok(Data::new_real(b.normalize()));
+	}
+
+	if fa.is_sign_negative() == fb.is_sign_negative() {
+		return Err(Error::LogicError(
+			"function must change sign between the bounds".to_string(),
+		));
+	}
+
+	for _ in 0..ROOT_ITERATIONS {
+		let middle = (a + b) / Decimal::TWO;
+
+		if (b - a).abs() <= ROOT_TOLERANCE * middle.abs().max(Decimal::ONE) {
+			break;
+		}
+
+		let fm = at(middle)?;
+
+		if fm.is_zero() {
+			return Ok(Data::new_real(middle.normalize()));
+		}
+
+		if fm.is_sign_negative() == fa.is_sign_negative() {
+			(a, fa) = (middle, fm);
+		} else {
+			b = middle;
+		}
+	}
 
-	Ok(Data::new_zero())
+	Ok(Data::from(Complex::real((a + b) / Decimal::TWO).clean()))
 }
@@ -1,9 +1,19 @@
 use crate::types::DataType;
 
+pub mod calculus;
 pub mod io;
 pub mod iter;
+pub mod linalg;
+pub mod list;
 pub mod math;
+pub mod ode;
 pub mod operators;
+pub mod optimize;
+pub mod polynomial;
+pub mod probability;
+pub mod sparse;
+pub mod stats;
+pub mod symbolic;
 
 #[inline(always)]
 pub fn is_std(f: &str) -> bool {
@@ -25,6 +35,7 @@ pub fn is_std(f: &str) -> bool {
 		"lt",
 		"lteq",
 		"abs",
+		"sign",
 		"round",
 		"ceil",
 		"floor",
@@ -37,15 +48,110 @@ pub fn is_std(f: &str) -> bool {
 		"sqrt",
 		"cbrt",
 		"nrt",
+		"gamma",
+		"lgamma",
+		"beta",
+		"erf",
+		"erfc",
+		"besselj",
+		"bessely",
+		"zeta",
+		"digamma",
+		"diff",
+		"grad",
+		"jacobian",
+		"hessian",
+		"simplify",
+		"trigsimp",
+		"expand",
+		"factor",
+		"collect",
+		"coeffs",
+		"subs",
+		"eval",
+		"integrate",
+		"limit",
+		"taylor",
+		"quadroot",
+		"roots",
+		"poly",
+		"degree",
+		"divmod",
+		"gcd",
+		"findroot",
+		"bisect",
+		"ode",
 		"graph",
 		"transpose",
 		"determinant",
 		"adj",
 		"inverse",
+		"solve",
+		"lu",
+		"qr",
+		"rank",
+		"rref",
+		"nullspace",
+		"trace",
+		"identity",
+		"zeros",
+		"ones",
+		"eigvals",
+		"eig",
+		"svd",
+		"norm",
+		"cond",
+		"pinv",
+		"sparse",
+		"dense",
+		"mean",
+		"median",
+		"mode",
+		"variance",
+		"pvariance",
+		"stddev",
+		"pstddev",
+		"quantile",
+		"min",
+		"max",
+		"cov",
+		"corr",
+		"linreg",
+		"polyfit",
+		"seed",
+		"randint",
+		"pdf",
+		"cdf",
+		"len",
+		"push",
+		"concat",
+		"range",
+		"linspace",
+		"reverse",
+		"sort",
+		"unique",
 	]
 	.contains(&f)
 }
 
+/// Functions of numbers which are applied to every element when given a matrix
+/// or list
+#[inline(always)]
+pub fn is_elementwise(f: &str) -> bool {
+	[
+		"sign", "round", "ceil", "floor", "exp", "ln", "log10", "log", "sin", "sinh", "cos", "cosh",
+		"tan", "atan", "sqrt", "nrt", "gamma", "lgamma", "erf", "erfc", "zeta", "digamma",
+	]
+	.contains(&f)
+}
+
+/// Functions of numbers which are kept as a call in an expression when given a
+/// name without a value or an expression, eg sin(x)
+#[inline(always)]
+pub fn is_symbolic(f: &str) -> bool {
+	is_elementwise(f) || ["abs", "cbrt", "atan2", "beta", "besselj", "bessely"].contains(&f)
+}
+
 // TODO: Add operators in this
 /// Returns argument type and return type
 #[inline(always)]
@@ -54,15 +160,275 @@ pub fn internal_type_map(f: &str) -> (Vec<Vec<DataType>>, DataType) {
 		"read" => (vec![], DataType::Number),
 		"real" => (vec![vec![DataType::Number]], DataType::Number),
 		"int" => (vec![vec![DataType::Number]], DataType::Number),
-		"print" | "round" | "ceil" | "floor" | "ln" | "log10" | "sin" | "cos" | "tan" | "sqrt"
-		| "cbrt" | "graph" => (vec![vec![DataType::Number]], DataType::Number),
-		"log" | "nrt" => (
+		"print" | "sign" | "round" | "ceil" | "floor" | "ln" | "log10" | "sin" | "cos" | "tan"
+		| "sqrt" | "cbrt" | "graph" => (vec![vec![DataType::Number]], DataType::Number),
+		"gamma" | "lgamma" | "erf" | "erfc" | "zeta" | "digamma" => {
+			(vec![vec![DataType::Number]], DataType::Number)
+		}
+		"diff" => (
+			vec![
+				vec![DataType::Ident, DataType::Expression, DataType::Polynomial],
+				vec![DataType::Ident],
+				vec![DataType::Number],
+			],
+			DataType::Expression,
+		),
+		"grad" | "hessian" => (
+			vec![
+				vec![DataType::Ident, DataType::Expression],
+				vec![DataType::List, DataType::Expression],
+			],
+			DataType::Expression,
+		),
+		"simplify" | "trigsimp" | "expand" | "factor" => (
+			vec![vec![
+				DataType::Ident,
+				DataType::Expression,
+				DataType::Polynomial,
+			]],
+			DataType::Expression,
+		),
+		"collect" => (
+			vec![
+				vec![DataType::Ident, DataType::Expression],
+				vec![DataType::Ident],
+			],
+			DataType::Expression,
+		),
+		"coeffs" => (
+			vec![
+				vec![DataType::Ident, DataType::Expression, DataType::Polynomial],
+				vec![DataType::Ident],
+			],
+			DataType::List,
+		),
+		"subs" => (
+			vec![
+				vec![DataType::Ident, DataType::Expression],
+				vec![DataType::Ident, DataType::Expression, DataType::List],
+				vec![DataType::Number, DataType::Expression, DataType::List],
+			],
+			DataType::Number,
+		),
+		"eval" => (
+			vec![
+				vec![DataType::Ident, DataType::Expression, DataType::Polynomial],
+				vec![DataType::Expression, DataType::Number, DataType::Polynomial],
+			],
+			DataType::Number,
+		),
+		"jacobian" => (
+			vec![
+				vec![DataType::List, DataType::Expression],
+				vec![DataType::List, DataType::Expression],
+			],
+			DataType::Expression,
+		),
+		"integrate" => (
+			vec![
+				vec![DataType::Ident, DataType::Expression, DataType::Polynomial],
+				vec![DataType::Number, DataType::Ident],
+				vec![DataType::Number, DataType::Ident],
+			],
+			DataType::Number,
+		),
+		"limit" => (
+			vec![
+				vec![DataType::Ident, DataType::Expression],
+				vec![DataType::Ident],
+				vec![DataType::Number, DataType::Ident, DataType::Expression],
+				vec![DataType::Number],
+			],
+			DataType::Number,
+		),
+		"taylor" => (
+			vec![
+				vec![DataType::Ident, DataType::Expression],
+				vec![DataType::Ident],
+				vec![DataType::Number, DataType::Ident, DataType::Expression],
+				vec![DataType::Number],
+			],
+			DataType::Expression,
+		),
+		"quadroot" => (
+			vec![
+				vec![DataType::Ident, DataType::Expression, DataType::Number],
+				vec![DataType::Number],
+				vec![DataType::Number],
+			],
+			DataType::List,
+		),
+		"roots" => (
+			vec![vec![DataType::List, DataType::Matrix, DataType::Polynomial]],
+			DataType::List,
+		),
+		"poly" => (
+			vec![
+				vec![
+					DataType::List,
+					DataType::Matrix,
+					DataType::Ident,
+					DataType::Expression,
+					DataType::Polynomial,
+				],
+				vec![DataType::Ident],
+			],
+			DataType::Polynomial,
+		),
+		"degree" => (vec![vec![DataType::Polynomial]], DataType::Number),
+		"divmod" => (
+			vec![
+				vec![DataType::Polynomial, DataType::Number],
+				vec![DataType::Polynomial, DataType::Number],
+			],
+			DataType::List,
+		),
+		"gcd" => (
+			vec![
+				vec![DataType::Polynomial, DataType::Number],
+				vec![DataType::Polynomial, DataType::Number],
+			],
+			DataType::Polynomial,
+		),
+		"findroot" => (
+			vec![
+				vec![DataType::Ident, DataType::Expression],
+				vec![DataType::Number],
+			],
+			DataType::Number,
+		),
+		"bisect" => (
+			vec![
+				vec![DataType::Ident, DataType::Expression],
+				vec![DataType::Number],
+				vec![DataType::Number],
+			],
+			DataType::Number,
+		),
+		"ode" => (
+			vec![
+				vec![DataType::Ident],
+				vec![DataType::Number, DataType::Matrix],
+				vec![DataType::Number],
+				vec![DataType::Number],
+				vec![DataType::Number],
+			],
+			DataType::Matrix,
+		),
+		"log" | "nrt" | "beta" | "besselj" | "bessely" => (
 			vec![vec![DataType::Number], vec![DataType::Number]],
 			DataType::Number,
 		),
-		"transpose" | "determinant" | "adj" | "inverse" => {
-			(vec![vec![DataType::Matrix]], DataType::Matrix)
-		}
+		"determinant" => (vec![vec![DataType::Matrix]], DataType::Number),
+		"adj" | "inverse" => (vec![vec![DataType::Matrix]], DataType::Matrix),
+		"transpose" => (
+			vec![vec![DataType::Matrix, DataType::Sparse]],
+			DataType::Matrix,
+		),
+		"solve" => (
+			vec![
+				vec![
+					DataType::Matrix,
+					DataType::Sparse,
+					DataType::Ident,
+					DataType::Expression,
+					DataType::List,
+				],
+				vec![
+					DataType::Matrix,
+					DataType::Ident,
+					DataType::Expression,
+					DataType::List,
+				],
+			],
+			DataType::Matrix,
+		),
+		"lu" | "qr" => (vec![vec![DataType::Matrix]], DataType::List),
+		"rref" | "nullspace" => (vec![vec![DataType::Matrix]], DataType::Matrix),
+		"rank" | "trace" | "cond" => (vec![vec![DataType::Matrix]], DataType::Number),
+		"eigvals" | "eig" | "svd" => (vec![vec![DataType::Matrix]], DataType::List),
+		"pinv" => (vec![vec![DataType::Matrix]], DataType::Matrix),
+		"norm" => (
+			vec![
+				vec![DataType::Matrix, DataType::List],
+				vec![DataType::Number, DataType::Ident],
+			],
+			DataType::Number,
+		),
+		"identity" => (vec![vec![DataType::Number]], DataType::Matrix),
+		"zeros" | "ones" => (
+			vec![vec![DataType::Number], vec![DataType::Number]],
+			DataType::Matrix,
+		),
+		"sparse" => (
+			vec![
+				vec![DataType::List, DataType::Matrix],
+				vec![DataType::List],
+				vec![DataType::List],
+				vec![DataType::Number],
+				vec![DataType::Number],
+			],
+			DataType::Sparse,
+		),
+		"dense" => (vec![vec![DataType::Sparse]], DataType::Matrix),
+		"mean" | "median" | "mode" | "variance" | "pvariance" | "stddev" | "pstddev" | "min"
+		| "max" => (
+			vec![vec![DataType::List, DataType::Matrix]],
+			DataType::Number,
+		),
+		"quantile" => (
+			vec![
+				vec![DataType::List, DataType::Matrix, DataType::Ident],
+				vec![DataType::Number],
+			],
+			DataType::Number,
+		),
+		"cov" | "corr" => (
+			vec![
+				vec![DataType::List, DataType::Matrix],
+				vec![DataType::List, DataType::Matrix],
+			],
+			DataType::Number,
+		),
+		"linreg" => (
+			vec![
+				vec![DataType::List, DataType::Matrix],
+				vec![DataType::List, DataType::Matrix],
+			],
+			DataType::List,
+		),
+		"polyfit" => (
+			vec![
+				vec![DataType::List, DataType::Matrix],
+				vec![DataType::List, DataType::Matrix],
+				vec![DataType::Number],
+			],
+			DataType::List,
+		),
+		"seed" => (vec![vec![DataType::Number]], DataType::Number),
+		"randint" => (
+			vec![vec![DataType::Number], vec![DataType::Number]],
+			DataType::Number,
+		),
+		"pdf" | "cdf" => (
+			vec![vec![DataType::Ident], vec![DataType::Number]],
+			DataType::Number,
+		),
+		"len" => (vec![vec![DataType::List]], DataType::Number),
+		"push" => (vec![vec![DataType::List], vec![]], DataType::List),
+		"concat" => (
+			vec![vec![DataType::List], vec![DataType::List]],
+			DataType::List,
+		),
+		"range" | "linspace" => (
+			vec![
+				vec![DataType::Number],
+				vec![DataType::Number],
+				vec![DataType::Number],
+			],
+			DataType::List,
+		),
+		"reverse" | "sort" | "unique" => (vec![vec![DataType::List]], DataType::List),
 		"abs" => (
 			vec![vec![DataType::Number, DataType::Matrix]],
 			DataType::Number,
@@ -0,0 +1,339 @@
+use std::slice;
+
+use rust_decimal::{Decimal, prelude::ToPrimitive};
+
+use crate::{
+	errors::{Error, ShapeError, TypeError, ValueError},
+	interpreter::{Function, InterpreterContext},
+	matrix::{Complex, Matrix},
+	standardlibrary::internal_type_map,
+	types::{Data, DataType},
+};
+
+/// Rows of the Dormand-Prince tableau as fractions, each stage combines the
+/// slopes of the stages before it
+const DORMAND_PRINCE: [&[(i64, i64)]; 6] = [
+	&[(1, 5)],
+	&[(3, 40), (9, 40)],
+	&[(44, 45), (-56, 15), (32, 9)],
+	&[(19372, 6561), (-25360, 2187), (64448, 6561), (-212, 729)],
+	&[
+		(9017, 3168),
+		(-355, 33),
+		(46732, 5247),
+		(49, 176),
+		(-5103, 18656),
+	],
+	&[
+		(35, 384),
+		(0, 1),
+		(500, 1113),
+		(125, 192),
+		(-2187, 6784),
+		(11, 84),
+	],
+];
+
+/// Fractions of the step at which each stage after the first is taken
+const NODES: [(i64, i64); 6] = [(1, 5), (3, 10), (4, 5), (8, 9), (1, 1), (1, 1)];
+
+/// Weights of the embedded fourth order solution, compared against the fifth
+/// order one in the last row of the tableau to estimate the error of a step
+const EMBEDDED: [(i64, i64); 7] = [
+	(5179, 57600),
+	(0, 1),
+	(7571, 16695),
+	(393, 640),
+	(-92097, 339200),
+	(187, 2100),
+	(1, 40),
+];
+
+/// Error allowed in each step, relative to the size of the state once that
+/// exceeds one
+const TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 12);
+
+const MAX_STEPS: usize = 100_000;
+
+#[inline(always)]
+fn fraction((numerator, denominator): (i64, i64)) -> Decimal {
+	Decimal::from(numerator) / Decimal::from(denominator)
+}
+
+/// `y + h(w₁k₁ + w₂k₂ + ...)` for the slopes `k` of a step
+#[inline(always)]
+fn combine(
+	y: &[Complex],
+	h: Decimal,
+	weights: &[(i64, i64)],
+	slopes: &[Vec<Complex>],
+) -> Vec<Complex> {
+	let mut y = y.to_vec();
+
+	for (&weight, slope) in weights.iter().zip(slopes) {
+		let weight = Complex::real(h * fraction(weight));
+
+		for (y, &k) in y.iter_mut().zip(slope) {
+			*y = *y + weight * k;
+		}
+	}
+
+	y
+}
+
+/// Right hand side `f(t, y)` of a system, called with the state in the shape
+/// of the initial value
+struct System {
+	f: Function,
+	shape: Option<(usize, usize)>,
+}
+
+impl System {
+	#[inline(always)]
+	fn new(f: &Data, y0: &Data, ctx: &mut InterpreterContext) -> Result<(Self, Vec<Complex>), Error> {
+		let Data::Ident(name) = f else {
+			return Err(TypeError::new(DataType::Ident, f.ty(), 0..0).to_error());
+		};
+
+		let Some(f) = ctx.1.get(name).cloned() else {
+			return Err(Error::LogicError(format!("undefined function: `{name}`")));
+		};
+
+		let arity = match &f {
+			Function::UserDefined(f) => f.params.len(),
+			Function::STD(f) => internal_type_map(&f.name).0.len(),
+		};
+
+		if arity != 2 {
+			return Err(
+				ValueError::new(
+					format!(
+						"`{name}` takes {arity} argument{}, not `t` and `y`",
+						if arity == 1 { "" } else { "s" }
+					),
+					"the right hand side of a differential equation is a function of `t` and `y`",
+					0..0,
+				)
+				.to_error(),
+			);
+		}
+
+		let (shape, state) = match y0 {
+			Data::Number(..) => (None, vec![Complex::from_data(y0)?]),
+			Data::Matrix(matrix) => (Some(matrix.shape()), matrix.elements().to_vec()),
+			_ => return Err(TypeError::new(DataType::Matrix, y0.ty(), 0..0).to_error()),
+		};
+
+		Ok((Self { f, shape }, state))
+	}
+
+	#[inline(always)]
+	fn slope<'a, 'b>(
+		&self,
+		t: Decimal,
+		y: &[Complex],
+		ctx: &'a mut InterpreterContext<'b>,
+	) -> Result<Vec<Complex>, Error>
+	where
+		'b: 'a,
+	{
+		let state = match self.shape {
+			Some((rows, cols)) => Data::Matrix(Matrix::new(rows, cols, y.to_vec())),
+			None => Data::from(y[0]),
+		};
+
+		match (
+			self.f.execute(ctx, vec![Data::new_real(t), state])?,
+			self.shape,
+		) {
+			(slope @ Data::Number(..), None) => Ok(vec![Complex::from_data(&slope)?]),
+			(Data::Matrix(slope), Some(shape)) if slope.shape() == shape => Ok(slope.elements().to_vec()),
+			(Data::Matrix(slope), Some(shape)) => Err(
+				ShapeError::new(
+					shape,
+					slope.shape(),
+					"derivative must have the shape of the state",
+					0..0,
+				)
+				.to_error(),
+			),
+			(slope, Some(_)) => Err(TypeError::new(DataType::Matrix, slope.ty(), 0..0).to_error()),
+			(slope, None) => Err(TypeError::new(DataType::Number, slope.ty(), 0..0).to_error()),
+		}
+	}
+}
+
+/// Row of the solution holding the time followed by every value of the state
+#[inline(always)]
+fn sample(t: Decimal, y: &[Complex]) -> Vec<Complex> {
+	let mut row = vec![Complex::real(t).clean()];
+
+	row.extend(y.iter().map(|y| y.clean()));
+
+	row
+}
+
+#[inline(always)]
+fn samples(rows: Vec<Vec<Complex>>) -> Data {
+	Data::Matrix(Matrix::from_rows(rows))
+}
+
+#[inline(always)]
+fn bounds(t0: &Data, t1: &Data) -> Result<(Decimal, Decimal), Error> {
+	let real = |t: &Data| match Complex::from_data(t)? {
+		t if t.is_real() => Ok(t.re),
+		_ => Err(Error::LogicError(
+			"times of a differential equation must be real".to_string(),
+		)),
+	};
+
+	Ok((real(t0)?, real(t1)?))
+}
+
+/// Solves `y' = f(t, y)` from `y(t0) = y0` up to `t1` by the classical fourth
+/// order Runge-Kutta method with steps of `h`, the last one shortened to end at
+/// `t1`.
+pub fn rk4<'a, 'b>(
+	f: &Data,
+	y0: &Data,
+	t0: &Data,
+	t1: &Data,
+	h: &Data,
+	ctx: &'a mut InterpreterContext<'b>,
+) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let (system, mut y) = System::new(f, y0, ctx)?;
+	let (mut t, end) = bounds(t0, t1)?;
+
+	let step = h.to_real().abs();
+
+	if step.is_zero() {
+		return Err(Error::LogicError("step must not be zero".to_string()));
+	}
+
+	let direction = if end < t {
+		Decimal::NEGATIVE_ONE
+	} else {
+		Decimal::ONE
+	};
+	let half = |h: Decimal| h / Decimal::TWO;
+	let sixth = [(1, 6), (1, 3), (1, 3), (1, 6)];
+
+	let mut rows = vec![sample(t, &y)];
+
+	while t != end {
+		let h = direction * step.min((end - t).abs());
+
+		let k1 = system.slope(t, &y, ctx)?;
+		let k2 = system.slope(
+			t + half(h),
+			&combine(&y, half(h), &[(1, 1)], slice::from_ref(&k1)),
+			ctx,
+		)?;
+		let k3 = system.slope(
+			t + half(h),
+			&combine(&y, half(h), &[(1, 1)], slice::from_ref(&k2)),
+			ctx,
+		)?;
+		let k4 = system.slope(t + h, &combine(&y, h, &[(1, 1)], slice::from_ref(&k3)), ctx)?;
+
+		y = combine(&y, h, &sixth, &[k1, k2, k3, k4]);
+		t = if (end - t - h).abs() <= step * TOLERANCE {
+			end
+		} else {
+			t + h
+		};
+
+		rows.push(sample(t, &y));
+	}
+
+	Ok(samples(rows))
+}
+
+/// Solves `y' = f(t, y)` from `y(t0) = y0` up to `t1` by the Dormand-Prince
+/// method, sizing every step so its estimated error stays within the tolerance.
+pub fn dormand_prince<'a, 'b>(
+	f: &Data,
+	y0: &Data,
+	t0: &Data,
+	t1: &Data,
+	ctx: &'a mut InterpreterContext<'b>,
+) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	let (system, mut y) = System::new(f, y0, ctx)?;
+	let (mut t, end) = bounds(t0, t1)?;
+
+	let mut h = (end - t) / Decimal::ONE_HUNDRED;
+	let mut rows = vec![sample(t, &y)];
+
+	if h.is_zero() {
+		return Ok(samples(rows));
+	}
+
+	// The last stage is taken at the end of the step, so it is the first
+	// stage of the next one
+	let mut k1 = system.slope(t, &y, ctx)?;
+
+	for _ in 0..MAX_STEPS {
+		if (end - t).abs() <= h.abs() {
+			h = end - t;
+		}
+
+		let mut slopes = vec![k1.clone()];
+
+		for (weights, &node) in DORMAND_PRINCE.iter().zip(NODES.iter()) {
+			let stage = combine(&y, h, weights, &slopes);
+
+			slopes.push(system.slope(t + h * fraction(node), &stage, ctx)?);
+		}
+
+		let next = combine(&y, h, DORMAND_PRINCE[5], &slopes);
+		let embedded = combine(&y, h, &EMBEDDED, &slopes);
+
+		let error = next
+			.iter()
+			.zip(&embedded)
+			.zip(&y)
+			.map(|((next, embedded), y)| {
+				(*next - *embedded).modulus() / next.modulus().max(y.modulus()).max(Decimal::ONE)
+			})
+			.max()
+			.unwrap_or(Decimal::ZERO)
+			/ TOLERANCE;
+
+		if error <= Decimal::ONE {
+			t = if h == end - t { end } else { t + h };
+			y = next;
+			k1 = slopes.pop().unwrap();
+
+			rows.push(sample(t, &y));
+
+			if t == end {
+				return Ok(samples(rows));
+			}
+		}
+
+		// The error of a step grows with the fifth power of its size
+		let factor = match error.to_f64() {
+			Some(error) if error > 0.0 => (0.9 * error.powf(-0.2)).clamp(0.2, 5.0),
+			_ => 5.0,
+		};
+
+		h *= Decimal::from_f64_retain(factor).unwrap_or(Decimal::ONE);
+
+		if h.is_zero() || (h / (end - t)).abs() <= TOLERANCE * TOLERANCE {
+			return Err(Error::LogicError(format!(
+				"step size vanished at t = {}, the solution may not exist past it",
+				t.normalize()
+			)));
+		}
+	}
+
+	Err(Error::LogicError(format!(
+		"differential equation took more than {MAX_STEPS} steps, it may be stiff"
+	)))
+}
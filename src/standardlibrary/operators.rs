@@ -1,132 +1,253 @@
 use std::{cmp::Ordering, ops::Rem};
 
-use rust_decimal::{Decimal, MathematicalOps, prelude::FromPrimitive};
+use rust_decimal::{
+	Decimal, MathematicalOps,
+	prelude::{FromPrimitive, ToPrimitive},
+};
 
 use crate::{
-	standardlibrary::math::{atan2, inverse},
-	types::Data,
+	errors::{Error, ShapeError, TypeError, ValueError},
+	matrix::{Complex, Matrix, SparseMatrix},
+	standardlibrary::{linalg::inverse, math::atan2, polynomial},
+	types::{Data, DataType},
 };
 
 #[inline(always)]
-pub fn add(lhd: &Data, rhd: &Data) -> Data {
-	match (lhd, rhd) {
-		(Data::Number(a, b), Data::Number(c, d)) => Data::Number(a + c, b + d),
-		(Data::Matrix(a), Data::Matrix(b)) => {
-			if a.len() != b.len() {
-				panic!("number of rows in matrix are not same, required for addition")
-			}
-
-			let mut r = vec![];
+pub fn add(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	Ok(match (lhd, rhd) {
+		(Data::Number(a, b), Data::Number(c, d)) => Data::Number(
+			a.checked_add(*c).ok_or_else(|| too_large("addition"))?,
+			b.checked_add(*d).ok_or_else(|| too_large("addition"))?,
+		),
+		(Data::List(..), _) | (_, Data::List(..)) => broadcast(lhd, rhd, add)?,
+		(Data::Polynomial(..), _) | (_, Data::Polynomial(..)) => polynomial::add(lhd, rhd)?,
+		(Data::Measured(..), _) | (_, Data::Measured(..)) => {
+			let ((a, sa), (b, sb)) = (measurement(lhd)?, measurement(rhd)?);
 
-			let mut col = vec![];
+			propagate(a + b, &[(Decimal::ONE, sa), (Decimal::ONE, sb)])
+		}
+		(Data::Matrix(a), Data::Matrix(b)) => {
+			same_shape(
+				lhd,
+				rhd,
+				"matrices must have the same dimensions for addition",
+			)?;
 
-			for (i, numbers) in a.iter().enumerate() {
-				for (j, number) in numbers.iter().enumerate() {
-					col.push(add(number, &b[i][j]));
-				}
-				r.push(col.clone());
-				col.clear();
-			}
+			Data::Matrix(a.zip_map(b, |a, b| a + b))
+		}
+		(Data::Number(a, b), Data::Matrix(m)) | (Data::Matrix(m), Data::Number(a, b)) => {
+			let t = Complex::new(*a, *b);
 
-			Data::Matrix(r)
+			Data::Matrix(m.map(|z| z + t))
 		}
-		_ => unimplemented!(),
-	}
+		(Data::Sparse(a), Data::Sparse(b)) => {
+			same_shape(
+				lhd,
+				rhd,
+				"matrices must have the same dimensions for addition",
+			)?;
+
+			Data::Sparse(SparseMatrix::from_triplets(
+				a.rows(),
+				a.cols(),
+				a.entries().chain(b.entries()).collect(),
+			))
+		}
+		(Data::Sparse(a), Data::Matrix(..) | Data::Number(..)) => {
+			add(&Data::Matrix(a.to_dense()), rhd)?
+		}
+		(Data::Matrix(..) | Data::Number(..), Data::Sparse(b)) => {
+			add(lhd, &Data::Matrix(b.to_dense()))?
+		}
+		_ => return Err(unsupported("addition", lhd, rhd)),
+	})
 }
 
 #[inline(always)]
-pub fn sub(lhd: &Data, rhd: &Data) -> Data {
-	match (lhd, rhd) {
-		(Data::Number(a, b), Data::Number(c, d)) => Data::Number(a - c, b - d),
-		(Data::Matrix(a), Data::Matrix(b)) => {
-			if a.len() != b.len() {
-				panic!("number of rows in matrix are not same, required for subtraction")
-			}
+pub fn sub(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	Ok(match (lhd, rhd) {
+		(Data::Number(a, b), Data::Number(c, d)) => Data::Number(
+			a.checked_sub(*c).ok_or_else(|| too_large("subtraction"))?,
+			b.checked_sub(*d).ok_or_else(|| too_large("subtraction"))?,
+		),
+		(Data::List(..), _) | (_, Data::List(..)) => broadcast(lhd, rhd, sub)?,
+		(Data::Polynomial(..), _) | (_, Data::Polynomial(..)) => polynomial::sub(lhd, rhd)?,
+		(Data::Measured(..), _) | (_, Data::Measured(..)) => {
+			let ((a, sa), (b, sb)) = (measurement(lhd)?, measurement(rhd)?);
 
-			let mut r = vec![];
+			propagate(a - b, &[(Decimal::ONE, sa), (Decimal::NEGATIVE_ONE, sb)])
+		}
+		(Data::Matrix(a), Data::Matrix(b)) => {
+			same_shape(
+				lhd,
+				rhd,
+				"matrices must have the same dimensions for subtraction",
+			)?;
 
-			let mut col = vec![];
+			Data::Matrix(a.zip_map(b, |a, b| a - b))
+		}
+		(Data::Matrix(m), Data::Number(a, b)) => {
+			let t = Complex::new(*a, *b);
 
-			for (i, numbers) in a.iter().enumerate() {
-				for (j, number) in numbers.iter().enumerate() {
-					col.push(sub(number, &b[i][j]));
-				}
-				r.push(col.clone());
-				col.clear();
-			}
+			Data::Matrix(m.map(|z| z - t))
+		}
+		(Data::Number(a, b), Data::Matrix(m)) => {
+			let t = Complex::new(*a, *b);
 
-			Data::Matrix(r)
+			Data::Matrix(m.map(|z| t - z))
 		}
-		_ => unimplemented!(),
-	}
+		(Data::Sparse(a), Data::Sparse(b)) => {
+			same_shape(
+				lhd,
+				rhd,
+				"matrices must have the same dimensions for subtraction",
+			)?;
+
+			Data::Sparse(SparseMatrix::from_triplets(
+				a.rows(),
+				a.cols(),
+				a.entries()
+					.chain(b.entries().map(|(i, j, z)| (i, j, -z)))
+					.collect(),
+			))
+		}
+		(Data::Sparse(a), Data::Matrix(..) | Data::Number(..)) => {
+			sub(&Data::Matrix(a.to_dense()), rhd)?
+		}
+		(Data::Matrix(..) | Data::Number(..), Data::Sparse(b)) => {
+			sub(lhd, &Data::Matrix(b.to_dense()))?
+		}
+		_ => return Err(unsupported("subtraction", lhd, rhd)),
+	})
 }
 
 #[inline(always)]
-pub fn mul(lhd: &Data, rhd: &Data) -> Data {
-	match (lhd, rhd) {
-		(Data::Number(a, b), Data::Number(c, d)) => Data::Number(a * c - b * d, a * d + b * c),
-		(t @ Data::Number(..), Data::Matrix(b)) | (Data::Matrix(b), t @ Data::Number(..)) => {
-			Data::Matrix(
-				b.iter()
-					.map(|c| c.iter().map(|d| mul(t, d)).collect())
-					.collect(),
-			)
+pub fn mul(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	Ok(match (lhd, rhd) {
+		(Data::Number(a, b), Data::Number(c, d)) => {
+			let product = || {
+				Some((
+					a.checked_mul(*c)?.checked_sub(b.checked_mul(*d)?)?,
+					a.checked_mul(*d)?.checked_add(b.checked_mul(*c)?)?,
+				))
+			};
+			let (re, im) = product().ok_or_else(|| too_large("multiplication"))?;
+
+			Data::Number(re, im)
+		}
+		(Data::List(..), _) | (_, Data::List(..)) => broadcast(lhd, rhd, mul)?,
+		(Data::Polynomial(..), _) | (_, Data::Polynomial(..)) => polynomial::mul(lhd, rhd)?,
+		(Data::Measured(..), _) | (_, Data::Measured(..)) => {
+			let ((a, sa), (b, sb)) = (measurement(lhd)?, measurement(rhd)?);
+
+			propagate(a * b, &[(b, sa), (a, sb)])
+		}
+		(Data::Number(a, b), Data::Matrix(m)) | (Data::Matrix(m), Data::Number(a, b)) => {
+			let t = Complex::new(*a, *b);
+
+			Data::Matrix(m.map(|z| t * z))
+		}
+		(Data::Number(a, b), Data::Sparse(m)) | (Data::Sparse(m), Data::Number(a, b)) => {
+			let t = Complex::new(*a, *b);
+
+			Data::Sparse(m.map(|z| t * z))
 		}
 		(Data::Matrix(a), Data::Matrix(b)) => {
-			// TODO: Check if matrices can be multipled
+			product_shape(lhd, rhd)?;
 
-			let mut r = vec![];
+			Data::Matrix(a.matmul(b))
+		}
+		(Data::Sparse(a), Data::Sparse(b)) => {
+			product_shape(lhd, rhd)?;
 
-			let mut col = vec![];
+			Data::Sparse(a.matmul(b))
+		}
+		(Data::Sparse(a), Data::Matrix(b)) => {
+			product_shape(lhd, rhd)?;
+
+			Data::Matrix(a.mul_dense(b))
+		}
+		(Data::Matrix(a), Data::Sparse(b)) => {
+			product_shape(lhd, rhd)?;
 
-			for row in a {
-				let mut c = 0;
-				while b[0].len() != c {
-					let mut sum = Data::new_zero();
+			Data::Matrix(a.mul_sparse(b))
+		}
+		_ => return Err(unsupported("multiplication", lhd, rhd)),
+	})
+}
 
-					for (k, number) in row.iter().enumerate() {
-						sum = add(&sum, &mul(number, &b[k][c]));
-					}
+#[inline(always)]
+pub fn div(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	Ok(match (lhd, rhd) {
+		(Data::Number(a, b), Data::Number(c, d)) => {
+			if c.is_zero() && d.is_zero() {
+				return Err(division_by_zero());
+			}
 
-					col.push(sum);
-					c += 1;
+			let quotient = || {
+				if d.is_zero() {
+					return Some((a.checked_div(*c)?, b.checked_div(*c)?));
 				}
-				r.push(col.clone());
-				col.clear();
+
+				let modulus = c.checked_mul(*c)?.checked_add(d.checked_mul(*d)?)?;
+
+				Some((
+					a.checked_mul(*c)?
+						.checked_add(b.checked_mul(*d)?)?
+						.checked_div(modulus)?,
+					b.checked_mul(*c)?
+						.checked_sub(a.checked_mul(*d)?)?
+						.checked_div(modulus)?,
+				))
+			};
+			let (re, im) = quotient().ok_or_else(|| too_large("division"))?;
+
+			Data::Number(re, im)
+		}
+		(Data::List(..), _) | (_, Data::List(..)) => broadcast(lhd, rhd, div)?,
+		(Data::Polynomial(..), _) | (_, Data::Polynomial(..)) => polynomial::div(lhd, rhd)?,
+		(Data::Measured(..), _) | (_, Data::Measured(..)) => {
+			let ((a, sa), (b, sb)) = (measurement(lhd)?, measurement(rhd)?);
+
+			if b.is_zero() {
+				return Err(division_by_zero());
 			}
 
-			Data::Matrix(r)
+			propagate(a / b, &[(Decimal::ONE / b, sa), (-a / (b * b), sb)])
 		}
-		_ => unimplemented!(),
-	}
-}
+		(Data::Matrix(..) | Data::Sparse(..), Data::Number(a, b)) if a.is_zero() && b.is_zero() => {
+			return Err(division_by_zero());
+		}
+		(Data::Matrix(m), Data::Number(a, b)) => {
+			let t = Complex::new(*a, *b);
 
-#[inline(always)]
-pub fn div(lhd: &Data, rhd: &Data) -> Data {
-	match (lhd, rhd) {
-		(Data::Number(a, b), Data::Number(c, d)) => Data::Number(
-			(a * c + b * d) / (c * c + d * d),
-			(b * c - a * d) / (c * c + d * d),
-		),
-		(Data::Matrix(b), t @ Data::Number(..)) => Data::Matrix(
-			b.iter()
-				.map(|c| c.iter().map(|d| div(d, t)).collect())
-				.collect(),
-		),
-		_ => unimplemented!(),
-	}
+			Data::Matrix(m.map(|z| z / t))
+		}
+		(Data::Sparse(m), Data::Number(a, b)) => {
+			let t = Complex::new(*a, *b);
+
+			Data::Sparse(m.map(|z| z / t))
+		}
+		// A number is divided by every element, as with `./`
+		(Data::Number(..), Data::Matrix(..) | Data::Sparse(..)) => dot_div(lhd, rhd)?,
+		_ => return Err(unsupported("division", lhd, rhd)),
+	})
 }
 
 #[inline(always)]
-pub fn pow(lhd: &Data, rhd: &Data) -> Data {
-	match (lhd, rhd) {
+pub fn pow(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	Ok(match (lhd, rhd) {
+		(Data::List(..), _) | (_, Data::List(..)) => broadcast(lhd, rhd, pow)?,
+		(Data::Polynomial(..), _) => polynomial::pow(lhd, rhd)?,
 		(Data::Number(a, b), Data::Number(n, m)) => {
 			if *m != Decimal::ZERO {
-				unimplemented!("raising to complex number powers is not supported yet")
+				return Err(complex_power());
 			}
 
-			if *b == Decimal::ZERO {
-				return Data::new_real(a.powd(*n));
+			// Fractional powers of negative numbers are complex, so are found
+			// from the polar form like those of complex numbers
+			if *b == Decimal::ZERO && (!a.is_sign_negative() || n.fract().is_zero()) {
+				return Ok(Data::new_real(real_power(*a, *n)?));
 			}
 
 			let modulus = (a * a + b * b).sqrt().unwrap();
@@ -138,141 +259,554 @@ pub fn pow(lhd: &Data, rhd: &Data) -> Data {
 				modulus.powd(*n) * (*n * argument).sin(),
 			)
 		}
-		(Data::Matrix(matrix), Data::Number(n, m)) => {
+		(Data::Measured(..), _) | (_, Data::Measured(..)) => {
+			let ((a, sa), (n, sn)) = (measurement(lhd)?, measurement(rhd)?);
+
+			if a.is_sign_negative() && !n.fract().is_zero() {
+				return Err(
+					ValueError::new(
+						format!(
+							"`{}` raised to the power of `{}` is not a real number",
+							a.normalize(),
+							n.normalize()
+						),
+						"measured values can only be raised to powers with real results",
+						0..0,
+					)
+					.to_error(),
+				);
+			}
+
+			let value = real_power(a, n)?;
+
+			// Each side only contributes when it is itself uncertain, which also
+			// keeps `ln` away from non-positive bases raised to exact powers.
+			let base_partial = if sa.is_zero() {
+				Decimal::ZERO
+			} else if a.is_zero() && n < Decimal::ONE {
+				return Err(undefined_derivative("^", a));
+			} else {
+				n * real_power(a, n - Decimal::ONE)?
+			};
+
+			let exponent_partial = if sn.is_zero() {
+				Decimal::ZERO
+			} else if a <= Decimal::ZERO {
+				return Err(undefined_derivative("^", a));
+			} else {
+				value * a.ln()
+			};
+
+			propagate(value, &[(base_partial, sa), (exponent_partial, sn)])
+		}
+		(Data::Sparse(a), Data::Number(n, _)) if n.is_sign_negative() => {
+			pow(&Data::Matrix(a.to_dense()), rhd)?
+		}
+		(Data::Matrix(..) | Data::Sparse(..), Data::Number(n, m)) => {
 			if *m != Decimal::ZERO {
-				unimplemented!("raising to complex number powers is not supported yet")
+				return Err(complex_power());
 			}
-			match n.cmp(&Decimal::ZERO) {
-				Ordering::Less => {
-					let mut resultant_matrix = inverse(lhd);
 
-					for _ in 0..n.to_string().parse::<i64>().unwrap() {
-						resultant_matrix = mul(&resultant_matrix, &resultant_matrix);
-					}
+			let (rows, cols) = lhd.shape();
+
+			if rows != cols {
+				return Err(
+					ShapeError::new(
+						(rows, rows),
+						(rows, cols),
+						"matrix must be square to be raised to a power",
+						0..0,
+					)
+					.to_error(),
+				);
+			}
 
-					resultant_matrix
-				}
-				Ordering::Equal => {
-					let cols = matrix.len();
-					let mut identity_matrix = vec![];
-
-					for (i, row) in matrix.iter().enumerate() {
-						if row.len() != cols {
-							panic!("matrix should be square for pow");
-						}
-
-						identity_matrix.push(
-							(0..cols)
-								.map(|j| {
-									Data::Number(
-										if i == j { Decimal::ONE } else { Decimal::ZERO },
-										Decimal::ZERO,
-									)
-								})
-								.collect(),
-						);
-					}
-
-					Data::Matrix(identity_matrix)
-				}
-				Ordering::Greater => {
-					let mut resultant_matrix = lhd.clone();
+			if !n.fract().is_zero() {
+				return Err(
+					ValueError::new(
+						format!(
+							"matrices can only be raised to whole powers, not `{}`",
+							n.normalize()
+						),
+						"use `.^` to raise every element to the power instead",
+						0..0,
+					)
+					.to_error(),
+				);
+			}
 
-					for _ in 1..n.to_string().parse::<i64>().unwrap() {
-						resultant_matrix = mul(&resultant_matrix, &lhd);
-					}
+			let Some(times) = n.abs().to_u64() else {
+				return Err(
+					ValueError::new(
+						format!("`{}` is too large a power of a matrix", n.normalize()),
+						"powers of a matrix are found by repeated multiplication",
+						0..0,
+					)
+					.to_error(),
+				);
+			};
 
-					resultant_matrix
+			match n.cmp(&Decimal::ZERO) {
+				Ordering::Less => repeated_product(&inverse(lhd)?, times)?,
+				Ordering::Equal if lhd.ty() == DataType::Sparse => {
+					Data::Sparse(SparseMatrix::from_dense(&Matrix::identity(rows)))
 				}
+				Ordering::Equal => Data::Matrix(Matrix::identity(rows)),
+				Ordering::Greater => repeated_product(lhd, times)?,
 			}
 		}
-		_ => unimplemented!(),
+		_ => return Err(unsupported("exponentiation", lhd, rhd)),
+	})
+}
+
+/// `a` multiplied by itself `times` times by repeated squaring, `times` must be
+/// at least one
+#[inline(always)]
+fn repeated_product(a: &Data, mut times: u64) -> Result<Data, Error> {
+	let (mut power, mut square) = (None, a.clone());
+
+	loop {
+		if times & 1 == 1 {
+			power = Some(match power {
+				Some(power) => mul(&power, &square)?,
+				None => square.clone(),
+			});
+		}
+
+		times >>= 1;
+
+		if times == 0 {
+			return Ok(power.unwrap());
+		}
+
+		square = mul(&square, &square)?;
 	}
 }
 
+/// Element-wise product of two matrices of the same shape
+#[inline(always)]
+pub fn dot_mul(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	elementwise(
+		lhd,
+		rhd,
+		mul,
+		"matrices must have the same dimensions for element-wise multiplication",
+	)
+}
+
+/// Element-wise quotient of two matrices of the same shape
+#[inline(always)]
+pub fn dot_div(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	elementwise(
+		lhd,
+		rhd,
+		div,
+		"matrices must have the same dimensions for element-wise division",
+	)
+}
+
+/// Raises every element of a matrix to the corresponding element of another
+#[inline(always)]
+pub fn dot_pow(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	elementwise(
+		lhd,
+		rhd,
+		pow,
+		"matrices must have the same dimensions for element-wise powers",
+	)
+}
+
 #[inline(always)]
-pub fn rem(lhd: &Data, rhd: &Data) -> Data {
-	match (lhd, rhd) {
+pub fn plus_minus(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	let (value, sigma) = (real(lhd)?, real(rhd)?);
+
+	Ok(Data::new_measured(value, sigma))
+}
+
+#[inline(always)]
+pub fn rem(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	Ok(match (lhd, rhd) {
 		(Data::Number(a, b), Data::Number(c, d)) => {
 			if *b != Decimal::ZERO || *d != Decimal::ZERO {
-				unimplemented!("remainder of complex number powers is not supported yet")
+				return Err(
+					ValueError::new(
+						"remainder is not defined for complex numbers".to_string(),
+						"take the remainder of the real and imaginary parts separately",
+						0..0,
+					)
+					.to_error(),
+				);
+			}
+
+			if c.is_zero() {
+				return Err(division_by_zero());
 			}
+
 			Data::new_real(a.rem(c))
 		}
-		_ => unimplemented!(),
-	}
+		(Data::List(..), _) | (_, Data::List(..)) => broadcast(lhd, rhd, rem)?,
+		(Data::Polynomial(..), _) | (_, Data::Polynomial(..)) => polynomial::rem(lhd, rhd)?,
+		(
+			Data::Matrix(..) | Data::Sparse(..),
+			Data::Number(..) | Data::Matrix(..) | Data::Sparse(..),
+		)
+		| (Data::Number(..), Data::Matrix(..) | Data::Sparse(..)) => elementwise(
+			lhd,
+			rhd,
+			rem,
+			"matrices must have the same dimensions for element-wise remainders",
+		)?,
+		_ => return Err(unsupported("remainder", lhd, rhd)),
+	})
 }
 
 #[inline(always)]
-pub fn is_eq(lhd: &Data, rhd: &Data) -> Data {
-	match (lhd, rhd) {
+pub fn is_eq(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	Ok(match (lhd, rhd) {
 		(Data::Number(a, b), Data::Number(c, d)) => {
 			Data::new_real(Decimal::from_u8((a == c && b == d) as u8).unwrap())
 		}
 		(Data::Matrix(a), Data::Matrix(b)) => Data::new_real(Decimal::from_u8((a == b) as u8).unwrap()),
-		_ => unimplemented!(),
-	}
+		(Data::Sparse(a), Data::Sparse(b)) => Data::new_real(Decimal::from_u8((a == b) as u8).unwrap()),
+		(Data::Polynomial(a), Data::Polynomial(b)) => {
+			Data::new_real(Decimal::from_u8((a == b) as u8).unwrap())
+		}
+		(Data::List(a), Data::List(b)) => Data::new_real(Decimal::from_u8((a == b) as u8).unwrap()),
+		(Data::Measured(..), _) | (_, Data::Measured(..)) => compare(lhd, rhd, |a, c| a == c)?,
+		(Data::Sparse(a), Data::Matrix(b)) | (Data::Matrix(b), Data::Sparse(a)) => {
+			Data::new_real(Decimal::from_u8((a.to_dense() == *b) as u8).unwrap())
+		}
+		_ => return Err(unsupported("comparison", lhd, rhd)),
+	})
 }
 
 #[inline(always)]
-pub fn neq(lhd: &Data, rhd: &Data) -> Data {
-	match (lhd, rhd) {
+pub fn neq(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	Ok(match (lhd, rhd) {
 		(Data::Number(a, b), Data::Number(c, d)) => {
-			Data::new_real(Decimal::from_u8((a != c && b != d) as u8).unwrap())
+			Data::new_real(Decimal::from_u8((a != c || b != d) as u8).unwrap())
 		}
 		(Data::Matrix(a), Data::Matrix(b)) => Data::new_real(Decimal::from_u8((a != b) as u8).unwrap()),
-		_ => unimplemented!(),
-	}
+		(Data::Sparse(a), Data::Sparse(b)) => Data::new_real(Decimal::from_u8((a != b) as u8).unwrap()),
+		(Data::Polynomial(a), Data::Polynomial(b)) => {
+			Data::new_real(Decimal::from_u8((a != b) as u8).unwrap())
+		}
+		(Data::List(a), Data::List(b)) => Data::new_real(Decimal::from_u8((a != b) as u8).unwrap()),
+		(Data::Measured(..), _) | (_, Data::Measured(..)) => compare(lhd, rhd, |a, c| a != c)?,
+		(Data::Sparse(a), Data::Matrix(b)) | (Data::Matrix(b), Data::Sparse(a)) => {
+			Data::new_real(Decimal::from_u8((a.to_dense() != *b) as u8).unwrap())
+		}
+		_ => return Err(unsupported("comparison", lhd, rhd)),
+	})
 }
 
 #[inline(always)]
-pub fn gt(lhd: &Data, rhd: &Data) -> Data {
-	match (lhd, rhd) {
+pub fn gt(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	Ok(match (lhd, rhd) {
 		(Data::Number(a, b), Data::Number(c, d)) => {
 			if *b != Decimal::ZERO || *d != Decimal::ZERO {
-				unimplemented!("relational operators on complex number powers is not supported yet")
+				return Err(unordered());
 			}
+
 			Data::new_real(Decimal::from_u8((a > c) as u8).unwrap())
 		}
-		_ => unimplemented!(),
-	}
+		(Data::Measured(..), _) | (_, Data::Measured(..)) => compare(lhd, rhd, |a, c| a > c)?,
+		_ => return Err(unsupported("comparison", lhd, rhd)),
+	})
 }
 
 #[inline(always)]
-pub fn gteq(lhd: &Data, rhd: &Data) -> Data {
-	match (lhd, rhd) {
+pub fn gteq(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	Ok(match (lhd, rhd) {
 		(Data::Number(a, b), Data::Number(c, d)) => {
 			if *b != Decimal::ZERO || *d != Decimal::ZERO {
-				unimplemented!("relational operators on complex number powers is not supported yet")
+				return Err(unordered());
 			}
+
 			Data::new_real(Decimal::from_u8((a >= c) as u8).unwrap())
 		}
-		_ => unimplemented!(),
-	}
+		(Data::Measured(..), _) | (_, Data::Measured(..)) => compare(lhd, rhd, |a, c| a >= c)?,
+		_ => return Err(unsupported("comparison", lhd, rhd)),
+	})
 }
 
 #[inline(always)]
-pub fn lt(lhd: &Data, rhd: &Data) -> Data {
-	match (lhd, rhd) {
+pub fn lt(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	Ok(match (lhd, rhd) {
 		(Data::Number(a, b), Data::Number(c, d)) => {
 			if *b != Decimal::ZERO || *d != Decimal::ZERO {
-				unimplemented!("relational operators on complex number powers is not supported yet")
+				return Err(unordered());
 			}
+
 			Data::new_real(Decimal::from_u8((a < c) as u8).unwrap())
 		}
-		_ => unimplemented!(),
-	}
+		(Data::Measured(..), _) | (_, Data::Measured(..)) => compare(lhd, rhd, |a, c| a < c)?,
+		_ => return Err(unsupported("comparison", lhd, rhd)),
+	})
 }
 
 #[inline(always)]
-pub fn lteq(lhd: &Data, rhd: &Data) -> Data {
-	match (lhd, rhd) {
+pub fn lteq(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	Ok(match (lhd, rhd) {
 		(Data::Number(a, b), Data::Number(c, d)) => {
 			if *b != Decimal::ZERO || *d != Decimal::ZERO {
-				unimplemented!("relational operators on complex number powers is not supported yet")
+				return Err(unordered());
 			}
+
 			Data::new_real(Decimal::from_u8((a <= c) as u8).unwrap())
 		}
-		_ => unimplemented!(),
+		(Data::Measured(..), _) | (_, Data::Measured(..)) => compare(lhd, rhd, |a, c| a <= c)?,
+		_ => return Err(unsupported("comparison", lhd, rhd)),
+	})
+}
+
+/// Applies `f` element-wise when either side is a list, any other value is
+/// broadcast over every element of the list.
+fn broadcast(
+	lhd: &Data,
+	rhd: &Data,
+	f: fn(&Data, &Data) -> Result<Data, Error>,
+) -> Result<Data, Error> {
+	Ok(match (lhd, rhd) {
+		(Data::List(a), Data::List(b)) => {
+			same_shape(
+				lhd,
+				rhd,
+				"lists must have the same length for element-wise operations",
+			)?;
+
+			Data::List(
+				a.iter()
+					.zip(b)
+					.map(|(a, b)| f(a, b))
+					.collect::<Result<_, _>>()?,
+			)
+		}
+		(Data::List(a), b) => Data::List(a.iter().map(|a| f(a, b)).collect::<Result<_, _>>()?),
+		(a, Data::List(b)) => Data::List(b.iter().map(|b| f(a, b)).collect::<Result<_, _>>()?),
+		_ => unreachable!(),
+	})
+}
+
+/// Applies `f` to corresponding elements when either side is a matrix, a
+/// number is broadcast over every element and sparse matrices are made dense.
+fn elementwise(
+	lhd: &Data,
+	rhd: &Data,
+	f: fn(&Data, &Data) -> Result<Data, Error>,
+	reason: &'static str,
+) -> Result<Data, Error> {
+	let apply = |a: Complex, b: Complex| Complex::from_data(&f(&Data::from(a), &Data::from(b))?);
+
+	let (shape, elements) = match (lhd, rhd) {
+		(Data::Sparse(a), _) => return elementwise(&Data::Matrix(a.to_dense()), rhd, f, reason),
+		(_, Data::Sparse(b)) => return elementwise(lhd, &Data::Matrix(b.to_dense()), f, reason),
+		(Data::Matrix(a), Data::Matrix(b)) => {
+			same_shape(lhd, rhd, reason)?;
+
+			(
+				a.shape(),
+				a.elements()
+					.iter()
+					.zip(b.elements())
+					.map(|(&a, &b)| apply(a, b))
+					.collect::<Result<_, _>>()?,
+			)
+		}
+		(Data::Matrix(a), Data::Number(..)) => {
+			let t = Complex::from_data(rhd)?;
+
+			(
+				a.shape(),
+				a.elements()
+					.iter()
+					.map(|&z| apply(z, t))
+					.collect::<Result<_, _>>()?,
+			)
+		}
+		(Data::Number(..), Data::Matrix(b)) => {
+			let t = Complex::from_data(lhd)?;
+
+			(
+				b.shape(),
+				b.elements()
+					.iter()
+					.map(|&z| apply(t, z))
+					.collect::<Result<_, _>>()?,
+			)
+		}
+		_ => return f(lhd, rhd),
+	};
+
+	Ok(Data::Matrix(Matrix::new(shape.0, shape.1, elements)))
+}
+
+fn same_shape(lhd: &Data, rhd: &Data, reason: &'static str) -> Result<(), Error> {
+	if lhd.shape() != rhd.shape() {
+		return Err(ShapeError::new(lhd.shape(), rhd.shape(), reason, 0..0).to_error());
+	}
+
+	Ok(())
+}
+
+/// Power of a real number, which underflows to zero rather than failing when
+/// it is too small for a decimal to hold
+#[inline(always)]
+fn real_power(a: Decimal, n: Decimal) -> Result<Decimal, Error> {
+	// `powd` gives up on fractional powers beyond about `e^11.7`, which the
+	// whole and fractional parts of `n` are raised to separately instead
+	let power = a.checked_powd(n).or_else(|| {
+		let whole = n.trunc();
+
+		a.checked_powd(whole)?
+			.checked_mul(a.checked_powd(n - whole)?)
+	});
+
+	match power {
+		Some(power) => Ok(power),
+		None if a.is_zero() => Err(division_by_zero()),
+		None if (a.abs() > Decimal::ONE) == n.is_sign_negative() => Ok(Decimal::ZERO),
+		None => Err(
+			ValueError::new(
+				format!(
+					"`{}` raised to the power of `{}` is too large",
+					a.normalize(),
+					n.normalize()
+				),
+				"numbers can not be larger than about 7.9e28",
+				0..0,
+			)
+			.to_error(),
+		),
 	}
 }
+
+/// Error for a pair of values an operator is not defined between
+#[inline(always)]
+fn unsupported(operation: &str, lhd: &Data, rhd: &Data) -> Error {
+	TypeError::new(DataType::Number, rhd.ty(), 0..0)
+		.with_message(format!(
+			"{operation} is not defined between `{}` and `{}`",
+			lhd.ty(),
+			rhd.ty()
+		))
+		.to_error()
+}
+
+#[inline(always)]
+fn division_by_zero() -> Error {
+	ValueError::new(
+		"division by zero".to_string(),
+		"the divisor must not be zero",
+		0..0,
+	)
+	.to_error()
+}
+
+/// Powers are only taken to real exponents
+#[inline(always)]
+fn complex_power() -> Error {
+	ValueError::new(
+		"raising to complex powers is not supported".to_string(),
+		"the power must be a real number",
+		0..0,
+	)
+	.to_error()
+}
+
+/// Complex numbers can be equal but not ordered
+#[inline(always)]
+fn unordered() -> Error {
+	ValueError::new(
+		"complex numbers can not be ordered".to_string(),
+		"compare the real parts or the moduli of the numbers instead",
+		0..0,
+	)
+	.to_error()
+}
+
+/// Result of an operation on numbers that is too large for a decimal to hold
+#[inline(always)]
+fn too_large(operation: &str) -> Error {
+	ValueError::new(
+		format!("result of {operation} is too large to represent"),
+		"numbers can not be larger than about 7.9e28",
+		0..0,
+	)
+	.to_error()
+}
+
+/// Checks that the columns of `lhd` match the rows of `rhd` for a product
+fn product_shape(lhd: &Data, rhd: &Data) -> Result<(), Error> {
+	let (cols, (rows, rhs_cols)) = (lhd.shape().1, rhd.shape());
+
+	if rows != cols {
+		return Err(
+			ShapeError::new(
+				(cols, rhs_cols),
+				(rows, rhs_cols),
+				"number of rows of the right matrix must equal the number of columns of the left matrix",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	Ok(())
+}
+
+/// Real number on either side of `±`, as measured values can not be complex
+fn real(data: &Data) -> Result<Decimal, Error> {
+	match data {
+		Data::Number(a, b) if b.is_zero() => Ok(*a),
+		Data::Number(..) => Err(
+			ValueError::new(
+				"measured values can not be complex".to_string(),
+				"only the real part of a measurement carries an uncertainty",
+				0..0,
+			)
+			.to_error(),
+		),
+		_ => Err(TypeError::new(DataType::Number, data.ty(), 0..0).to_error()),
+	}
+}
+
+/// Splits a value into its nominal value and standard deviation, plain numbers
+/// are treated as exact.
+fn measurement(data: &Data) -> Result<(Decimal, Decimal), Error> {
+	match data {
+		Data::Measured(value, sigma) => Ok((*value, *sigma)),
+		data => Ok((real(data)?, Decimal::ZERO)),
+	}
+}
+
+/// Compares the nominal values of measured values, ignoring their uncertainty
+fn compare(lhd: &Data, rhd: &Data, f: fn(Decimal, Decimal) -> bool) -> Result<Data, Error> {
+	let ((a, _), (c, _)) = (measurement(lhd)?, measurement(rhd)?);
+
+	Ok(Data::new_real(Decimal::from(f(a, c) as u8)))
+}
+
+/// Uncertainty through a function at a point where its derivative is undefined,
+/// such as `sqrt` at zero, is unbounded to first order
+pub fn undefined_derivative(f: &str, at: Decimal) -> Error {
+	ValueError::new(
+		format!(
+			"cannot propagate uncertainty through `{f}` at `{}`",
+			at.normalize()
+		),
+		"the derivative is undefined here, so the uncertainty can not be estimated to first order",
+		0..0,
+	)
+	.to_error()
+}
+
+/// First order propagation of uncertainty, `terms` holds the partial derivative
+/// with respect to each independent input along with its standard deviation.
+pub fn propagate(value: Decimal, terms: &[(Decimal, Decimal)]) -> Data {
+	let variance = terms.iter().fold(Decimal::ZERO, |acc, (partial, sigma)| {
+		acc + (partial * sigma) * (partial * sigma)
+	});
+
+	Data::new_measured(value, variance.sqrt().unwrap().normalize())
+}
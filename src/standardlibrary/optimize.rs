@@ -0,0 +1,579 @@
+use rust_decimal::{Decimal, MathematicalOps, dec};
+
+use crate::{
+	errors::{Error, ShapeError, TypeError, ValueError},
+	expr::Expression,
+	interpreter::{Function, InterpreterContext, UserDefinedFunction},
+	matrix::{Complex, Matrix},
+	standardlibrary::math::sin_cos,
+	types::{Data, DataType},
+};
+
+/// Size of the gradient below which a point is taken to be a minimum
+const GRADIENT_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 15);
+
+/// Relative size of a step, or of the interval of a golden section search,
+/// below which the search stops
+const STEP_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 20);
+
+/// Smallest fraction of a step searched along before giving up on finding a
+/// lower point
+const SMALLEST_STEP: Decimal = Decimal::from_parts(1, 0, 0, false, 24);
+
+/// Fraction of the decrease predicted by the gradient a step has to achieve
+const SUFFICIENT_DECREASE: Decimal = Decimal::from_parts(1, 0, 0, false, 4);
+
+const MAX_ITERATIONS: usize = 1000;
+
+/// `(√5 - 1) / 2`, the fraction of an interval kept by each golden section
+const GOLDEN: Decimal = dec!(0.6180339887498948482045868344);
+
+/// Form of the point a function is minimized over, so the result can be given
+/// back in the form the start was
+#[derive(Clone, Copy)]
+enum Shape {
+	Number,
+	List,
+	Matrix(usize, usize),
+}
+
+impl Shape {
+	#[inline(always)]
+	fn read(d: &Data) -> Result<(Self, Vec<Decimal>), Error> {
+		let (shape, values) = match d {
+			Data::Number(..) => (Self::Number, vec![Complex::from_data(d)?]),
+			Data::List(list) => (
+				Self::List,
+				list
+					.iter()
+					.map(Complex::from_data)
+					.collect::<Result<Vec<Complex>, Error>>()?,
+			),
+			Data::Matrix(matrix) => (
+				Self::Matrix(matrix.rows(), matrix.cols()),
+				matrix.elements().to_vec(),
+			),
+			_ => return Err(TypeError::new(DataType::Number, d.ty(), 0..0).to_error()),
+		};
+
+		let values = values
+			.into_iter()
+			.map(|z| {
+				if z.is_real() {
+					Ok(z.re)
+				} else {
+					Err(Error::LogicError(
+						"functions can only be minimized over real numbers".to_string(),
+					))
+				}
+			})
+			.collect::<Result<Vec<Decimal>, Error>>()?;
+
+		Ok((shape, values))
+	}
+
+	#[inline(always)]
+	fn data(self, x: &[Decimal]) -> Data {
+		let clean = |x: &Decimal| Complex::real(*x).clean();
+
+		match self {
+			Self::Number => Data::from(clean(&x[0])),
+			Self::List => Data::List(x.iter().map(|x| Data::from(clean(x))).collect()),
+			Self::Matrix(rows, cols) => {
+				Data::Matrix(Matrix::new(rows, cols, x.iter().map(clean).collect()))
+			}
+		}
+	}
+}
+
+/// Function to minimize, taking a real number for each of its parameters, with
+/// the partial derivatives `differentiate` finds for it
+struct Objective {
+	f: UserDefinedFunction,
+	partials: Option<Vec<UserDefinedFunction>>,
+	/// `-1` when maximizing, as that minimizes the negated function
+	sign: Decimal,
+}
+
+impl Objective {
+	#[inline(always)]
+	fn new(f: &Data, n: usize, sign: Decimal, ctx: &mut InterpreterContext) -> Result<Self, Error> {
+		let Data::Ident(name) = f else {
+			return Err(TypeError::new(DataType::Ident, f.ty(), 0..0).to_error());
+		};
+
+		// Functions of the standard library are called through a function of
+		// their own so both kinds can be differentiated the same way
+		let f = match ctx.1.get(name) {
+			Some(Function::UserDefined(f)) => f.clone(),
+			Some(Function::STD(f)) => UserDefinedFunction {
+				params: vec![("x".to_string(), DataType::Number)],
+				return_type: DataType::Number,
+				code: Expression::FunctionCall(
+					f.name.clone(),
+					vec![(Expression::Identifier("x".to_string()), 0..0)],
+				),
+				range: 0..0,
+			},
+			None => return Err(Error::LogicError(format!("undefined function: `{name}`"))),
+		};
+
+		if f.params.len() != n {
+			return Err(Error::LogicError(format!(
+				"`{name}` takes {} arguments but the starting point has {n} values",
+				f.params.len()
+			)));
+		}
+
+		let partials = f
+			.params
+			.iter()
+			.map(|(param, _)| {
+				Ok(UserDefinedFunction {
+					code: f.code.differentiate(&Data::Ident(param.clone()), ctx)?,
+					..f.clone()
+				})
+			})
+			.collect::<Result<Vec<UserDefinedFunction>, Error>>()
+			.ok();
+
+		Ok(Self { f, partials, sign })
+	}
+
+	#[inline(always)]
+	fn call<'a, 'b>(
+		f: &UserDefinedFunction,
+		x: &[Decimal],
+		ctx: &'a mut InterpreterContext<'b>,
+	) -> Result<Decimal, Error>
+	where
+		'b: 'a,
+	{
+		let args = x.iter().map(|&x| Data::new_real(x)).collect();
+
+		match Complex::from_data(&f.execute(ctx, args)?)? {
+			y if y.is_real() => Ok(y.re),
+			_ => Err(Error::LogicError(
+				"function to minimize must have real values".to_string(),
+			)),
+		}
+	}
+
+	#[inline(always)]
+	fn value<'a, 'b>(
+		&self,
+		x: &[Decimal],
+		ctx: &'a mut InterpreterContext<'b>,
+	) -> Result<Decimal, Error>
+	where
+		'b: 'a,
+	{
+		Ok(self.sign * Self::call(&self.f, x, ctx)?)
+	}
+
+	/// Gradient from the partial derivatives, or else from central differences
+	#[inline(always)]
+	fn gradient<'a, 'b>(
+		&self,
+		x: &[Decimal],
+		ctx: &'a mut InterpreterContext<'b>,
+	) -> Result<Vec<Decimal>, Error>
+	where
+		'b: 'a,
+	{
+		if let Some(partials) = &self.partials {
+			return partials
+				.iter()
+				.map(|partial| Ok(self.sign * Self::call(partial, x, ctx)?))
+				.collect();
+		}
+
+		let mut gradient = Vec::with_capacity(x.len());
+		let mut point = x.to_vec();
+
+		for i in 0..x.len() {
+			let h = Decimal::from_parts(1, 0, 0, false, 10) * x[i].abs().max(Decimal::ONE);
+
+			point[i] = x[i] + h;
+			let above = self.value(&point, ctx)?;
+
+			point[i] = x[i] - h;
+			let below = self.value(&point, ctx)?;
+
+			point[i] = x[i];
+			gradient.push((above - below) / (h + h));
+		}
+
+		Ok(gradient)
+	}
+}
+
+/// Dot product of `a` and `b`, or `None` when it does not fit in a decimal
+#[inline(always)]
+fn dot(a: &[Decimal], b: &[Decimal]) -> Option<Decimal> {
+	a.iter().zip(b).try_fold(Decimal::ZERO, |sum, (a, b)| {
+		sum.checked_add(a.checked_mul(*b)?)
+	})
+}
+
+#[inline(always)]
+fn largest(a: &[Decimal]) -> Decimal {
+	a.iter().map(|a| a.abs()).max().unwrap_or(Decimal::ZERO)
+}
+
+/// Minimum of `f` near `x` found by the BFGS method, which builds up an
+/// estimate of the inverse of the Hessian from how the gradient changes along
+/// each step, searching back along each step until it decreases `f` enough.
+#[inline(always)]
+fn bfgs<'a, 'b>(
+	f: &mut impl FnMut(&[Decimal], &mut InterpreterContext) -> Result<Decimal, Error>,
+	gradient: &mut impl FnMut(&[Decimal], &mut InterpreterContext) -> Result<Vec<Decimal>, Error>,
+	mut x: Vec<Decimal>,
+	ctx: &'a mut InterpreterContext<'b>,
+) -> Result<Vec<Decimal>, Error>
+where
+	'b: 'a,
+{
+	let n = x.len();
+	let identity = || {
+		(0..n)
+			.map(|i| {
+				(0..n)
+					.map(|j| if i == j { Decimal::ONE } else { Decimal::ZERO })
+					.collect()
+			})
+			.collect::<Vec<Vec<Decimal>>>()
+	};
+
+	// The search has run off too far to be computed with, or for too long,
+	// without the function levelling out
+	let unbounded = |x: &[Decimal]| {
+		ValueError::new(
+			format!(
+				"function is unbounded or the search did not converge, last estimate is {}",
+				if n == 1 { Shape::Number } else { Shape::List }.data(x)
+			),
+			"start the search closer to the point, or give lower and upper bounds to search between",
+			0..0,
+		)
+		.to_error()
+	};
+
+	let mut inverse = identity();
+	let mut scaled = false;
+	let mut value = f(&x, ctx)?;
+	let mut g = gradient(&x, ctx)?;
+
+	for _ in 0..MAX_ITERATIONS {
+		if largest(&g) <= GRADIENT_TOLERANCE * value.abs().max(Decimal::ONE) {
+			return Ok(x);
+		}
+
+		let mut direction = inverse
+			.iter()
+			.map(|row| dot(row, &g).map(|d| -d))
+			.collect::<Option<Vec<Decimal>>>()
+			.ok_or_else(|| unbounded(&x))?;
+
+		// The estimate has stopped pointing downhill, so it starts over
+		if dot(&direction, &g).is_none_or(|slope| slope >= Decimal::ZERO) {
+			inverse = identity();
+			scaled = false;
+			direction = g.iter().map(|g| -g).collect();
+		}
+
+		let slope = dot(&direction, &g).ok_or_else(|| unbounded(&x))?;
+
+		let mut t = Decimal::ONE;
+		let (next, next_value) = loop {
+			let next = x
+				.iter()
+				.zip(&direction)
+				.map(|(x, d)| x.checked_add(t.checked_mul(*d)?))
+				.collect::<Option<Vec<Decimal>>>();
+
+			// A step too long to be taken, or to a point the function is too
+			// large at, is searched back along like one that does not
+			// decrease the function enough
+			if let Some(next) = next {
+				let next_value = match f(&next, ctx) {
+					Err(Error::ValueError(..)) => None,
+					next_value => Some(next_value?),
+				};
+				let expected = (SUFFICIENT_DECREASE * t)
+					.checked_mul(slope)
+					.and_then(|decrease| value.checked_add(decrease));
+
+				if let Some(next_value) = next_value
+					&& expected.is_none_or(|expected| next_value <= expected)
+				{
+					break (next, next_value);
+				}
+			}
+
+			t /= Decimal::TWO;
+
+			// No lower point could be found, so this is as close as the
+			// precision of the values allows, unless even the shortest steps
+			// still run off too far to compute the function at
+			if t < SMALLEST_STEP {
+				return match t.checked_mul(largest(&direction)) {
+					Some(step)
+						if step
+							<= STEP_TOLERANCE.sqrt().unwrap_or(Decimal::ONE) * largest(&x).max(Decimal::ONE) =>
+					{
+						Ok(x)
+					}
+					_ => Err(unbounded(&x)),
+				};
+			}
+		};
+
+		let next_g = gradient(&next, ctx)?;
+
+		let s = next
+			.iter()
+			.zip(&x)
+			.map(|(a, b)| a.checked_sub(*b))
+			.collect::<Option<Vec<Decimal>>>()
+			.ok_or_else(|| unbounded(&next))?;
+		let y = next_g
+			.iter()
+			.zip(&g)
+			.map(|(a, b)| a.checked_sub(*b))
+			.collect::<Option<Vec<Decimal>>>()
+			.ok_or_else(|| unbounded(&next))?;
+
+		let converged = largest(&s) <= STEP_TOLERANCE * largest(&next).max(Decimal::ONE);
+
+		(x, value, g) = (next, next_value, next_g);
+
+		if converged {
+			return Ok(x);
+		}
+
+		let Some(sy) = dot(&s, &y) else {
+			return Err(unbounded(&x));
+		};
+
+		if sy <= Decimal::ZERO {
+			continue;
+		}
+
+		// The estimate starts from the scale of the curvature along the first
+		// step
+		if !scaled {
+			let scale = dot(&y, &y)
+				.and_then(|yy| sy.checked_div(yy))
+				.ok_or_else(|| unbounded(&x))?;
+
+			inverse = identity()
+				.into_iter()
+				.map(|row| row.into_iter().map(|h| h * scale).collect())
+				.collect();
+			scaled = true;
+		}
+
+		// H = (I - ρsyᵀ) H (I - ρysᵀ) + ρssᵀ where ρ = 1 / sᵀy, which is
+		// multiplied in first as the products of small steps would otherwise
+		// fall below the precision of a decimal
+		let rho = Decimal::ONE.checked_div(sy).ok_or_else(|| unbounded(&x))?;
+		let hy = inverse
+			.iter()
+			.map(|row| dot(row, &y))
+			.collect::<Option<Vec<Decimal>>>()
+			.ok_or_else(|| unbounded(&x))?;
+		let weight = dot(&y, &hy)
+			.and_then(|yhy| yhy.checked_mul(rho))
+			.and_then(|yhy| yhy.checked_add(Decimal::ONE))
+			.ok_or_else(|| unbounded(&x))?;
+
+		let update = |h: Decimal, i: usize, j: usize| {
+			let (srho, hyrho) = (s[i].checked_mul(rho)?, hy[i].checked_mul(rho)?);
+
+			h.checked_add(weight.checked_mul(srho)?.checked_mul(s[j])?)?
+				.checked_sub(hyrho.checked_mul(s[j])?)?
+				.checked_sub(srho.checked_mul(hy[j])?)
+		};
+
+		for (i, row) in inverse.iter_mut().enumerate() {
+			for (j, h) in row.iter_mut().enumerate() {
+				*h = update(*h, i, j).ok_or_else(|| unbounded(&x))?;
+			}
+		}
+	}
+
+	Err(unbounded(&x))
+}
+
+/// Minimum of `f` within `[a, b]` found by golden section search, which keeps
+/// the part of the interval the lower of two values lies in, then refined by
+/// bisecting where the derivative changes sign since the values themselves
+/// become too flat to compare.
+#[inline(always)]
+fn golden_section<'a, 'b>(
+	objective: &Objective,
+	mut a: Decimal,
+	mut b: Decimal,
+	ctx: &'a mut InterpreterContext<'b>,
+) -> Result<Decimal, Error>
+where
+	'b: 'a,
+{
+	let width = |a: Decimal, b: Decimal| STEP_TOLERANCE * a.abs().max(b.abs()).max(Decimal::ONE);
+
+	let mut c = b - GOLDEN * (b - a);
+	let mut d = a + GOLDEN * (b - a);
+	let (mut fc, mut fd) = (objective.value(&[c], ctx)?, objective.value(&[d], ctx)?);
+
+	for _ in 0..MAX_ITERATIONS {
+		if (b - a).abs() <= width(a, b).sqrt().unwrap_or(Decimal::ONE) {
+			break;
+		}
+
+		if fc < fd {
+			(b, d, fd) = (d, c, fc);
+			c = b - GOLDEN * (b - a);
+			fc = objective.value(&[c], ctx)?;
+		} else {
+			(a, c, fc) = (c, d, fd);
+			d = a + GOLDEN * (b - a);
+			fd = objective.value(&[d], ctx)?;
+		}
+	}
+
+	let slope =
+		|x: Decimal, ctx: &mut InterpreterContext| Ok::<_, Error>(objective.gradient(&[x], ctx)?[0]);
+
+	// The lowest value is at an end of what is left of the interval when the
+	// function is not falling into it from there
+	if slope(a, ctx)? >= Decimal::ZERO {
+		return Ok(a);
+	}
+
+	if slope(b, ctx)? <= Decimal::ZERO {
+		return Ok(b);
+	}
+
+	for _ in 0..MAX_ITERATIONS {
+		let middle = (a + b) / Decimal::TWO;
+
+		if (b - a).abs() <= width(a, b) {
+			break;
+		}
+
+		if slope(middle, ctx)? < Decimal::ZERO {
+			a = middle;
+		} else {
+			b = middle;
+		}
+	}
+
+	Ok((a + b) / Decimal::TWO)
+}
+
+#[inline(always)]
+fn optimize<'a, 'b>(
+	args: &[Data],
+	sign: Decimal,
+	ctx: &'a mut InterpreterContext<'b>,
+) -> Result<Data, Error>
+where
+	'b: 'a,
+{
+	if let [f, x0] = args {
+		let (shape, x0) = Shape::read(x0)?;
+		let objective = Objective::new(f, x0.len(), sign, ctx)?;
+
+		let x = bfgs(
+			&mut |x, ctx| objective.value(x, ctx),
+			&mut |x, ctx| objective.gradient(x, ctx),
+			x0,
+			ctx,
+		)?;
+
+		return Ok(shape.data(&x));
+	}
+
+	let [f, lower, upper] = args else {
+		return Err(Error::LogicError(
+			"expected a function followed by a starting point, or by its lower and upper bounds"
+				.to_string(),
+		));
+	};
+
+	let (shape, lower) = Shape::read(lower)?;
+	let (_, upper) = Shape::read(upper)?;
+
+	if lower.len() != upper.len() {
+		return Err(
+			ShapeError::new(
+				(1, lower.len()),
+				(1, upper.len()),
+				"there must be an upper bound for every lower bound",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	if lower.iter().zip(&upper).any(|(lower, upper)| lower > upper) {
+		return Err(Error::LogicError(
+			"lower bounds must not be above the upper bounds".to_string(),
+		));
+	}
+
+	let objective = Objective::new(f, lower.len(), sign, ctx)?;
+
+	if let ([a], [b]) = (lower.as_slice(), upper.as_slice()) {
+		return Ok(shape.data(&[golden_section(&objective, *a, *b, ctx)?]));
+	}
+
+	// Each variable is searched over without bounds as `u` where
+	// x = lower + (upper - lower)(1 + sin u) / 2, starting from the middle
+	let half: Vec<Decimal> = lower
+		.iter()
+		.zip(&upper)
+		.map(|(lower, upper)| (upper - lower) / Decimal::TWO)
+		.collect();
+
+	let point = |u: &[Decimal]| -> Vec<Decimal> {
+		u.iter()
+			.zip(&lower)
+			.zip(&half)
+			.map(|((&u, lower), half)| lower + half * (Decimal::ONE + sin_cos(u).0))
+			.collect()
+	};
+
+	let u = bfgs(
+		&mut |u, ctx| objective.value(&point(u), ctx),
+		&mut |u, ctx| {
+			let gradient = objective.gradient(&point(u), ctx)?;
+
+			Ok(
+				gradient
+					.iter()
+					.zip(u)
+					.zip(&half)
+					.map(|((g, &u), half)| g * half * sin_cos(u).1)
+					.collect(),
+			)
+		},
+		vec![Decimal::ZERO; lower.len()],
+		ctx,
+	)?;
+
+	Ok(shape.data(&point(&u)))
+}
+
+/// Point at which a function is lowest, searched for from a starting point or
+/// between lower and upper bounds.
+pub fn minimize(args: &[Data], ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	optimize(args, Decimal::ONE, ctx)
+}
+
+/// Point at which a function is highest, searched for from a starting point or
+/// between lower and upper bounds.
+pub fn maximize(args: &[Data], ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	optimize(args, Decimal::NEGATIVE_ONE, ctx)
+}
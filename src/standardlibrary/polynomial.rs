@@ -0,0 +1,338 @@
+use rust_decimal::Decimal;
+
+use crate::{
+	errors::{Error, ShapeError, TypeError},
+	expr::Expression,
+	interpreter::InterpreterContext,
+	polynomial::Polynomial,
+	simplify::{LARGEST_POWER, Ratio},
+	standardlibrary::{
+		calculus::{symbolic, variables},
+		operators,
+	},
+	token::Token,
+	types::{Data, DataType},
+};
+
+#[inline(always)]
+fn polynomial(p: &Data) -> Result<&Polynomial, Error> {
+	match p {
+		Data::Polynomial(p) => Ok(p),
+		_ => Err(TypeError::new(DataType::Polynomial, p.ty(), 0..0).to_error()),
+	}
+}
+
+/// Exact coefficient of a polynomial from a real number
+#[inline(always)]
+fn coefficient(c: &Data) -> Result<Ratio, Error> {
+	match c {
+		Data::Number(a, b) if b.is_zero() => Ok(Ratio::new(*a, Decimal::ONE).unwrap()),
+		Data::Number(..) => Err(Error::LogicError(
+			"polynomials can only have real coefficients".to_string(),
+		)),
+		_ => Err(TypeError::new(DataType::Number, c.ty(), 0..0).to_error()),
+	}
+}
+
+#[inline(always)]
+fn data(c: Ratio) -> Data {
+	Data::new_real(c.value().normalize())
+}
+
+/// The single variable given for a polynomial
+#[inline(always)]
+fn variable(x: &Data) -> Result<String, Error> {
+	match &variables(x)?[..] {
+		[x] => Ok(x.clone()),
+		_ => Err(Error::LogicError(
+			"expected a single variable for the polynomial".to_string(),
+		)),
+	}
+}
+
+/// Both sides of an operator as polynomials in the same variable, a number
+/// standing for a constant polynomial
+fn operands(lhd: &Data, rhd: &Data) -> Result<(Polynomial, Polynomial), Error> {
+	let x = match (lhd, rhd) {
+		(Data::Polynomial(p), _) | (_, Data::Polynomial(p)) => p.variable().to_string(),
+		_ => return Err(TypeError::new(DataType::Polynomial, lhd.ty(), 0..0).to_error()),
+	};
+
+	let operand = |data: &Data| match data {
+		Data::Polynomial(p) if p.variable() == x => Ok(p.clone()),
+		Data::Polynomial(p) => Err(Error::LogicError(format!(
+			"expected polynomials in the same variable, found `{x}` and `{}`",
+			p.variable()
+		))),
+		data => Ok(Polynomial::constant(&x, coefficient(data)?)),
+	};
+
+	Ok((operand(lhd)?, operand(rhd)?))
+}
+
+/// Builds a polynomial from its coefficients from the highest power down,
+/// given as a list or a vector, or from an expression. Coefficients are in
+/// `x` unless another variable is given.
+pub fn poly(args: &[Data], ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	let x = args.get(1).map(variable).transpose()?;
+
+	let coefficients = match &args[0] {
+		Data::List(list) => list.clone(),
+		Data::Matrix(matrix) => {
+			let (rows, cols) = matrix.shape();
+
+			if rows != 1 && cols != 1 {
+				return Err(
+					ShapeError::new(
+						(1, rows * cols),
+						(rows, cols),
+						"coefficients of a polynomial must be a row or column vector",
+						0..0,
+					)
+					.to_error(),
+				);
+			}
+
+			matrix.elements().iter().map(|&z| Data::from(z)).collect()
+		}
+		Data::Polynomial(p) => {
+			return Ok(Data::Polynomial(Polynomial::from_expression(
+				&p.expression(),
+				&x.unwrap_or(p.variable().to_string()),
+			)?));
+		}
+		f => {
+			let (expression, variables) = symbolic(f, ctx)?;
+
+			let x = match (x, &variables[..]) {
+				(Some(x), _) => x,
+				(None, []) => "x".to_string(),
+				(None, [x]) => x.clone(),
+				(None, _) => {
+					return Err(Error::LogicError(format!(
+						"expected a polynomial of one variable, found {}",
+						variables.join(", ")
+					)));
+				}
+			};
+
+			return Ok(Data::Polynomial(Polynomial::from_expression(
+				&expression,
+				&x,
+			)?));
+		}
+	};
+
+	Ok(Data::Polynomial(Polynomial::new(
+		&x.unwrap_or("x".to_string()),
+		coefficients
+			.iter()
+			.map(coefficient)
+			.collect::<Result<_, _>>()?,
+	)))
+}
+
+#[inline(always)]
+pub fn degree(p: &Data) -> Result<Data, Error> {
+	Ok(Data::new_real(Decimal::from(polynomial(p)?.degree())))
+}
+
+/// Coefficients from the highest power down
+#[inline(always)]
+pub fn coeffs(p: &Data) -> Result<Data, Error> {
+	Ok(Data::List(
+		polynomial(p)?
+			.coefficients()
+			.iter()
+			.map(|&c| data(c))
+			.collect(),
+	))
+}
+
+#[inline(always)]
+pub fn add(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	let (f, g) = operands(lhd, rhd)?;
+
+	Ok(Data::Polynomial(f.add(&g)))
+}
+
+#[inline(always)]
+pub fn sub(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	let (f, g) = operands(lhd, rhd)?;
+
+	Ok(Data::Polynomial(f.add(&g.neg())))
+}
+
+#[inline(always)]
+pub fn mul(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	let (f, g) = operands(lhd, rhd)?;
+
+	Ok(Data::Polynomial(f.mul(&g)))
+}
+
+/// Quotient of polynomials which divide exactly, see `divmod` for those which
+/// leave a remainder
+pub fn div(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	let (f, g) = operands(lhd, rhd)?;
+
+	match f.divide(&g) {
+		Some((quotient, remainder)) if remainder.is_zero() => Ok(Data::Polynomial(quotient)),
+		Some(_) => Err(Error::LogicError(format!(
+			"`{}` does not divide `{}` exactly, use divmod for the quotient and remainder",
+			g.expression(),
+			f.expression()
+		))),
+		None => Err(Error::LogicError(
+			"division by the zero polynomial".to_string(),
+		)),
+	}
+}
+
+/// Remainder of long division
+#[inline(always)]
+pub fn rem(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	let Data::List(division) = divmod(lhd, rhd)? else {
+		unreachable!()
+	};
+
+	Ok(division[1].clone())
+}
+
+/// Raises a polynomial to a non-negative integer power
+pub fn pow(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	let f = polynomial(lhd)?;
+
+	match coefficient(rhd)?.integer() {
+		Some(n) if (0..=LARGEST_POWER).contains(&n) => Ok(Data::Polynomial(f.pow(n as u32))),
+		_ => Err(Error::LogicError(format!(
+			"polynomials can only be raised to integer powers from 0 to {LARGEST_POWER}"
+		))),
+	}
+}
+
+/// Quotient and remainder of long division, as a list of both
+pub fn divmod(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	let (f, g) = operands(lhd, rhd)?;
+
+	let Some((quotient, remainder)) = f.divide(&g) else {
+		return Err(Error::LogicError(
+			"division by the zero polynomial".to_string(),
+		));
+	};
+
+	Ok(Data::List(vec![
+		Data::Polynomial(quotient),
+		Data::Polynomial(remainder),
+	]))
+}
+
+/// Greatest common divisor with a leading coefficient of one
+#[inline(always)]
+pub fn gcd(lhd: &Data, rhd: &Data) -> Result<Data, Error> {
+	let (f, g) = operands(lhd, rhd)?;
+
+	Ok(Data::Polynomial(f.gcd(&g)))
+}
+
+/// Value at a number or another polynomial, or the expression with the
+/// variable replaced when given a name or an expression. The value can also be
+/// bound to the variable, eg eval(p, x = 2).
+pub fn eval(p: &Data, value: &Data, ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	let f = polynomial(p)?;
+
+	let value = match value {
+		Data::Expression(Expression::Binary(x, Token::Eq, value, _)) => {
+			if **x != Expression::Identifier(f.variable().to_string()) {
+				return Err(Error::LogicError(format!(
+					"expected a value for `{}`, found one for `{x}`",
+					f.variable()
+				)));
+			}
+
+			value.as_ref().clone().evaluate(ctx, 0..0)?
+		}
+		value => value.clone(),
+	};
+
+	if matches!(value, Data::Ident(..) | Data::Expression(..))
+		&& let Some(value) = Expression::from_data(&value)
+	{
+		return f
+			.expression()
+			.substitute(&[(f.variable().to_string(), value)])
+			.evaluate(ctx, 0..0);
+	}
+
+	f.coefficients()
+		.iter()
+		.try_fold(Data::new_zero(), |sum, &c| {
+			operators::add(&operators::mul(&sum, &value)?, &data(c))
+		})
+}
+
+/// The `n`-th derivative with respect to a variable, the first when no order
+/// is given and zero with respect to any other variable
+pub fn derivative(args: &[Data]) -> Result<Data, Error> {
+	let f = polynomial(&args[0])?;
+
+	let order = match args.get(2) {
+		Some(n) => match coefficient(n)?.integer() {
+			Some(n) if n >= 0 => n,
+			_ => {
+				return Err(Error::LogicError(
+					"expected a non-negative integer order of the derivative".to_string(),
+				));
+			}
+		},
+		None => 1,
+	};
+
+	if args
+		.get(1)
+		.map(variable)
+		.transpose()?
+		.is_some_and(|x| x != f.variable())
+	{
+		return Ok(Data::Polynomial(Polynomial::constant(
+			f.variable(),
+			Ratio::ZERO,
+		)));
+	}
+
+	Ok(Data::Polynomial(
+		(0..order).fold(f.clone(), |f, _| f.derivative()),
+	))
+}
+
+/// Antiderivative without a constant of integration, or the exact definite
+/// integral between two bounds
+pub fn integrate(args: &[Data]) -> Result<Data, Error> {
+	let f = polynomial(&args[0])?;
+	let antiderivative = f.integral();
+
+	match args {
+		[_] => Ok(Data::Polynomial(antiderivative)),
+		[_, x] if variable(x)? == f.variable() => Ok(Data::Polynomial(antiderivative)),
+		[_, x] => Err(Error::LogicError(format!(
+			"expected to integrate with respect to `{}`, found `{}`",
+			f.variable(),
+			variable(x)?
+		))),
+		[_, a, b, ..] => {
+			let (a, b) = (coefficient(a)?, coefficient(b)?);
+
+			Ok(data(
+				antiderivative.value(b).add(antiderivative.value(a).neg()),
+			))
+		}
+		[] => unreachable!(),
+	}
+}
+
+/// Distinct roots, exact for factors up to quadratics, see `Expression::solve`
+#[inline(always)]
+pub fn roots(p: &Data) -> Result<Data, Error> {
+	let f = polynomial(p)?;
+
+	Ok(Data::List(f.expression().solve(f.variable())?))
+}
@@ -0,0 +1,637 @@
+use rust_decimal::{Decimal, MathematicalOps, prelude::ToPrimitive};
+
+use crate::{
+	errors::{Error, TypeError, ValueError},
+	interpreter::InterpreterContext,
+	matrix::{Complex, Matrix},
+	standardlibrary::{
+		linalg::shape,
+		math::{self, LN_SQRT_TAU, exponential},
+	},
+	types::{Data, DataType},
+};
+
+/// Relative change below which series and continued fractions have converged
+const TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 26);
+
+/// Stands in for zero in continued fractions so they never divide by it
+const TINY: Decimal = Decimal::from_parts(1, 0, 0, false, 25);
+
+const ITERATIONS: usize = 10_000;
+
+/// Exponents here are logarithms of probabilities and densities, which never
+/// grow large enough to overflow.
+#[inline(always)]
+fn exp(x: Decimal) -> Decimal {
+	exponential(x).unwrap()
+}
+
+/// Square of the standard score of `x`, or none so far out in the tails that it
+/// is too large to hold, where the normal density is zero.
+#[inline(always)]
+fn standard_square(x: Decimal, mu: Decimal, sigma: Decimal) -> Option<Decimal> {
+	let z = x.checked_sub(mu)?.checked_div(sigma)?;
+
+	z.checked_mul(z)
+}
+
+#[inline(always)]
+fn ln_gamma(x: Decimal) -> Decimal {
+	math::ln_gamma(Complex::real(x)).re
+}
+
+/// Regularized upper incomplete gamma function `Q(a, x)`
+#[inline(always)]
+fn gamma_q(a: Decimal, x: Decimal) -> Decimal {
+	if x <= Decimal::ZERO {
+		return Decimal::ONE;
+	}
+
+	let front = exp(a * x.ln() - x - ln_gamma(a));
+
+	if x < a + Decimal::ONE {
+		let (mut term, mut sum) = (Decimal::ONE / a, Decimal::ONE / a);
+
+		for n in 1..ITERATIONS {
+			term *= x / (a + Decimal::from(n));
+			sum += term;
+
+			if term.abs() <= sum.abs() * TOLERANCE {
+				break;
+			}
+		}
+
+		return Decimal::ONE - front * sum;
+	}
+
+	// Lentz's method on the continued fraction
+	let mut b = x + Decimal::ONE - a;
+	let mut c = Decimal::ONE / TINY;
+	let mut d = Decimal::ONE / b;
+	let mut h = d;
+
+	for i in 1..ITERATIONS {
+		let i = Decimal::from(i);
+		let an = -i * (i - a);
+
+		b += Decimal::TWO;
+		d = an * d + b;
+
+		if d.abs() < TINY {
+			d = TINY;
+		}
+
+		c = b + an / c;
+
+		if c.abs() < TINY {
+			c = TINY;
+		}
+
+		d = Decimal::ONE / d;
+
+		let delta = d * c;
+		h *= delta;
+
+		if (delta - Decimal::ONE).abs() <= TOLERANCE {
+			break;
+		}
+	}
+
+	front * h
+}
+
+/// Continued fraction of the incomplete beta function
+#[inline(always)]
+fn beta_fraction(a: Decimal, b: Decimal, x: Decimal) -> Decimal {
+	let guard = |v: Decimal| if v.abs() < TINY { TINY } else { v };
+
+	let mut c = Decimal::ONE;
+	let mut d = Decimal::ONE / guard(Decimal::ONE - (a + b) * x / (a + Decimal::ONE));
+	let mut h = d;
+
+	for m in 1..ITERATIONS {
+		let m = Decimal::from(m);
+		let m2 = m * Decimal::TWO;
+
+		let even = m * (b - m) * x / ((a - Decimal::ONE + m2) * (a + m2));
+
+		d = Decimal::ONE / guard(Decimal::ONE + even * d);
+		c = guard(Decimal::ONE + even / c);
+		h *= d * c;
+
+		let odd = -(a + m) * (a + b + m) * x / ((a + m2) * (a + Decimal::ONE + m2));
+
+		d = Decimal::ONE / guard(Decimal::ONE + odd * d);
+		c = guard(Decimal::ONE + odd / c);
+
+		let delta = d * c;
+		h *= delta;
+
+		if (delta - Decimal::ONE).abs() <= TOLERANCE {
+			break;
+		}
+	}
+
+	h
+}
+
+/// Regularized incomplete beta function `I_x(a, b)`
+#[inline(always)]
+fn beta_regularized(a: Decimal, b: Decimal, x: Decimal) -> Decimal {
+	if x <= Decimal::ZERO {
+		return Decimal::ZERO;
+	}
+
+	if x >= Decimal::ONE {
+		return Decimal::ONE;
+	}
+
+	let front =
+		exp(ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (Decimal::ONE - x).ln());
+
+	// The fraction converges quickly only on one side of the mean
+	if x < (a + Decimal::ONE) / (a + b + Decimal::TWO) {
+		front * beta_fraction(a, b, x) / a
+	} else {
+		Decimal::ONE - front * beta_fraction(b, a, Decimal::ONE - x) / b
+	}
+}
+
+/// Distributions understood by `pdf`, `cdf` and `quantile`
+enum Distribution {
+	Normal { mu: Decimal, sigma: Decimal },
+	Uniform { a: Decimal, b: Decimal },
+	Binomial { n: Decimal, p: Decimal },
+	Poisson { lambda: Decimal },
+	Exponential { lambda: Decimal },
+	Student { nu: Decimal },
+}
+
+impl Distribution {
+	#[inline(always)]
+	fn new(name: &Data, params: &[Data]) -> Result<Self, Error> {
+		let Data::Ident(name) = name else {
+			return Err(TypeError::new(DataType::Ident, name.ty(), 0..0).to_error());
+		};
+
+		let params = params
+			.iter()
+			.map(|param| match param {
+				Data::Number(x, y) if y.is_zero() => Ok(*x),
+				Data::Number(..) => Err(Error::LogicError(
+					"parameters of a distribution must be real numbers".to_string(),
+				)),
+				_ => Err(TypeError::new(DataType::Number, param.ty(), 0..0).to_error()),
+			})
+			.collect::<Result<Vec<Decimal>, Error>>()?;
+
+		let invalid = |requirement: &str| {
+			Err(Error::LogicError(format!(
+				"parameters of `{name}` must satisfy {requirement}"
+			)))
+		};
+
+		let distribution = match (name.as_str(), params.as_slice()) {
+			("normal", []) => Self::Normal {
+				mu: Decimal::ZERO,
+				sigma: Decimal::ONE,
+			},
+			("normal", &[mu, sigma]) => {
+				if sigma <= Decimal::ZERO {
+					return invalid("sigma > 0");
+				}
+
+				Self::Normal { mu, sigma }
+			}
+			("uniform", []) => Self::Uniform {
+				a: Decimal::ZERO,
+				b: Decimal::ONE,
+			},
+			("uniform", &[a, b]) => {
+				if b <= a {
+					return invalid("a < b");
+				}
+
+				Self::Uniform { a, b }
+			}
+			("binomial", &[n, p]) => {
+				if n.is_sign_negative() || !n.fract().is_zero() || p < Decimal::ZERO || p > Decimal::ONE {
+					return invalid("n being a non negative integer and 0 <= p <= 1");
+				}
+
+				Self::Binomial { n, p }
+			}
+			("poisson", &[lambda]) => {
+				if lambda <= Decimal::ZERO {
+					return invalid("lambda > 0");
+				}
+
+				Self::Poisson { lambda }
+			}
+			("exponential", []) => Self::Exponential {
+				lambda: Decimal::ONE,
+			},
+			("exponential", &[lambda]) => {
+				if lambda <= Decimal::ZERO {
+					return invalid("lambda > 0");
+				}
+
+				Self::Exponential { lambda }
+			}
+			("student", &[nu]) => {
+				if nu <= Decimal::ZERO {
+					return invalid("nu > 0");
+				}
+
+				Self::Student { nu }
+			}
+			("normal" | "uniform" | "binomial" | "poisson" | "exponential" | "student", _) => {
+				return Err(Error::LogicError(format!(
+					"wrong number of parameters for `{name}`"
+				)));
+			}
+			_ => {
+				return Err(Error::LogicError(format!(
+					"unknown distribution `{name}`, expected normal, uniform, binomial, poisson, exponential or student"
+				)));
+			}
+		};
+
+		Ok(distribution)
+	}
+
+	/// Density of continuous distributions, the probability of exactly `x` for
+	/// discrete ones.
+	#[inline(always)]
+	fn pdf(&self, x: Decimal) -> Decimal {
+		match *self {
+			Self::Normal { mu, sigma } => match standard_square(x, mu, sigma) {
+				Some(square) => exp(-square / Decimal::TWO - LN_SQRT_TAU) / sigma,
+				None => Decimal::ZERO,
+			},
+			Self::Uniform { a, b } => {
+				if x < a || x > b {
+					Decimal::ZERO
+				} else {
+					Decimal::ONE / (b - a)
+				}
+			}
+			Self::Binomial { n, p } => {
+				if x.is_sign_negative() || !x.fract().is_zero() || x > n {
+					return Decimal::ZERO;
+				}
+
+				if p.is_zero() || p == Decimal::ONE {
+					let certain = if p.is_zero() { Decimal::ZERO } else { n };
+
+					return if x == certain {
+						Decimal::ONE
+					} else {
+						Decimal::ZERO
+					};
+				}
+
+				exp(
+					ln_gamma(n + Decimal::ONE) - ln_gamma(x + Decimal::ONE) - ln_gamma(n - x + Decimal::ONE)
+						+ x * p.ln()
+						+ (n - x) * (Decimal::ONE - p).ln(),
+				)
+			}
+			Self::Poisson { lambda } => {
+				if x.is_sign_negative() || !x.fract().is_zero() {
+					return Decimal::ZERO;
+				}
+
+				exp(x * lambda.ln() - lambda - ln_gamma(x + Decimal::ONE))
+			}
+			Self::Exponential { lambda } => {
+				if x.is_sign_negative() {
+					Decimal::ZERO
+				} else {
+					lambda * exp(-lambda * x)
+				}
+			}
+			Self::Student { nu } => {
+				let half = Decimal::new(5, 1);
+
+				if x.checked_mul(x).is_none() {
+					return Decimal::ZERO;
+				}
+
+				exp(
+					ln_gamma((nu + Decimal::ONE) * half)
+						- ln_gamma(nu * half)
+						- (nu * Decimal::PI).ln() * half
+						- (nu + Decimal::ONE) * half * (Decimal::ONE + x * x / nu).ln(),
+				)
+			}
+		}
+	}
+
+	/// Probability of a value no greater than `x`
+	#[inline(always)]
+	fn cdf(&self, x: Decimal) -> Decimal {
+		let half = Decimal::new(5, 1);
+
+		match *self {
+			Self::Normal { mu, sigma } => {
+				let tail = match standard_square(x, mu, sigma) {
+					Some(square) => half * gamma_q(half, square * half),
+					None => Decimal::ZERO,
+				};
+
+				if x < mu { tail } else { Decimal::ONE - tail }
+			}
+			Self::Uniform { a, b } => ((x - a) / (b - a)).clamp(Decimal::ZERO, Decimal::ONE),
+			Self::Binomial { n, p } => {
+				let k = x.floor();
+
+				if k.is_sign_negative() {
+					Decimal::ZERO
+				} else if k >= n {
+					Decimal::ONE
+				} else {
+					beta_regularized(n - k, k + Decimal::ONE, Decimal::ONE - p)
+				}
+			}
+			Self::Poisson { lambda } => {
+				let k = x.floor();
+
+				if k.is_sign_negative() {
+					Decimal::ZERO
+				} else {
+					gamma_q(k + Decimal::ONE, lambda)
+				}
+			}
+			Self::Exponential { lambda } => {
+				if x.is_sign_negative() {
+					Decimal::ZERO
+				} else {
+					Decimal::ONE - exp(-lambda * x)
+				}
+			}
+			Self::Student { nu } => {
+				let tail = match x.checked_mul(x).and_then(|square| square.checked_add(nu)) {
+					Some(denominator) => half * beta_regularized(nu * half, half, nu / denominator),
+					None => Decimal::ZERO,
+				};
+
+				if x.is_sign_negative() {
+					tail
+				} else {
+					Decimal::ONE - tail
+				}
+			}
+		}
+	}
+
+	/// Smallest value whose cumulative probability reaches `p`
+	#[inline(always)]
+	fn quantile(&self, p: Decimal) -> Result<Decimal, Error> {
+		let infinite = || {
+			Err(Error::LogicError(
+				"quantile is infinite for this probability".to_string(),
+			))
+		};
+
+		match *self {
+			Self::Uniform { a, b } => Ok(a + p * (b - a)),
+			Self::Exponential { lambda } => {
+				if p == Decimal::ONE {
+					return infinite();
+				}
+
+				Ok(-(Decimal::ONE - p).ln() / lambda)
+			}
+			Self::Binomial { n, .. } => Ok(self.search(p, n)),
+			Self::Poisson { lambda } => {
+				if p == Decimal::ONE {
+					return infinite();
+				}
+
+				let mut upper = lambda.ceil() + Decimal::ONE;
+
+				while self.cdf(upper) < p {
+					upper *= Decimal::TWO;
+				}
+
+				Ok(self.search(p, upper))
+			}
+			Self::Normal { .. } | Self::Student { .. } => {
+				if p.is_zero() || p == Decimal::ONE {
+					return infinite();
+				}
+
+				Ok(self.invert(p))
+			}
+		}
+	}
+
+	/// Bisects the integers from zero to `upper` for the smallest whose
+	/// cumulative probability reaches `p`
+	#[inline(always)]
+	fn search(&self, p: Decimal, upper: Decimal) -> Decimal {
+		let (mut lower, mut upper) = (Decimal::ZERO, upper);
+
+		if self.cdf(lower) >= p {
+			return lower;
+		}
+
+		while upper - lower > Decimal::ONE {
+			let middle = ((lower + upper) / Decimal::TWO).floor();
+
+			if self.cdf(middle) >= p {
+				upper = middle;
+			} else {
+				lower = middle;
+			}
+		}
+
+		upper
+	}
+
+	/// Solves `cdf(x) = p` for a continuous distribution by Newton's method,
+	/// falling back to bisection whenever a step leaves the bracket.
+	#[inline(always)]
+	fn invert(&self, p: Decimal) -> Decimal {
+		let (centre, scale) = match *self {
+			Self::Normal { mu, sigma } => (mu, sigma),
+			_ => (Decimal::ZERO, Decimal::ONE),
+		};
+
+		let mut width = scale;
+
+		while self.cdf(centre - width) > p || self.cdf(centre + width) < p {
+			width *= Decimal::TWO;
+		}
+
+		let (mut lower, mut upper) = (centre - width, centre + width);
+		let mut x = centre;
+
+		for _ in 0..200 {
+			let error = self.cdf(x) - p;
+
+			if error.is_sign_negative() {
+				lower = x;
+			} else {
+				upper = x;
+			}
+
+			let density = self.pdf(x);
+
+			let next = if density.is_zero() {
+				(lower + upper) / Decimal::TWO
+			} else {
+				let next = x - error / density;
+
+				if next <= lower || next >= upper {
+					(lower + upper) / Decimal::TWO
+				} else {
+					next
+				}
+			};
+
+			let converged = (next - x).abs() <= TOLERANCE * x.abs().max(scale);
+
+			x = next;
+
+			if converged {
+				break;
+			}
+		}
+
+		x
+	}
+}
+
+#[inline(always)]
+fn real(x: Decimal) -> Data {
+	Data::new_real(x.round_sf(20).unwrap_or(x).normalize())
+}
+
+/// Reads the real number a distribution is evaluated at
+#[inline(always)]
+fn point(x: &Data) -> Result<Decimal, Error> {
+	match x {
+		Data::Number(x, y) if y.is_zero() => Ok(*x),
+		Data::Number(..) => Err(Error::LogicError(
+			"distributions can only be evaluated at real numbers".to_string(),
+		)),
+		_ => Err(TypeError::new(DataType::Number, x.ty(), 0..0).to_error()),
+	}
+}
+
+/// Probability density (or mass) of the distribution named by the first
+/// argument, which is followed by the point and then its parameters.
+pub fn pdf(args: &[Data]) -> Result<Data, Error> {
+	let distribution = Distribution::new(&args[0], &args[2..])?;
+
+	Ok(real(distribution.pdf(point(&args[1])?)))
+}
+
+pub fn cdf(args: &[Data]) -> Result<Data, Error> {
+	let distribution = Distribution::new(&args[0], &args[2..])?;
+
+	Ok(real(distribution.cdf(point(&args[1])?)))
+}
+
+pub fn quantile(args: &[Data]) -> Result<Data, Error> {
+	let distribution = Distribution::new(&args[0], &args[2..])?;
+
+	let p = point(&args[1])?;
+
+	if p < Decimal::ZERO || p > Decimal::ONE {
+		return Err(Error::LogicError(
+			"quantile must be a real number from 0 to 1".to_string(),
+		));
+	}
+
+	Ok(real(distribution.quantile(p)?))
+}
+
+/// Reads an integer argument of a random number function
+#[inline(always)]
+fn integer(d: &Data) -> Result<Decimal, Error> {
+	match d {
+		Data::Number(a, b) if b.is_zero() && a.fract().is_zero() => Ok(*a),
+		Data::Number(..) => Err(Error::LogicError("expected an integer".to_string())),
+		_ => Err(TypeError::new(DataType::Number, d.ty(), 0..0).to_error()),
+	}
+}
+
+/// Restarts the random number generator from `n`, making every random number
+/// after it reproducible.
+pub fn seed(n: &Data, ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	let seed = integer(n)?;
+
+	*ctx.2 = crate::random::Random::new(seed.to_i128().unwrap() as u64);
+
+	Ok(Data::new_real(seed))
+}
+
+/// Number from `[0, 1)`, or a matrix of them when given its dimensions
+pub fn rand(args: &[Data], ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	sample(args, ctx, |random| random.uniform().normalize())
+}
+
+/// Number from the standard normal distribution, or a matrix of them when given
+/// its dimensions
+pub fn randn(args: &[Data], ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	sample(args, ctx, |random| {
+		// Box-Muller transform, the first number is kept away from zero
+		let (u, v) = (Decimal::ONE - random.uniform(), random.uniform());
+
+		let radius = (Decimal::from(-2) * u.ln()).sqrt().unwrap();
+
+		(radius * (Decimal::TWO_PI * v).cos()).normalize()
+	})
+}
+
+#[inline(always)]
+fn sample(
+	args: &[Data],
+	ctx: &mut InterpreterContext,
+	mut f: impl FnMut(&mut crate::random::Random) -> Decimal,
+) -> Result<Data, Error> {
+	match args {
+		[] => Ok(Data::new_real(f(ctx.2))),
+		[m, n] => {
+			let (m, n) = shape(m, n)?;
+
+			Ok(Data::Matrix(Matrix::from_fn(m, n, |_, _| {
+				Complex::real(f(ctx.2))
+			})))
+		}
+		_ => Err(Error::LogicError(
+			"expected no arguments or the dimensions of a matrix".to_string(),
+		)),
+	}
+}
+
+/// Integer from `a` to `b`, both inclusive
+pub fn randint(a: &Data, b: &Data, ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	let (a, b) = (integer(a)?, integer(b)?);
+
+	if a > b {
+		return Err(Error::LogicError(
+			"lower bound of randint must not exceed the upper bound".to_string(),
+		));
+	}
+
+	let Some(span) = b
+		.checked_sub(a)
+		.and_then(|span| span.checked_add(Decimal::ONE))
+		.and_then(|span| span.to_u64())
+	else {
+		return Err(
+			ValueError::new(
+				format!("range of randint from `{a}` to `{b}` is too wide"),
+				"randint picks from at most 2^64 integers",
+				0..0,
+			)
+			.to_error(),
+		);
+	};
+
+	Ok(Data::new_real(a + Decimal::from(ctx.2.below(span))))
+}
@@ -0,0 +1,215 @@
+use rust_decimal::Decimal;
+
+use crate::{
+	errors::{Error, IndexError, ShapeError, TypeError, ValueError},
+	matrix::{Complex, Matrix, SparseMatrix, length},
+	standardlibrary::linalg::dimension,
+	types::{Data, DataType},
+};
+
+/// Size of the residual relative to the right hand side at which conjugate
+/// gradient stops iterating.
+const TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 20);
+
+#[inline(always)]
+fn list(v: &Data) -> Result<&Vec<Data>, Error> {
+	match v {
+		Data::List(list) => Ok(list),
+		_ => Err(TypeError::new(DataType::List, v.ty(), 0..0).to_error()),
+	}
+}
+
+/// Reads a one based index into a dimension of length `len`
+#[inline(always)]
+fn position(d: &Data, len: usize) -> Result<usize, Error> {
+	let Data::Number(n, b) = d else {
+		return Err(TypeError::new(DataType::Number, d.ty(), 0..0).to_error());
+	};
+
+	if !b.is_zero() || !n.fract().is_zero() || *n < Decimal::ONE || *n > Decimal::from(len) {
+		return Err(IndexError::new(*n, len, 0..0).to_error());
+	}
+
+	Ok(n.to_string().parse::<usize>().unwrap() - 1)
+}
+
+#[inline(always)]
+fn sparse_matrix(v: &Data) -> Result<&SparseMatrix, Error> {
+	match v {
+		Data::Sparse(matrix) => Ok(matrix),
+		_ => Err(TypeError::new(DataType::Sparse, v.ty(), 0..0).to_error()),
+	}
+}
+
+/// Builds an `m` by `n` sparse matrix out of lists of row indices, column
+/// indices and values, values given for the same position are summed.
+#[inline(always)]
+pub fn sparse(rows: &Data, cols: &Data, values: &Data, m: &Data, n: &Data) -> Result<Data, Error> {
+	let (m, n) = (dimension(m)?, dimension(n)?);
+	let (rows, cols, values) = (list(rows)?, list(cols)?, list(values)?);
+
+	for other in [cols, values] {
+		if other.len() != rows.len() {
+			return Err(
+				ShapeError::new(
+					(1, rows.len()),
+					(1, other.len()),
+					"row indices, column indices and values must have the same length",
+					0..0,
+				)
+				.to_error(),
+			);
+		}
+	}
+
+	let triplets = rows
+		.iter()
+		.zip(cols)
+		.zip(values)
+		.map(|((i, j), z)| Ok((position(i, m)?, position(j, n)?, Complex::from_data(z)?)))
+		.collect::<Result<_, Error>>()?;
+
+	Ok(Data::Sparse(SparseMatrix::from_triplets(m, n, triplets)))
+}
+
+/// Converts a dense matrix into a sparse one holding only its non-zero elements
+#[inline(always)]
+pub fn from_dense(v: &Data) -> Result<Data, Error> {
+	let Data::Matrix(matrix) = v else {
+		return Err(TypeError::new(DataType::Matrix, v.ty(), 0..0).to_error());
+	};
+
+	Ok(Data::Sparse(SparseMatrix::from_dense(matrix)))
+}
+
+#[inline(always)]
+pub fn dense(v: &Data) -> Result<Data, Error> {
+	Ok(Data::Matrix(sparse_matrix(v)?.to_dense()))
+}
+
+/// Solves `A * X = B` for a sparse `A` by conjugate gradient, every column of
+/// `B` is solved separately.
+///
+/// Conjugate gradient only works when `A` is symmetric (or hermitian) and
+/// positive definite, any other `A` is rejected rather than solved.
+#[inline(always)]
+pub fn solve(a: &Data, b: &Data) -> Result<Data, Error> {
+	let matrix = sparse_matrix(a)?;
+	let (n, cols) = matrix.shape();
+
+	if n != cols {
+		return Err(
+			ShapeError::new(
+				(n, n),
+				(n, cols),
+				"matrix of coefficients must be square to solve a system",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	let Data::Matrix(rhs) = b else {
+		return Err(TypeError::new(DataType::Matrix, b.ty(), 0..0).to_error());
+	};
+
+	if rhs.rows() != n {
+		return Err(
+			ShapeError::new(
+				(n, rhs.cols()),
+				rhs.shape(),
+				"right hand side must have a row for every equation",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	if *matrix != matrix.map(Complex::conj).transpose() {
+		return Err(
+			ValueError::new(
+				"matrix of coefficients is not symmetric".to_string(),
+				"sparse systems are solved by conjugate gradient, which needs a symmetric positive definite matrix",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	let columns = (0..rhs.cols())
+		.map(|j| {
+			let column = rhs.iter_rows().map(|row| row[j]).collect::<Vec<Complex>>();
+
+			conjugate_gradient(matrix, &column)
+		})
+		.collect::<Result<Vec<Vec<Complex>>, Error>>()?;
+
+	Ok(Data::Matrix(Matrix::from_fn(n, rhs.cols(), |i, j| {
+		columns[j][i].clean()
+	})))
+}
+
+/// Conjugate gradient for a hermitian `a`, which only converges when `a` is
+/// also positive definite.
+fn conjugate_gradient(a: &SparseMatrix, b: &[Complex]) -> Result<Vec<Complex>, Error> {
+	let n = b.len();
+	let target = length(b) * TOLERANCE;
+
+	let mut x = vec![Complex::ZERO; n];
+	let mut r = b.to_vec();
+	let mut p = r.clone();
+	let mut residual = length(&r);
+
+	// Every step would end in the exact solution after `n` of them, the rest
+	// make up for rounding
+	for _ in 0..=10 * n {
+		if residual <= target {
+			return Ok(x);
+		}
+
+		let ap = a.mul_vector(&p);
+
+		// Both sides of the step length are scaled by the largest part of `p` so
+		// that neither is squared into nothing
+		let scale = p.iter().map(|z| z.magnitude()).max().unwrap();
+		let curvature = p.iter().zip(&ap).fold(Decimal::ZERO, |acc, (&p, &ap)| {
+			acc + ((p / Complex::real(scale)).conj() * (ap / Complex::real(scale))).re
+		});
+
+		if curvature <= Decimal::ZERO {
+			return Err(
+				ValueError::new(
+					"matrix of coefficients is not positive definite".to_string(),
+					"sparse systems are solved by conjugate gradient, which needs a symmetric positive definite matrix",
+					0..0,
+				)
+				.to_error(),
+			);
+		}
+
+		let alpha = Complex::real((residual / scale) * (residual / scale) / curvature);
+
+		for ((x, r), (&p, &ap)) in x.iter_mut().zip(r.iter_mut()).zip(p.iter().zip(&ap)) {
+			*x = *x + alpha * p;
+			*r = *r - alpha * ap;
+		}
+
+		let next = length(&r);
+		let beta = Complex::real((next / residual) * (next / residual));
+
+		for (p, &r) in p.iter_mut().zip(&r) {
+			*p = r + beta * *p;
+		}
+
+		residual = next;
+	}
+
+	Err(
+		ValueError::new(
+			"conjugate gradient did not converge".to_string(),
+			"the matrix of coefficients may be too badly conditioned to solve as a sparse system",
+			0..0,
+		)
+		.to_error(),
+	)
+}
@@ -0,0 +1,351 @@
+use rust_decimal::{Decimal, MathematicalOps, prelude::ToPrimitive};
+
+use crate::{
+	errors::{Error, ShapeError, TypeError, ValueError},
+	matrix::{Complex, Matrix},
+	standardlibrary::linalg::least_squares,
+	types::{Data, DataType},
+};
+
+/// Reads the real numbers held by a list or every element of a matrix
+#[inline(always)]
+fn samples(v: &Data) -> Result<Vec<Decimal>, Error> {
+	let real = |z: Complex| {
+		if z.is_real() {
+			Ok(z.re)
+		} else {
+			Err(
+				ValueError::new(
+					format!("`{}` is not a real number", Data::from(z)),
+					"statistics can only be computed on real numbers",
+					0..0,
+				)
+				.to_error(),
+			)
+		}
+	};
+
+	let samples = match v {
+		Data::List(list) => list
+			.iter()
+			.map(|element| real(Complex::from_data(element)?))
+			.collect::<Result<Vec<Decimal>, Error>>()?,
+		Data::Matrix(matrix) => matrix
+			.elements()
+			.iter()
+			.map(|&z| real(z))
+			.collect::<Result<Vec<Decimal>, Error>>()?,
+		_ => return Err(TypeError::new(DataType::List, v.ty(), 0..0).to_error()),
+	};
+
+	if samples.is_empty() {
+		return Err(
+			ValueError::new(
+				"statistics of no values are undefined".to_string(),
+				"statistics need at least one value",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	Ok(samples)
+}
+
+#[inline(always)]
+fn sorted(v: &Data) -> Result<Vec<Decimal>, Error> {
+	let mut samples = samples(v)?;
+
+	samples.sort();
+
+	Ok(samples)
+}
+
+/// Reads two sets of samples which are paired value by value
+#[inline(always)]
+fn paired(x: &Data, y: &Data) -> Result<(Vec<Decimal>, Vec<Decimal>), Error> {
+	let (x, y) = (samples(x)?, samples(y)?);
+
+	if x.len() != y.len() {
+		return Err(
+			ShapeError::new(
+				(1, x.len()),
+				(1, y.len()),
+				"both sets of values must have the same number of values",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	Ok((x, y))
+}
+
+#[inline(always)]
+fn count(samples: &[Decimal]) -> Decimal {
+	Decimal::from(samples.len())
+}
+
+#[inline(always)]
+fn average(samples: &[Decimal]) -> Decimal {
+	samples.iter().sum::<Decimal>() / count(samples)
+}
+
+/// Sum of products of the deviations of `x` and `y` from their means
+#[inline(always)]
+fn comoment(x: &[Decimal], y: &[Decimal]) -> Decimal {
+	let (mx, my) = (average(x), average(y));
+
+	x.iter().zip(y).map(|(x, y)| (x - mx) * (y - my)).sum()
+}
+
+/// Number of samples less one, which the sample statistics divide by
+#[inline(always)]
+fn degrees_of_freedom(samples: &[Decimal]) -> Result<Decimal, Error> {
+	if samples.len() < 2 {
+		return Err(
+			ValueError::new(
+				format!(
+					"sample statistics of {} value{} are undefined",
+					samples.len(),
+					if samples.len() == 1 { "" } else { "s" }
+				),
+				"sample statistics need at least two values",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	Ok(count(samples) - Decimal::ONE)
+}
+
+#[inline(always)]
+fn real(x: Decimal) -> Data {
+	Data::new_real(x.normalize())
+}
+
+#[inline(always)]
+pub fn mean(v: &Data) -> Result<Data, Error> {
+	Ok(real(average(&samples(v)?)))
+}
+
+#[inline(always)]
+pub fn median(v: &Data) -> Result<Data, Error> {
+	let samples = sorted(v)?;
+	let n = samples.len();
+
+	Ok(real(if n % 2 == 1 {
+		samples[n / 2]
+	} else {
+		(samples[n / 2 - 1] + samples[n / 2]) / Decimal::TWO
+	}))
+}
+
+/// Most frequent value, the smallest one when several are equally frequent.
+#[inline(always)]
+pub fn mode(v: &Data) -> Result<Data, Error> {
+	let samples = sorted(v)?;
+
+	let (mut mode, mut most) = (samples[0], 0);
+	let mut i = 0;
+
+	while i < samples.len() {
+		let run = samples[i..]
+			.iter()
+			.take_while(|&&x| x == samples[i])
+			.count();
+
+		if run > most {
+			(mode, most) = (samples[i], run);
+		}
+
+		i += run;
+	}
+
+	Ok(real(mode))
+}
+
+/// Sample variance, dividing by one less than the number of values.
+#[inline(always)]
+pub fn variance(v: &Data) -> Result<Data, Error> {
+	let samples = samples(v)?;
+
+	Ok(real(
+		comoment(&samples, &samples) / degrees_of_freedom(&samples)?,
+	))
+}
+
+/// Population variance, dividing by the number of values.
+#[inline(always)]
+pub fn pvariance(v: &Data) -> Result<Data, Error> {
+	let samples = samples(v)?;
+
+	Ok(real(comoment(&samples, &samples) / count(&samples)))
+}
+
+#[inline(always)]
+pub fn stddev(v: &Data) -> Result<Data, Error> {
+	Ok(real(variance(v)?.to_real().sqrt().unwrap()))
+}
+
+#[inline(always)]
+pub fn pstddev(v: &Data) -> Result<Data, Error> {
+	Ok(real(pvariance(v)?.to_real().sqrt().unwrap()))
+}
+
+/// Value below which a fraction `p` of the values lie, interpolating linearly
+/// between the closest two.
+#[inline(always)]
+pub fn quantile(v: &Data, p: &Data) -> Result<Data, Error> {
+	let samples = sorted(v)?;
+
+	let p = match p {
+		Data::Number(p, b) if b.is_zero() && *p >= Decimal::ZERO && *p <= Decimal::ONE => *p,
+		Data::Number(..) => {
+			return Err(
+				ValueError::new(
+					format!("`{p}` is not a quantile"),
+					"quantiles are real numbers from 0 to 1",
+					0..0,
+				)
+				.to_error(),
+			);
+		}
+		_ => return Err(TypeError::new(DataType::Number, p.ty(), 0..0).to_error()),
+	};
+
+	let h = (count(&samples) - Decimal::ONE) * p;
+	let lower = h.floor().to_usize().unwrap();
+	let upper = (lower + 1).min(samples.len() - 1);
+
+	Ok(real(
+		samples[lower] + (h - h.floor()) * (samples[upper] - samples[lower]),
+	))
+}
+
+#[inline(always)]
+pub fn min(v: &Data) -> Result<Data, Error> {
+	Ok(real(*samples(v)?.iter().min().unwrap()))
+}
+
+#[inline(always)]
+pub fn max(v: &Data) -> Result<Data, Error> {
+	Ok(real(*samples(v)?.iter().max().unwrap()))
+}
+
+/// Sample covariance of two sets of paired values
+#[inline(always)]
+pub fn cov(x: &Data, y: &Data) -> Result<Data, Error> {
+	let (x, y) = paired(x, y)?;
+
+	Ok(real(comoment(&x, &y) / degrees_of_freedom(&x)?))
+}
+
+/// Pearson correlation coefficient of two sets of paired values
+#[inline(always)]
+pub fn corr(x: &Data, y: &Data) -> Result<Data, Error> {
+	let (x, y) = paired(x, y)?;
+
+	let (sxx, syy) = (comoment(&x, &x), comoment(&y, &y));
+
+	if sxx.is_zero() || syy.is_zero() {
+		return Err(
+			ValueError::new(
+				"correlation of a constant set of values is undefined".to_string(),
+				"correlation needs both sets of values to vary",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	Ok(real(comoment(&x, &y) / (sxx * syy).sqrt().unwrap()))
+}
+
+/// Least squares line through the points, returned as `{slope, intercept, r²}`.
+#[inline(always)]
+pub fn linreg(x: &Data, y: &Data) -> Result<Data, Error> {
+	let (x, y) = paired(x, y)?;
+
+	let (sxx, syy, sxy) = (comoment(&x, &x), comoment(&y, &y), comoment(&x, &y));
+
+	if sxx.is_zero() {
+		return Err(
+			ValueError::new(
+				"a line can not be fitted when every x value is the same".to_string(),
+				"fitting a line needs at least two different x values",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	let slope = sxy / sxx;
+	let intercept = average(&y) - slope * average(&x);
+
+	// Points which all share a y value lie exactly on the horizontal line
+	let r2 = if syy.is_zero() {
+		Decimal::ONE
+	} else {
+		(sxy / sxx) * (sxy / syy)
+	};
+
+	Ok(Data::List(vec![real(slope), real(intercept), real(r2)]))
+}
+
+/// Least squares polynomial of degree `deg` through the points, returned as
+/// its coefficients from the highest power down.
+#[inline(always)]
+pub fn polyfit(x: &Data, y: &Data, deg: &Data) -> Result<Data, Error> {
+	let (x, y) = paired(x, y)?;
+
+	let deg = match deg {
+		Data::Number(n, b) if b.is_zero() && n.fract().is_zero() && !n.is_sign_negative() => *n,
+		Data::Number(..) => {
+			return Err(
+				ValueError::new(
+					format!("`{deg}` is not the degree of a polynomial"),
+					"degrees of polynomials are non negative integers",
+					0..0,
+				)
+				.to_error(),
+			);
+		}
+		_ => return Err(TypeError::new(DataType::Number, deg.ty(), 0..0).to_error()),
+	};
+
+	if Decimal::from(x.len()) <= deg {
+		return Err(
+			ValueError::new(
+				format!(
+					"fitting a polynomial of degree {deg} needs at least {} points, found {}",
+					deg + Decimal::ONE,
+					x.len()
+				),
+				"give more points or fit a polynomial of lower degree",
+				0..0,
+			)
+			.to_error(),
+		);
+	}
+
+	let deg = deg.to_usize().unwrap();
+
+	let vandermonde = Matrix::from_fn(x.len(), deg + 1, |i, j| {
+		Complex::real(x[i].powu((deg - j) as u64))
+	});
+
+	let coefficients = least_squares(
+		vandermonde,
+		&Matrix::new(y.len(), 1, y.iter().map(|&y| Complex::real(y)).collect()),
+	)?;
+
+	Ok(Data::List(
+		coefficients
+			.elements()
+			.iter()
+			.map(|z| real(z.clean().re))
+			.collect(),
+	))
+}
@@ -0,0 +1,157 @@
+use crate::{
+	errors::{Error, TypeError},
+	expr::Expression,
+	interpreter::InterpreterContext,
+	simplify::ratio,
+	solve::solve_linear,
+	standardlibrary::calculus::{self, elements, symbolic},
+	token::Token,
+	types::{Data, DataType},
+};
+
+/// Value of an expression which is a number, otherwise the expression itself
+#[inline(always)]
+fn data(expr: Expression) -> Data {
+	match ratio(&expr) {
+		Some(n) => Data::new_real(n.value().normalize()),
+		None => Data::Expression(expr),
+	}
+}
+
+#[inline(always)]
+fn variable(x: &Data) -> Result<&str, Error> {
+	match x {
+		Data::Ident(x) => Ok(x),
+		_ => Err(TypeError::new(DataType::Ident, x.ty(), 0..0).to_error()),
+	}
+}
+
+/// Canonical form of an expression, see `Expression::simplify`
+#[inline(always)]
+pub fn simplify(a: &Data, ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	Ok(data(symbolic(a, ctx)?.0.simplify()))
+}
+
+/// Canonical form of an expression also using the identities between
+/// trigonometric functions
+#[inline(always)]
+pub fn trigsimp(a: &Data, ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	Ok(data(symbolic(a, ctx)?.0.simplify_trig()))
+}
+
+#[inline(always)]
+pub fn expand(a: &Data, ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	Ok(data(symbolic(a, ctx)?.0.expand()))
+}
+
+#[inline(always)]
+pub fn factor(a: &Data, ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	Ok(data(symbolic(a, ctx)?.0.factor()?))
+}
+
+#[inline(always)]
+pub fn collect(a: &Data, x: &Data, ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	Ok(data(symbolic(a, ctx)?.0.collect(variable(x)?)))
+}
+
+/// Coefficients of a polynomial from the highest power down, in the form
+/// `roots` takes them
+#[inline(always)]
+pub fn coeffs(a: &Data, x: &Data, ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	Ok(Data::List(
+		symbolic(a, ctx)?
+			.0
+			.coefficients(variable(x)?)?
+			.into_iter()
+			.map(data)
+			.collect(),
+	))
+}
+
+/// Value of an expression with values in place of some of its variables,
+/// computed as far as the values allow
+#[inline(always)]
+fn substitute(
+	a: &Data,
+	bindings: &[(String, Expression)],
+	ctx: &mut InterpreterContext,
+) -> Result<Data, Error> {
+	symbolic(a, ctx)?.0.substitute(bindings).evaluate(ctx, 0..0)
+}
+
+/// Replaces a variable, or each of a list of variables, with a value
+pub fn subs(a: &Data, x: &Data, value: &Data, ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	let names = calculus::variables(x)?;
+
+	let values = if names.len() == 1 {
+		vec![value.clone()]
+	} else {
+		elements(value)
+	};
+
+	if names.len() != values.len() {
+		return Err(Error::LogicError(format!(
+			"expected {} values to substitute, found {}",
+			names.len(),
+			values.len()
+		)));
+	}
+
+	let bindings = names
+		.into_iter()
+		.zip(values.iter())
+		.map(|(name, value)| match Expression::from_data(value) {
+			Some(value) => Ok((name, value)),
+			None => Err(Error::LogicError(format!(
+				"`{name}` can only be replaced with a number or an expression"
+			))),
+		})
+		.collect::<Result<Vec<(String, Expression)>, Error>>()?;
+
+	substitute(a, &bindings, ctx)
+}
+
+/// Value of an expression with the values bound to names in the call, eg
+/// eval(y, x = 2), or with the current values of the names it refers to
+pub fn eval(a: &Data, bindings: &[Data], ctx: &mut InterpreterContext) -> Result<Data, Error> {
+	let bindings = bindings
+		.iter()
+		.map(|binding| match binding {
+			Data::Expression(Expression::Binary(name, Token::Eq, value, _))
+				if let Expression::Identifier(name) = name.as_ref() =>
+			{
+				Ok((name.clone(), value.as_ref().clone()))
+			}
+			_ => Err(Error::LogicError(format!(
+				"expected a value bound to a name, eg `x = 2`, found `{binding}`"
+			))),
+		})
+		.collect::<Result<Vec<(String, Expression)>, Error>>()?;
+
+	substitute(a, &bindings, ctx)
+}
+
+/// Solutions of an equation for a variable, or the values of the variables
+/// which solve a list of linear equations
+pub fn solve(
+	equations: &Data,
+	variables: &Data,
+	ctx: &mut InterpreterContext,
+) -> Result<Data, Error> {
+	match equations {
+		Data::List(..) | Data::Expression(Expression::Matrix(..)) => {
+			let equations = elements(equations)
+				.iter()
+				.map(|equation| Ok(symbolic(equation, ctx)?.0))
+				.collect::<Result<Vec<Expression>, Error>>()?;
+
+			Ok(Data::List(solve_linear(
+				&equations,
+				&calculus::variables(variables)?,
+			)?))
+		}
+		equation => Ok(Data::List(
+			symbolic(equation, ctx)?.0.solve(variable(variables)?)?,
+		)),
+	}
+}
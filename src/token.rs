@@ -30,6 +30,7 @@ pub enum Token {
 	Ident(String),
 
 	Let,
+	Sym,
 	Fn,
 	If,
 	Then,
@@ -51,9 +52,14 @@ pub enum Token {
 	Div,
 	Pow,
 	Rem,
+	DotMul,
+	DotDiv,
+	DotPow,
+	PlusMinus,
 
 	Comma,
 	Colon,
+	DotDot,
 	Semi,
 	LParen,
 	RParen,
@@ -69,6 +75,7 @@ impl Token {
 	pub fn new(token: String) -> Self {
 		match token.as_ref() {
 			"let" => Token::Let,
+			"sym" => Token::Sym,
 			"fn" => Token::Fn,
 			"if" => Token::If,
 			"then" => Token::Then,
@@ -90,9 +97,14 @@ impl Token {
 			"/" => Token::Div,
 			"^" => Token::Pow,
 			"%" => Token::Rem,
+			".*" => Token::DotMul,
+			"./" => Token::DotDiv,
+			".^" => Token::DotPow,
+			"+-" | "±" => Token::PlusMinus,
 
 			"," => Token::Comma,
 			":" => Token::Colon,
+			".." => Token::DotDot,
 			";" => Token::Semi,
 			"(" => Token::LParen,
 			")" => Token::RParen,
@@ -122,6 +134,7 @@ impl Display for Token {
 				Token::Float(n) => n.to_string(),
 				Token::Ident(ident) => ident.to_string(),
 				Token::Let => "let".to_string(),
+				Token::Sym => "sym".to_string(),
 				Token::Fn => "fn".to_string(),
 				Token::If => "if".to_string(),
 				Token::Then => "then".to_string(),
@@ -141,8 +154,13 @@ impl Display for Token {
 				Token::Div => "/".to_string(),
 				Token::Pow => "^".to_string(),
 				Token::Rem => "%".to_string(),
+				Token::DotMul => ".*".to_string(),
+				Token::DotDiv => "./".to_string(),
+				Token::DotPow => ".^".to_string(),
+				Token::PlusMinus => "±".to_string(),
 				Token::Comma => ",".to_string(),
 				Token::Colon => ":".to_string(),
+				Token::DotDot => "..".to_string(),
 				Token::Semi => ";".to_string(),
 				Token::LParen => "(".to_string(),
 				Token::RParen => ")".to_string(),
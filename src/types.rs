@@ -1,11 +1,19 @@
-use crate::expr::Expression;
+use crate::{
+	expr::Expression,
+	matrix::{Matrix, SparseMatrix},
+	polynomial::Polynomial,
+};
 use rust_decimal::Decimal;
 use std::fmt::Display;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Data {
 	Number(Decimal, Decimal),
-	Matrix(Vec<Vec<Data>>),
+	Measured(Decimal, Decimal),
+	Matrix(Matrix),
+	Sparse(SparseMatrix),
+	Polynomial(Polynomial),
+	List(Vec<Data>),
 	Ident(String),
 	Expression(Expression),
 }
@@ -15,12 +23,38 @@ impl Data {
 	pub fn ty(&self) -> DataType {
 		match self {
 			Data::Number(..) => DataType::Number,
+			Data::Measured(..) => DataType::Measured,
 			Data::Matrix(..) => DataType::Matrix,
+			Data::Sparse(..) => DataType::Sparse,
+			Data::Polynomial(..) => DataType::Polynomial,
+			Data::List(..) => DataType::List,
 			Data::Ident(..) => DataType::Ident,
 			Data::Expression(..) => DataType::Expression,
 		}
 	}
 
+	/// Number of rows and columns, lists are treated as a single row and every
+	/// other value as a single element.
+	#[inline(always)]
+	pub fn shape(&self) -> (usize, usize) {
+		match self {
+			Data::Matrix(matrix) => matrix.shape(),
+			Data::Sparse(matrix) => matrix.shape(),
+			Data::List(list) => (1, list.len()),
+			_ => (1, 1),
+		}
+	}
+
+	/// Whether the value is a name without a value, an expression or a
+	/// polynomial, which all stand for numbers in a larger expression
+	#[inline(always)]
+	pub fn is_symbolic(&self) -> bool {
+		matches!(
+			self,
+			Data::Ident(..) | Data::Expression(..) | Data::Polynomial(..)
+		)
+	}
+
 	#[inline(always)]
 	pub fn to_real(&self) -> Decimal {
 		match &self {
@@ -47,6 +81,11 @@ impl Data {
 		Self::Number(Decimal::ZERO, b)
 	}
 
+	#[inline(always)]
+	pub fn new_measured(value: Decimal, sigma: Decimal) -> Self {
+		Self::Measured(value, sigma.abs())
+	}
+
 	#[inline(always)]
 	pub fn new_zero() -> Self {
 		Self::Number(Decimal::ZERO, Decimal::ZERO)
@@ -68,13 +107,20 @@ impl Display for Data {
 						format!("{a} + {b}i")
 					}
 				}
+				Data::Measured(value, sigma) => format!("{value} ± {sigma}"),
+				Data::Matrix(matrix) if matrix.is_empty() => "[]".to_string(),
 				Data::Matrix(matrix) => {
+					let matrix = matrix
+						.iter_rows()
+						.map(|row| row.iter().map(|&z| Data::from(z)).collect())
+						.collect::<Vec<Vec<Data>>>();
+
 					let mut highest_padding_required = 0;
 					let mut whitespace_index_map = vec![];
 
 					for i in 0..matrix[0].len() {
 						let mut max_len = 0;
-						for row in matrix {
+						for row in &matrix {
 							if row[i].to_string().len() > max_len {
 								max_len = row[i].to_string().len();
 							}
@@ -114,6 +160,29 @@ impl Display for Data {
 						" ".repeat(highest_padding_required),
 					)
 				}
+				Data::Sparse(matrix) => {
+					let (rows, cols) = matrix.shape();
+
+					let entries = matrix
+						.entries()
+						.map(|(i, j, z)| format!("\n({}, {})  {}", i + 1, j + 1, Data::from(z)))
+						.collect::<String>();
+
+					format!(
+						"{rows}x{cols} sparse matrix with {} non-zero element{}{entries}",
+						matrix.nnz(),
+						if matrix.nnz() == 1 { "" } else { "s" }
+					)
+				}
+				Data::Polynomial(polynomial) => polynomial.expression().to_string(),
+				Data::List(list) => format!(
+					"{{{}}}",
+					list
+						.iter()
+						.map(|element| element.to_string())
+						.collect::<Vec<String>>()
+						.join(", ")
+				),
 				Data::Ident(str) => str.to_owned(),
 				Data::Expression(expr) => expr.to_string(),
 			}
@@ -124,20 +193,28 @@ impl Display for Data {
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Ord, Eq)]
 pub enum DataType {
 	Number,
+	Measured,
 	Matrix,
+	Sparse,
+	Polynomial,
+	List,
 	Ident,
 	Expression,
 }
 
 impl DataType {
 	#[inline(always)]
-	pub fn parse(ident: &str) -> Self {
-		match ident.to_uppercase().as_str() {
+	pub fn parse(ident: &str) -> Option<Self> {
+		Some(match ident.to_uppercase().as_str() {
 			"C" | "COMPLEX" => Self::Number,
+			"U" | "MEASURED" => Self::Measured,
 			"M" | "MATRIX" => Self::Matrix,
+			"S" | "SPARSE" => Self::Sparse,
+			"P" | "POLYNOMIAL" => Self::Polynomial,
+			"L" | "LIST" => Self::List,
 			"FN" => Self::Ident,
-			_ => unimplemented!(),
-		}
+			_ => return None,
+		})
 	}
 }
 
@@ -148,7 +225,11 @@ impl Display for DataType {
 			"{}",
 			match self {
 				DataType::Number => "C",
+				DataType::Measured => "U",
 				DataType::Matrix => "Matrix",
+				DataType::Sparse => "Sparse",
+				DataType::Polynomial => "Poly",
+				DataType::List => "List",
 				DataType::Ident => "Fn",
 				DataType::Expression => "Expr",
 			}